@@ -0,0 +1,228 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Balance of a BIP44 account, with discovery of its addresses up to a gap limit.
+
+use std::collections::HashSet;
+
+use iota_types::block::{
+    address::{Address, AliasAddress},
+    output::{AliasId, FoundryId, NftId, Output, OutputId, Rent, RentStructure},
+};
+
+use super::NativeTokenBalance;
+use crate::{node_api::indexer::query_parameters::QueryParameter, secret::SecretManager, Client, Result};
+
+/// The base coin balance of an account. The total amount is the sum of all other amounts.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BaseCoinBalance {
+    /// The amount held by the outputs of the account.
+    pub total: u64,
+    /// The amount that can be spent now.
+    pub available: u64,
+    /// The amount held by outputs that are time-locked.
+    pub timelocked: u64,
+    /// The amount held by outputs that can be claimed now but return to their sender once they expire.
+    pub pending_expiration: u64,
+    /// The amount that can't be spent without giving up the outputs holding it: the storage deposit of alias,
+    /// foundry and NFT outputs and of basic outputs with native tokens, or the amount to return to the sender of an
+    /// output with a storage deposit return unlock condition.
+    pub required_storage_deposit: u64,
+}
+
+impl BaseCoinBalance {
+    // Adds the amount of an output the account can unlock, now or once it is no longer time-locked.
+    fn add_output(&mut self, output: &Output, rent_structure: &RentStructure, current_time: u32) {
+        let amount = output.amount();
+
+        self.total += amount;
+
+        if let Some(unlock_conditions) = output.unlock_conditions() {
+            if unlock_conditions.is_time_locked(current_time) {
+                self.timelocked += amount;
+                return;
+            }
+            if unlock_conditions.expiration().is_some() && !unlock_conditions.is_expired(current_time) {
+                self.pending_expiration += amount;
+                return;
+            }
+        }
+
+        // Once expired, the output belongs to the return address without any further condition.
+        let return_amount = output
+            .unlock_conditions()
+            .filter(|unlock_conditions| !unlock_conditions.is_expired(current_time))
+            .and_then(|unlock_conditions| unlock_conditions.storage_deposit_return())
+            .map_or(0, |storage_deposit_return| storage_deposit_return.amount());
+        let storage_deposit = match output {
+            Output::Basic(basic) if basic.native_tokens().is_empty() => 0,
+            _ => output.rent_cost(rent_structure),
+        };
+        let required_storage_deposit = return_amount.max(storage_deposit).min(amount);
+
+        self.required_storage_deposit += required_storage_deposit;
+        self.available += amount - required_storage_deposit;
+    }
+}
+
+/// The balance of a BIP44 account.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AccountBalance {
+    /// The public and internal addresses of the account that hold outputs or are the return address of some.
+    pub addresses: Vec<Address>,
+    /// The base coin balance.
+    pub base_coin: BaseCoinBalance,
+    /// The native token balances, ordered by token ID.
+    pub native_tokens: Vec<NativeTokenBalance>,
+    /// The IDs of the NFTs the account can unlock.
+    pub nft_ids: Vec<NftId>,
+    /// The IDs of the aliases the account is the state controller or the governor of.
+    pub alias_ids: Vec<AliasId>,
+    /// The IDs of the foundries controlled by the aliases the account is the state controller of.
+    pub foundry_ids: Vec<FoundryId>,
+}
+
+impl Client {
+    /// Returns the balance of a BIP44 account. Its public and internal addresses are looked up by increasing index
+    /// until `gap_limit` consecutive indexes, at least one, have no outputs.
+    ///
+    /// The base coin and native token balances count basic and NFT outputs the addresses can unlock, and alias
+    /// outputs they are the state controller of along with the foundries of these aliases. An alias the account is
+    /// only the governor of is listed without counting its amount.
+    pub async fn account_balance(
+        &self,
+        secret_manager: &SecretManager,
+        coin_type: u32,
+        account_index: u32,
+        gap_limit: u32,
+    ) -> Result<AccountBalance> {
+        let gap_limit = gap_limit.max(1);
+        let bech32_hrp = self.get_bech32_hrp().await?;
+        let mut balance = AccountBalance::default();
+        let mut output_ids = HashSet::new();
+        let mut address_index = 0;
+        let mut unused_indexes = 0;
+
+        'discovery: loop {
+            let addresses = self
+                .get_addresses(secret_manager)
+                .with_coin_type(coin_type)
+                .with_account_index(account_index)
+                .with_range(address_index..address_index + gap_limit)
+                .get_all_raw()
+                .await?;
+
+            // An index is unused if neither its public nor its internal address has outputs.
+            for (public, internal) in addresses.public.into_iter().zip(addresses.internal) {
+                let mut used = false;
+
+                for address in [public, internal] {
+                    let address_output_ids = self.account_address_output_ids(&address.to_bech32(&bech32_hrp)).await?;
+
+                    if !address_output_ids.is_empty() {
+                        used = true;
+                        balance.addresses.push(address);
+                        output_ids.extend(address_output_ids);
+                    }
+                }
+
+                unused_indexes = if used { 0 } else { unused_indexes + 1 };
+
+                if unused_indexes == gap_limit {
+                    break 'discovery;
+                }
+            }
+
+            address_index += gap_limit;
+        }
+
+        let protocol_parameters = self.get_protocol_parameters().await?;
+        let current_time = self.get_time_checked().await?;
+        let mut foundry_output_ids = Vec::new();
+
+        for output_response in self.get_outputs(output_ids.into_iter().collect()).await? {
+            let output_id = output_response.metadata.output_id()?;
+            let output = Output::try_from_dto(&output_response.output, protocol_parameters.token_supply())?;
+
+            match &output {
+                Output::Alias(alias) => {
+                    let alias_id = alias.alias_id_non_null(&output_id);
+
+                    balance.alias_ids.push(alias_id);
+
+                    if !balance.addresses.contains(alias.state_controller_address()) {
+                        continue;
+                    }
+
+                    foundry_output_ids.extend(
+                        self.foundry_output_ids(vec![QueryParameter::AliasAddress(
+                            Address::Alias(AliasAddress::new(alias_id)).to_bech32(&bech32_hrp),
+                        )])
+                        .await?,
+                    );
+                }
+                _ => {
+                    let Some(address) = output.unlock_conditions().and_then(|unlock_conditions| {
+                        unlock_conditions
+                            .address()
+                            .map(|address| unlock_conditions.locked_address(address.address(), current_time))
+                    }) else {
+                        continue;
+                    };
+
+                    // The output was found through an address that can't unlock it, e.g. it isn't expired yet.
+                    if !balance.addresses.contains(address) {
+                        continue;
+                    }
+                    if let Output::Nft(nft) = &output {
+                        balance.nft_ids.push(nft.nft_id_non_null(&output_id));
+                    }
+                }
+            }
+
+            balance
+                .base_coin
+                .add_output(&output, protocol_parameters.rent_structure(), current_time);
+        }
+
+        for output_response in self.get_outputs(foundry_output_ids).await? {
+            let output = Output::try_from_dto(&output_response.output, protocol_parameters.token_supply())?;
+
+            if let Output::Foundry(foundry) = &output {
+                balance.foundry_ids.push(foundry.id());
+                balance
+                    .base_coin
+                    .add_output(&output, protocol_parameters.rent_structure(), current_time);
+            }
+        }
+
+        balance.alias_ids.sort();
+        balance.nft_ids.sort();
+        balance.foundry_ids.sort();
+        balance.native_tokens = self.native_token_balances(&balance.addresses).await?;
+
+        Ok(balance)
+    }
+
+    // Returns the IDs of the outputs an address holds, is the expiration return address of, or controls as state
+    // controller or governor.
+    async fn account_address_output_ids(&self, bech32_address: &str) -> Result<Vec<OutputId>> {
+        let mut output_ids = Vec::new();
+
+        for query in [
+            QueryParameter::Address(bech32_address.to_string()),
+            QueryParameter::ExpirationReturnAddress(bech32_address.to_string()),
+        ] {
+            output_ids.extend(self.basic_output_ids(vec![query.clone()]).await?);
+            output_ids.extend(self.nft_output_ids(vec![query]).await?);
+        }
+        for query in [
+            QueryParameter::StateController(bech32_address.to_string()),
+            QueryParameter::Governor(bech32_address.to_string()),
+        ] {
+            output_ids.extend(self.alias_output_ids(vec![query]).await?);
+        }
+
+        Ok(output_ids)
+    }
+}
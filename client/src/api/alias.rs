@@ -0,0 +1,186 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Governance of alias outputs: rotation of their state controller and governor, update of their state metadata, and
+//! destruction along with their foundries.
+
+use iota_types::block::{
+    address::{Address, AliasAddress},
+    output::{
+        feature::Feature,
+        unlock_condition::{GovernorAddressUnlockCondition, StateControllerAddressUnlockCondition, UnlockCondition},
+        AliasId, AliasOutput, AliasOutputBuilder, Output, TokenScheme,
+    },
+    Block,
+};
+
+use super::block_builder::{input_selection::Burn, verify_state_transitions};
+use crate::{node_api::indexer::query_parameters::QueryParameter, secret::SecretManager, Client, Result};
+
+impl Client {
+    /// Sets the state controller of an alias, with a governance transition unlocked by its governor.
+    pub async fn set_alias_state_controller(
+        &self,
+        secret_manager: &SecretManager,
+        alias_id: AliasId,
+        state_controller: Address,
+    ) -> Result<Block> {
+        self.send_alias_transition(secret_manager, alias_id, false, |builder| {
+            Ok(
+                builder.replace_unlock_condition(UnlockCondition::StateControllerAddress(
+                    StateControllerAddressUnlockCondition::new(state_controller),
+                ))?,
+            )
+        })
+        .await
+    }
+
+    /// Sets the governor of an alias, with a governance transition unlocked by its current governor.
+    pub async fn set_alias_governor(
+        &self,
+        secret_manager: &SecretManager,
+        alias_id: AliasId,
+        governor: Address,
+    ) -> Result<Block> {
+        self.send_alias_transition(secret_manager, alias_id, false, |builder| {
+            Ok(builder.replace_unlock_condition(UnlockCondition::GovernorAddress(
+                GovernorAddressUnlockCondition::new(governor),
+            ))?)
+        })
+        .await
+    }
+
+    /// Sets the state metadata of an alias, with a state transition unlocked by its state controller.
+    pub async fn set_alias_state_metadata(
+        &self,
+        secret_manager: &SecretManager,
+        alias_id: AliasId,
+        state_metadata: Vec<u8>,
+    ) -> Result<Block> {
+        self.send_alias_transition(secret_manager, alias_id, true, |builder| {
+            Ok(builder.with_state_metadata(state_metadata))
+        })
+        .await
+    }
+
+    /// Destroys an alias along with its foundries, returning the sent blocks.
+    ///
+    /// The foundries are first destroyed with a state transition of the alias, which burns the circulating supplies
+    /// of their tokens, so these have to be held by the addresses of the secret manager. The alias is then destroyed
+    /// by its governor, once the first transaction is included, and its amount and native tokens go to a remainder.
+    pub async fn destroy_alias(&self, secret_manager: &SecretManager, alias_id: AliasId) -> Result<Vec<Block>> {
+        let alias = self.alias_output(alias_id).await?;
+        let bech32_hrp = self.get_bech32_hrp().await?;
+        let foundry_output_ids = self
+            .foundry_output_ids(vec![QueryParameter::AliasAddress(
+                Address::Alias(AliasAddress::new(alias_id)).to_bech32(&bech32_hrp),
+            )])
+            .await?;
+        let token_supply = self.get_token_supply().await?;
+        let mut blocks = Vec::new();
+
+        if !foundry_output_ids.is_empty() {
+            let mut burn = Burn::new();
+
+            for output_response in self.get_outputs(foundry_output_ids).await? {
+                if let Output::Foundry(foundry) = Output::try_from_dto(&output_response.output, token_supply)? {
+                    let TokenScheme::Simple(token_scheme) = foundry.token_scheme();
+
+                    burn = burn.add_foundry(foundry.id());
+
+                    if !token_scheme.circulating_supply().is_zero() {
+                        burn = burn.add_native_token(foundry.token_id(), token_scheme.circulating_supply());
+                    }
+                }
+            }
+
+            let next_state = next_alias_state(&alias, alias_id, true).finish_output(token_supply)?;
+
+            blocks.push(
+                self.send_verified_transaction(secret_manager, vec![next_state], Some(burn))
+                    .await?,
+            );
+            self.retry_until_included(&blocks[0].id(), None, None).await?;
+        }
+
+        blocks.push(
+            self.send_verified_transaction(secret_manager, Vec::new(), Some(Burn::new().add_alias(alias_id)))
+                .await?,
+        );
+
+        Ok(blocks)
+    }
+
+    async fn send_alias_transition(
+        &self,
+        secret_manager: &SecretManager,
+        alias_id: AliasId,
+        state_transition: bool,
+        transition: impl FnOnce(AliasOutputBuilder) -> Result<AliasOutputBuilder>,
+    ) -> Result<Block> {
+        let protocol_parameters = self.get_protocol_parameters().await?;
+        let alias = self.alias_output(alias_id).await?;
+        let builder = transition(next_alias_state(&alias, alias_id, state_transition))?;
+        // A governance transition can't change the amount.
+        let amount = if state_transition {
+            let minimum_amount = builder
+                .clone()
+                .with_minimum_storage_deposit(protocol_parameters.rent_structure().clone())
+                .finish_output(protocol_parameters.token_supply())?
+                .amount();
+
+            alias.amount().max(minimum_amount)
+        } else {
+            alias.amount()
+        };
+        let next_state = builder
+            .with_amount(amount)?
+            .finish_output(protocol_parameters.token_supply())?;
+
+        self.send_verified_transaction(secret_manager, vec![next_state], None)
+            .await
+    }
+
+    // Sends a transaction once its chain state transitions are verified.
+    async fn send_verified_transaction(
+        &self,
+        secret_manager: &SecretManager,
+        outputs: Vec<Output>,
+        burn: Option<Burn>,
+    ) -> Result<Block> {
+        let mut builder = self.block().with_secret_manager(secret_manager);
+
+        if !outputs.is_empty() {
+            builder = builder.with_outputs(outputs)?;
+        }
+        if let Some(burn) = burn {
+            builder = builder.with_burn(burn);
+        }
+
+        let prepared_transaction_data = builder.prepare_transaction().await?;
+
+        verify_state_transitions(&prepared_transaction_data, self.get_time_checked().await?)?;
+
+        let payload = builder.sign_transaction(prepared_transaction_data).await?;
+
+        builder.finish_block(Some(payload)).await
+    }
+}
+
+// Returns a builder of the next state of an alias. A state transition increments the state index, while a governance
+// transition keeps it.
+fn next_alias_state(alias: &AliasOutput, alias_id: AliasId, state_transition: bool) -> AliasOutputBuilder {
+    let state_index = alias.state_index() + u32::from(state_transition);
+
+    // The sender of a previous transition doesn't unlock an input of this transaction.
+    AliasOutputBuilder::from(alias)
+        .with_alias_id(alias_id)
+        .with_state_index(state_index)
+        .with_features(
+            alias
+                .features()
+                .iter()
+                .filter(|feature| !matches!(feature, Feature::Sender(_)))
+                .cloned(),
+        )
+}
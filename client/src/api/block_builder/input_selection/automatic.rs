@@ -73,13 +73,21 @@ impl<'a> ClientBlockBuilder<'a> {
 
         // First get inputs for utxo chains (Alias, Foundry, NFT outputs).
         let mut available_inputs = self.get_utxo_chains_inputs(self.outputs.iter()).await?;
-        let required_inputs_for_sender_or_issuer = self
-            .get_inputs_for_sender_and_issuer(&available_inputs)
-            .await?
+        let inputs_for_sender_or_issuer = self.get_inputs_for_sender_and_issuer(&available_inputs).await?;
+        let required_inputs_for_sender_or_issuer = inputs_for_sender_or_issuer
             .iter()
             .map(|input| *input.output_id())
             .collect::<HashSet<_>>();
 
+        for input in inputs_for_sender_or_issuer {
+            if !available_inputs
+                .iter()
+                .any(|available_input| available_input.output_id() == input.output_id())
+            {
+                available_inputs.push(input);
+            }
+        }
+
         let current_time = self.client.get_time_checked().await?;
 
         // Try to select inputs with required inputs for utxo chains alone before requesting more inputs from addresses.
@@ -88,11 +96,16 @@ impl<'a> ClientBlockBuilder<'a> {
             self.outputs.clone(),
             protocol_parameters.clone(),
         )
-        .required_inputs(required_inputs_for_sender_or_issuer.clone());
+        .required_inputs(required_inputs_for_sender_or_issuer.clone())
+        .forbidden_inputs(self.forbidden_inputs.clone())
+        .strategy(self.input_selection_strategy.clone());
 
         if let Some(address) = self.custom_remainder_address {
             input_selection = input_selection.remainder_address(address);
         }
+        if let Some(burn) = &self.burn {
+            input_selection = input_selection.burn(burn.clone());
+        }
 
         if let Ok(selected_transaction_data) = input_selection.select() {
             return Ok(selected_transaction_data);
@@ -172,11 +185,16 @@ impl<'a> ClientBlockBuilder<'a> {
                         self.outputs.clone(),
                         protocol_parameters.clone(),
                     )
-                    .required_inputs(required_inputs_for_sender_or_issuer.clone());
+                    .required_inputs(required_inputs_for_sender_or_issuer.clone())
+                    .forbidden_inputs(self.forbidden_inputs.clone())
+                    .strategy(self.input_selection_strategy.clone());
 
                     if let Some(address) = self.custom_remainder_address {
                         input_selection = input_selection.remainder_address(address);
                     }
+                    if let Some(burn) = &self.burn {
+                        input_selection = input_selection.burn(burn.clone());
+                    }
 
                     let selected_transaction_data = match input_selection.select() {
                         Ok(r) => r,
@@ -186,7 +204,10 @@ impl<'a> ClientBlockBuilder<'a> {
                             cached_error.replace(err);
                             continue;
                         }
-                        Err(err @ crate::Error::NotEnoughNativeTokens { .. }) => {
+                        Err(
+                            err @ (crate::Error::NotEnoughNativeTokens { .. }
+                            | crate::Error::InsufficientNativeTokenAmount { .. }),
+                        ) => {
                             cached_error.replace(err);
                             continue;
                         }
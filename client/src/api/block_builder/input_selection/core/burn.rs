@@ -11,7 +11,7 @@ use crate::block::output::{AliasId, FoundryId, NftId, TokenId};
 
 /// A type to specify what needs to be burned during input selection.
 /// Nothing will be burned that has not been explicitly set with this struct.
-#[derive(Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Burn {
     /// Aliases to burn.
@@ -4,16 +4,18 @@
 pub(crate) mod burn;
 pub(crate) mod remainder;
 pub(crate) mod requirement;
+pub(crate) mod strategy;
 pub(crate) mod transition;
 
-use std::collections::HashSet;
+use std::{collections::HashSet, sync::Arc};
 
 pub use burn::Burn;
 pub use requirement::Requirement;
 use requirement::{alias::is_alias_state_transition, Requirements};
+pub use strategy::{BuiltinStrategy, InputSelectionStrategy, SelectionCandidate};
 
 use crate::{
-    api::types::RemainderData,
+    api::{block_builder::input_selection::helpers::sort_input_signing_data, types::RemainderData},
     block::{
         address::{Address, AliasAddress, NftAddress},
         output::{Output, OutputId},
@@ -44,6 +46,7 @@ pub struct InputSelection {
     remainder_address: Option<Address>,
     protocol_parameters: ProtocolParameters,
     timestamp: u32,
+    strategy: Arc<dyn InputSelectionStrategy>,
 }
 
 /// Result of the input selection algorithm.
@@ -185,6 +188,7 @@ impl InputSelection {
                 .duration_since(instant::SystemTime::UNIX_EPOCH)
                 .expect("time went backwards")
                 .as_secs() as u32,
+            strategy: Arc::new(BuiltinStrategy::default()),
         }
     }
 
@@ -218,6 +222,12 @@ impl InputSelection {
         self
     }
 
+    /// Sets the strategy of an [`InputSelection`], ordering the inputs selected for amounts and native tokens.
+    pub fn strategy(mut self, strategy: Arc<dyn InputSelectionStrategy>) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
     // TODO should we somehow enforce using filter so we don't have to use can_be_unlocked_now later everywhere ?
     /// Filters out the available inputs that
     /// - can't be unlocked by the given addresses
@@ -286,7 +296,7 @@ impl InputSelection {
         self.outputs.extend(storage_deposit_returns);
 
         Ok(Selected {
-            inputs: self.selected_inputs,
+            inputs: sort_input_signing_data(self.selected_inputs)?,
             outputs: self.outputs.into_iter().map(|output| output.output).collect(),
             remainder,
         })
@@ -5,6 +5,7 @@ use std::collections::{HashMap, HashSet};
 
 use super::{InputSelection, OutputInfo, Requirement};
 use crate::{
+    api::block_builder::input_selection::core::strategy::order_inputs,
     block::{
         address::Address,
         output::{
@@ -93,21 +94,22 @@ impl InputSelection {
 
         // TODO don't pick burned things
 
-        // TODO if consolidate strategy: sum all the lowest amount until diff is covered.
-        // TODO this would be lowest amount of input strategy.
-        self.available_inputs
-            .sort_by(|left, right| left.output.amount().cmp(&right.output.amount()));
-
         'overall: {
             // 1. Basic with ED25519 address without SDRUC
             {
-                let inputs = self.available_inputs.iter().filter(|input| {
-                    if let Output::Basic(output) = &input.output {
-                        output.address().is_ed25519() && output.unlock_conditions().storage_deposit_return().is_none()
-                    } else {
-                        false
-                    }
-                });
+                let inputs = order_inputs(
+                    self.strategy.as_ref(),
+                    self.available_inputs.iter().filter(|input| {
+                        if let Output::Basic(output) = &input.output {
+                            output.address().is_ed25519()
+                                && output.unlock_conditions().storage_deposit_return().is_none()
+                        } else {
+                            false
+                        }
+                    }),
+                    |input| input.output.amount().into(),
+                    missing_amount(inputs_sum, outputs_sum, remainder_amount, native_tokens_remainder).into(),
+                );
 
                 for input in inputs {
                     inputs_sum += input.output.amount();
@@ -122,23 +124,37 @@ impl InputSelection {
 
             // 2. Basic with ED25519 address and SDRUC
             {
-                let inputs = self.available_inputs.iter().filter(|input| {
-                    if let Output::Basic(output) = &input.output {
-                        if output.address().is_ed25519() {
-                            if let Some(sdr) = output.unlock_conditions().storage_deposit_return() {
-                                // Filter out outputs that have to send back their full amount as they contribute to
-                                // nothing.
-                                sdr.amount() != input.output.amount()
+                let inputs = order_inputs(
+                    self.strategy.as_ref(),
+                    self.available_inputs.iter().filter(|input| {
+                        if let Output::Basic(output) = &input.output {
+                            if output.address().is_ed25519() {
+                                if let Some(sdr) = output.unlock_conditions().storage_deposit_return() {
+                                    // Filter out outputs that have to send back their full amount as they contribute
+                                    // to nothing.
+                                    sdr.amount() != input.output.amount()
+                                } else {
+                                    false
+                                }
                             } else {
                                 false
                             }
                         } else {
                             false
                         }
-                    } else {
-                        false
-                    }
-                });
+                    }),
+                    // The amount that doesn't have to be sent back.
+                    |input| {
+                        let sdr_amount = input
+                            .output
+                            .unlock_conditions()
+                            .and_then(UnlockConditions::storage_deposit_return)
+                            .map_or(0, |sdr| sdr.amount());
+
+                        input.output.amount().saturating_sub(sdr_amount).into()
+                    },
+                    missing_amount(inputs_sum, outputs_sum, remainder_amount, native_tokens_remainder).into(),
+                );
 
                 for input in inputs {
                     let sdruc = input
@@ -171,17 +187,22 @@ impl InputSelection {
 
             // 3. Basic with other kind of address
             {
-                let inputs = self.available_inputs.iter().filter(|input| {
-                    if let Output::Basic(output) = &input.output {
-                        if let [UnlockCondition::Address(address)] = output.unlock_conditions().as_ref() {
-                            !address.address().is_ed25519()
+                let inputs = order_inputs(
+                    self.strategy.as_ref(),
+                    self.available_inputs.iter().filter(|input| {
+                        if let Output::Basic(output) = &input.output {
+                            if let [UnlockCondition::Address(address)] = output.unlock_conditions().as_ref() {
+                                !address.address().is_ed25519()
+                            } else {
+                                false
+                            }
                         } else {
                             false
                         }
-                    } else {
-                        false
-                    }
-                });
+                    }),
+                    |input| input.output.amount().into(),
+                    missing_amount(inputs_sum, outputs_sum, remainder_amount, native_tokens_remainder).into(),
+                );
 
                 for input in inputs {
                     inputs_sum += input.output.amount();
@@ -196,13 +217,14 @@ impl InputSelection {
 
             // 4. Other kinds of outputs
             {
-                let mut inputs = self
-                    .available_inputs
-                    .iter()
-                    .filter(|input| !input.output.is_basic())
-                    .peekable();
-
-                if inputs.peek().is_some() {
+                let inputs = order_inputs(
+                    self.strategy.as_ref(),
+                    self.available_inputs.iter().filter(|input| !input.output.is_basic()),
+                    |input| input.output.amount().into(),
+                    missing_amount(inputs_sum, outputs_sum, remainder_amount, native_tokens_remainder).into(),
+                );
+
+                if !inputs.is_empty() {
                     for input in inputs {
                         inputs_sum += input.output.amount();
                         newly_selected_inputs.push(input.clone());
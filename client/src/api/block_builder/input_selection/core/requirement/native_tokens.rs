@@ -7,6 +7,7 @@ use primitive_types::U256;
 
 use super::{InputSelection, OutputInfo, Requirement};
 use crate::{
+    api::block_builder::input_selection::core::strategy::order_inputs,
     block::output::{NativeToken, NativeTokens, NativeTokensBuilder, Output, TokenScheme},
     error::Result,
     secret::types::InputSigningData,
@@ -134,22 +135,27 @@ impl InputSelection {
         if let Some(diffs) = get_native_tokens_diff(&output_native_tokens, &input_native_tokens)? {
             for diff in diffs.iter() {
                 let mut amount = U256::zero();
-                // TODO sort ?
-                let inputs = self.available_inputs.iter().filter(|input| {
+                let native_token_amount = |input: &InputSigningData| {
                     input
                         .output
                         .native_tokens()
-                        .map_or(false, |native_tokens| native_tokens.contains(diff.token_id()))
-                });
+                        .and_then(|native_tokens| native_tokens.get(diff.token_id()))
+                        .map_or(U256::zero(), NativeToken::amount)
+                };
+                let inputs = order_inputs(
+                    self.strategy.as_ref(),
+                    self.available_inputs.iter().filter(|input| {
+                        input
+                            .output
+                            .native_tokens()
+                            .is_some_and(|native_tokens| native_tokens.contains(diff.token_id()))
+                    }),
+                    native_token_amount,
+                    diff.amount(),
+                );
 
                 for input in inputs {
-                    amount += input
-                        .output
-                        .native_tokens()
-                        .and_then(|native_tokens| native_tokens.get(diff.token_id()))
-                        // PANIC: safe to unwrap as the filter guarantees inputs containing this native token.
-                        .unwrap()
-                        .amount();
+                    amount += native_token_amount(input);
 
                     if newly_selected_ids.insert(*input.output_id()) {
                         newly_selected_inputs.push(input.clone());
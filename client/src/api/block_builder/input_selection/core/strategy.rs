@@ -0,0 +1,161 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{cmp::Reverse, collections::HashSet, fmt::Debug};
+
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::{block::output::OutputId, secret::types::InputSigningData};
+
+// Bounds the search of an exact match, which is exponential in the number of candidates.
+const BRANCH_AND_BOUND_MAX_TRIES: usize = 100_000;
+
+/// An available input that can contribute to a requirement.
+#[derive(Clone, Copy, Debug)]
+pub struct SelectionCandidate<'a> {
+    /// The input.
+    pub input: &'a InputSigningData,
+    /// The value the input contributes to the requirement, its amount or its amount of a native token.
+    pub value: U256,
+}
+
+/// A strategy deciding in which order the available inputs are selected to fulfill the
+/// [`Requirement::Amount`](super::Requirement::Amount) and
+/// [`Requirement::NativeTokens`](super::Requirement::NativeTokens) requirements.
+pub trait InputSelectionStrategy: Debug + Send + Sync {
+    /// Orders the candidates of a requirement, the first ones being selected first until the `target` value, still
+    /// missing to fulfill the requirement, is reached.
+    fn order(&self, candidates: &mut [SelectionCandidate<'_>], target: U256);
+}
+
+/// The input selection strategies provided by the library.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BuiltinStrategy {
+    /// Selects the inputs with the smallest value first, consolidating small outputs.
+    #[default]
+    SmallestFirst,
+    /// Selects the inputs with the largest value first, using as few inputs as possible.
+    LargestFirst,
+    /// Selects the input with the smallest value that reaches the target on its own if there is one, and otherwise the
+    /// inputs with the largest value first, keeping the remainder small.
+    MinimizeRemainder,
+    /// Selects the inputs that were booked the earliest first.
+    OldestFirst,
+    /// Searches for inputs whose values exactly sum to the target, so that no remainder is needed, and otherwise
+    /// behaves like [`BuiltinStrategy::MinimizeRemainder`].
+    BranchAndBound,
+}
+
+impl InputSelectionStrategy for BuiltinStrategy {
+    fn order(&self, candidates: &mut [SelectionCandidate<'_>], target: U256) {
+        match self {
+            Self::SmallestFirst => candidates.sort_by_key(|candidate| candidate.value),
+            Self::LargestFirst => candidates.sort_by_key(|candidate| Reverse(candidate.value)),
+            Self::MinimizeRemainder => minimize_remainder(candidates, target),
+            Self::OldestFirst => candidates.sort_by_key(|candidate| {
+                (
+                    candidate.input.output_metadata.milestone_index_booked(),
+                    *candidate.input.output_id(),
+                )
+            }),
+            Self::BranchAndBound => match exact_match(candidates, target) {
+                Some(exact_match) => {
+                    candidates.sort_by_key(|candidate| Reverse(candidate.value));
+                    // Stable, so the exact match stays ordered by descending value.
+                    candidates.sort_by_key(|candidate| !exact_match.contains(candidate.input.output_id()));
+                }
+                None => minimize_remainder(candidates, target),
+            },
+        }
+    }
+}
+
+fn minimize_remainder(candidates: &mut [SelectionCandidate<'_>], target: U256) {
+    candidates.sort_by_key(|candidate| Reverse(candidate.value));
+
+    // The candidates being sorted by descending value, the last one reaching the target is the smallest one.
+    if let Some(index) = candidates.iter().rposition(|candidate| candidate.value >= target) {
+        candidates[..=index].rotate_right(1);
+    }
+}
+
+/// Searches, depth-first, for candidates whose values exactly sum to the target.
+fn exact_match(candidates: &[SelectionCandidate<'_>], target: U256) -> Option<HashSet<OutputId>> {
+    if target.is_zero() {
+        return None;
+    }
+
+    let mut sorted = candidates.to_vec();
+    sorted.sort_by_key(|candidate| Reverse(candidate.value));
+
+    // The sum of the values of the candidates from every index on, to prune branches that can't reach the target.
+    let mut remaining = vec![U256::zero(); sorted.len() + 1];
+    for index in (0..sorted.len()).rev() {
+        remaining[index] = remaining[index + 1].saturating_add(sorted[index].value);
+    }
+
+    search(&sorted, &remaining, target).map(|selected| {
+        selected
+            .into_iter()
+            .map(|index| *sorted[index].input.output_id())
+            .collect()
+    })
+}
+
+// Explores the candidates depth-first, including each one before excluding it, and returns the indexes of the first
+// exact match. The stack is explicit since the search can be as deep as the number of candidates.
+fn search(sorted: &[SelectionCandidate<'_>], remaining: &[U256], target: U256) -> Option<Vec<usize>> {
+    let mut selected = Vec::new();
+    // The branches left to explore: the index of the next candidate, the sum of the selected candidates and their
+    // number.
+    let mut branches = vec![(0, U256::zero(), 0)];
+    let mut tries = BRANCH_AND_BOUND_MAX_TRIES;
+
+    while let Some((index, sum, selected_count)) = branches.pop() {
+        selected.truncate(selected_count);
+
+        if sum == target {
+            return Some(selected);
+        }
+        if tries == 0 {
+            return None;
+        }
+        if index == sorted.len() || sum.saturating_add(remaining[index]) < target {
+            continue;
+        }
+
+        tries -= 1;
+
+        let value = sorted[index].value;
+
+        branches.push((index + 1, sum, selected_count));
+
+        if !value.is_zero() && sum.saturating_add(value) <= target {
+            selected.push(index);
+            branches.push((index + 1, sum + value, selected_count + 1));
+        }
+    }
+
+    None
+}
+
+/// Orders inputs with a strategy, given the value they contribute to a requirement.
+pub(crate) fn order_inputs<'a>(
+    strategy: &dyn InputSelectionStrategy,
+    inputs: impl Iterator<Item = &'a InputSigningData>,
+    value: impl Fn(&InputSigningData) -> U256,
+    target: U256,
+) -> Vec<&'a InputSigningData> {
+    let mut candidates = inputs
+        .map(|input| SelectionCandidate {
+            input,
+            value: value(input),
+        })
+        .collect::<Vec<_>>();
+
+    strategy.order(&mut candidates, target);
+
+    candidates.into_iter().map(|candidate| candidate.input).collect()
+}
@@ -35,17 +35,25 @@ use crate::{secret::types::InputSigningData, Result};
 // }
 
 // Inputs need to be sorted before signing, because the reference unlock conditions can only reference a lower index
-pub(crate) fn _sort_input_signing_data(inputs: Vec<InputSigningData>) -> crate::Result<Vec<InputSigningData>> {
-    // filter for ed25519 address first, safe to unwrap since we encoded it before
-    let (mut sorted_inputs, alias_nft_address_inputs): (Vec<InputSigningData>, Vec<InputSigningData>) = inputs
-        .into_iter()
-        // PANIC: safe to unwrap as we encoded the address before
-        .partition(|input| Address::try_from_bech32(&input.bech32_address).unwrap().1.kind() == Ed25519Address::KIND);
+pub(crate) fn sort_input_signing_data(inputs: Vec<InputSigningData>) -> crate::Result<Vec<InputSigningData>> {
+    // filter for ed25519 address first, the inputs are kept along with their address
+    let mut sorted_inputs = Vec::new();
+    let mut alias_nft_address_inputs = Vec::new();
 
-    for input in alias_nft_address_inputs {
-        let input_address = Address::try_from_bech32(&input.bech32_address);
-        match sorted_inputs.iter().position(|input_signing_data| match input_address {
-            Ok((_, unlock_address)) => match unlock_address {
+    for input in inputs {
+        let (_, input_address) = Address::try_from_bech32(&input.bech32_address)?;
+
+        if input_address.kind() == Ed25519Address::KIND {
+            sorted_inputs.push((input, input_address));
+        } else {
+            alias_nft_address_inputs.push((input, input_address));
+        }
+    }
+
+    for (input, input_address) in alias_nft_address_inputs {
+        match sorted_inputs
+            .iter()
+            .position(|(input_signing_data, _)| match input_address {
                 Address::Alias(unlock_address) => {
                     if let Output::Alias(alias_output) = &input_signing_data.output {
                         *unlock_address.alias_id() == alias_output.alias_id_non_null(input_signing_data.output_id())
@@ -61,12 +69,10 @@ pub(crate) fn _sort_input_signing_data(inputs: Vec<InputSigningData>) -> crate::
                     }
                 }
                 _ => false,
-            },
-            _ => false,
-        }) {
+            }) {
             Some(position) => {
                 // Insert after the output we need
-                sorted_inputs.insert(position + 1, input);
+                sorted_inputs.insert(position + 1, (input, input_address));
             }
             None => {
                 // insert before address
@@ -82,28 +88,26 @@ pub(crate) fn _sort_input_signing_data(inputs: Vec<InputSigningData>) -> crate::
 
                 if let Some(alias_or_nft_address) = alias_or_nft_address {
                     // Check for existing outputs for this address, and insert before
-                    match sorted_inputs.iter().position(|input_signing_data| {
-                        Address::try_from_bech32(&input_signing_data.bech32_address)
-                            .expect("safe to unwrap, we encoded it before")
-                            .1
-                            == alias_or_nft_address
-                    }) {
+                    match sorted_inputs
+                        .iter()
+                        .position(|(_, address)| *address == alias_or_nft_address)
+                    {
                         Some(position) => {
                             // Insert before the output with this address required for unlocking
-                            sorted_inputs.insert(position, input);
+                            sorted_inputs.insert(position, (input, input_address));
                         }
                         // just push output
-                        None => sorted_inputs.push(input),
+                        None => sorted_inputs.push((input, input_address)),
                     }
                 } else {
                     // just push basic or foundry output
-                    sorted_inputs.push(input);
+                    sorted_inputs.push((input, input_address));
                 }
             }
         }
     }
 
-    Ok(sorted_inputs)
+    Ok(sorted_inputs.into_iter().map(|(input, _)| input).collect())
 }
 
 /// Computes the minimum storage deposit amount that a basic output needs to have with an [AddressUnlockCondition] and
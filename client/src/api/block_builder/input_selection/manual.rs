@@ -100,11 +100,16 @@ impl<'a> ClientBlockBuilder<'a> {
             .map(|input| *input.output_id())
             .collect::<HashSet<_>>();
         let mut input_selection = InputSelection::new(inputs_data, self.outputs.clone(), protocol_parameters.clone())
-            .required_inputs(required_inputs);
+            .required_inputs(required_inputs)
+            .forbidden_inputs(self.forbidden_inputs.clone())
+            .strategy(self.input_selection_strategy.clone());
 
         if let Some(address) = self.custom_remainder_address {
             input_selection = input_selection.remainder_address(address);
         }
+        if let Some(burn) = &self.burn {
+            input_selection = input_selection.burn(burn.clone());
+        }
 
         input_selection.select()
     }
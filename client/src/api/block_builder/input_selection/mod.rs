@@ -4,13 +4,13 @@
 //! Input selection for transactions
 
 mod automatic;
-mod core;
+pub(crate) mod core;
 mod helpers;
 mod manual;
 mod sender_issuer;
 mod utxo_chains;
 
 pub use self::{
-    core::{Burn, InputSelection, Requirement, Selected},
+    core::{BuiltinStrategy, Burn, InputSelection, InputSelectionStrategy, Requirement, Selected, SelectionCandidate},
     helpers::minimum_storage_deposit_basic_output,
 };
@@ -213,7 +213,16 @@ impl<'a> ClientBlockBuilder<'a> {
         let utxo_chain_inputs = self
             .get_utxo_chains_inputs(required_inputs.iter().map(|i| &i.output))
             .await?;
-        required_inputs.extend(utxo_chain_inputs.into_iter());
+
+        // The chains of the required inputs may be the required inputs themselves.
+        for input in utxo_chain_inputs {
+            if !required_inputs
+                .iter()
+                .any(|required_input| required_input.output_id() == input.output_id())
+            {
+                required_inputs.push(input);
+            }
+        }
 
         Ok(required_inputs)
     }
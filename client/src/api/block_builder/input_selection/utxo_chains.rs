@@ -131,14 +131,14 @@ impl<'a> ClientBlockBuilder<'a> {
                         let output_id = client.alias_output_id(*alias_output.alias_id()).await?;
                         let output_response = client.get_output(&output_id).await?;
                         if let OutputDto::Alias(alias_output_dto) = &output_response.output {
-                            let alias_output = AliasOutput::try_from_dto(alias_output_dto, token_supply)?;
+                            let input_alias_output = AliasOutput::try_from_dto(alias_output_dto, token_supply)?;
 
                             // A governance transition is identified by an unchanged State Index in next
                             // state.
-                            if alias_output.state_index() == alias_output.state_index() {
-                                utxo_chains.push((*alias_output.governor_address(), output_response));
+                            if alias_output.state_index() == input_alias_output.state_index() {
+                                utxo_chains.push((*input_alias_output.governor_address(), output_response));
                             } else {
-                                utxo_chains.push((*alias_output.state_controller_address(), output_response));
+                                utxo_chains.push((*input_alias_output.state_controller_address(), output_response));
                             }
                         }
                     }
@@ -173,6 +173,34 @@ impl<'a> ClientBlockBuilder<'a> {
             }
         }
 
+        // Chain outputs that are burned don't have a next state, an alias is then destroyed by its governor.
+        if let Some(burn) = &self.burn {
+            for alias_id in &burn.aliases {
+                let output_response = client.get_output(&client.alias_output_id(*alias_id).await?).await?;
+                if let OutputDto::Alias(alias_output_dto) = &output_response.output {
+                    let alias_output = AliasOutput::try_from_dto(alias_output_dto, token_supply)?;
+                    utxo_chains.push((*alias_output.governor_address(), output_response));
+                }
+            }
+            for nft_id in &burn.nfts {
+                let output_response = client.get_output(&client.nft_output_id(*nft_id).await?).await?;
+                if let OutputDto::Nft(nft_output_dto) = &output_response.output {
+                    let nft_output = NftOutput::try_from_dto(nft_output_dto, token_supply)?;
+                    let unlock_address = nft_output
+                        .unlock_conditions()
+                        .locked_address(nft_output.address(), current_time);
+                    utxo_chains.push((*unlock_address, output_response));
+                }
+            }
+            for foundry_id in &burn.foundries {
+                let output_response = client.get_output(&client.foundry_output_id(*foundry_id).await?).await?;
+                if let OutputDto::Foundry(foundry_output_dto) = &output_response.output {
+                    let foundry_output = FoundryOutput::try_from_dto(foundry_output_dto, token_supply)?;
+                    utxo_chains.push((Address::Alias(*foundry_output.alias_address()), output_response));
+                }
+            }
+        }
+
         // Get recursively owned alias or nft outputs
         get_alias_and_nft_outputs_recursively(self.client, &mut utxo_chains).await?;
 
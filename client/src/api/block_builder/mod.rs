@@ -4,8 +4,9 @@
 pub mod input_selection;
 pub mod pow;
 pub mod transaction;
+pub mod transaction_chain;
 
-use std::{collections::HashSet, ops::Range};
+use std::{collections::HashSet, ops::Range, sync::Arc};
 
 use iota_types::block::{
     address::{Address, Ed25519Address},
@@ -13,7 +14,7 @@ use iota_types::block::{
     output::{
         dto::OutputDto,
         unlock_condition::{AddressUnlockCondition, UnlockCondition},
-        AliasId, BasicOutputBuilder, Output, OUTPUT_COUNT_RANGE,
+        AliasId, BasicOutputBuilder, Output, OutputId, OUTPUT_COUNT_RANGE,
     },
     parent::Parents,
     payload::{Payload, TaggedDataPayload},
@@ -21,7 +22,8 @@ use iota_types::block::{
 };
 use packable::bounded::TryIntoBoundedU16Error;
 
-pub use self::transaction::verify_semantic;
+use self::input_selection::{BuiltinStrategy, Burn, InputSelectionStrategy};
+pub use self::transaction::{verify_semantic, verify_semantic_report, verify_state_transitions};
 use crate::{constants::SHIMMER_COIN_TYPE, secret::SecretManager, Client, Error, Result};
 
 /// Builder of the block API
@@ -33,13 +35,16 @@ pub struct ClientBlockBuilder<'a> {
     account_index: u32,
     initial_address_index: u32,
     inputs: Option<Vec<UtxoInput>>,
+    forbidden_inputs: HashSet<OutputId>,
     input_range: Range<u32>,
     outputs: Vec<Output>,
     custom_remainder_address: Option<Address>,
+    input_selection_strategy: Arc<dyn InputSelectionStrategy>,
     tag: Option<Vec<u8>>,
     data: Option<Vec<u8>>,
     parents: Option<Parents>,
     allow_burning: bool,
+    burn: Option<Burn>,
 }
 
 /// Block output address
@@ -75,6 +80,8 @@ pub struct ClientBlockBuilderOptions {
     pub outputs: Option<Vec<OutputDto>>,
     /// Custom remainder address
     pub custom_remainder_address: Option<String>,
+    /// Input selection strategy
+    pub input_selection_strategy: Option<BuiltinStrategy>,
     /// Hex encoded tag
     pub tag: Option<String>,
     /// Hex encoded data
@@ -95,13 +102,16 @@ impl<'a> ClientBlockBuilder<'a> {
             account_index: 0,
             initial_address_index: 0,
             inputs: None,
+            forbidden_inputs: HashSet::new(),
             input_range: 0..100,
             outputs: Vec::new(),
             custom_remainder_address: None,
+            input_selection_strategy: Arc::new(BuiltinStrategy::default()),
             tag: None,
             data: None,
             parents: None,
             allow_burning: false,
+            burn: None,
         }
     }
 
@@ -111,6 +121,12 @@ impl<'a> ClientBlockBuilder<'a> {
         self
     }
 
+    /// Sets what input selection burns, a transaction can then be sent without outputs.
+    pub fn with_burn(mut self, burn: Burn) -> Self {
+        self.burn.replace(burn);
+        self
+    }
+
     /// Sets the seed.
     pub fn with_secret_manager(mut self, manager: &'a SecretManager) -> Self {
         self.secret_manager.replace(manager);
@@ -151,6 +167,12 @@ impl<'a> ClientBlockBuilder<'a> {
         Ok(self)
     }
 
+    /// Set inputs that must not be selected, e.g. because they are already spent by a pending transaction.
+    pub fn with_forbidden_inputs(mut self, inputs: HashSet<OutputId>) -> Self {
+        self.forbidden_inputs = inputs;
+        self
+    }
+
     /// Set a custom range in which to search for addresses for custom provided inputs. Default: 0..100
     pub fn with_input_range(mut self, range: Range<u32>) -> Self {
         self.input_range = range;
@@ -207,6 +229,12 @@ impl<'a> ClientBlockBuilder<'a> {
         Ok(self)
     }
 
+    /// Sets the strategy ordering the inputs selected to cover the amount and native tokens of the outputs.
+    pub fn with_input_selection_strategy(mut self, strategy: impl InputSelectionStrategy + 'static) -> Self {
+        self.input_selection_strategy = Arc::new(strategy);
+        self
+    }
+
     /// Set tagged_data to the builder
     pub fn with_tag(mut self, tag: Vec<u8>) -> Self {
         self.tag.replace(tag);
@@ -289,6 +317,10 @@ impl<'a> ClientBlockBuilder<'a> {
             self = self.with_custom_remainder_address(&custom_remainder_address)?;
         }
 
+        if let Some(input_selection_strategy) = options.input_selection_strategy {
+            self = self.with_input_selection_strategy(input_selection_strategy);
+        }
+
         if let Some(tag) = options.tag {
             self = self.with_tag(prefix_hex::decode(&tag)?);
         }
@@ -313,10 +345,10 @@ impl<'a> ClientBlockBuilder<'a> {
         if self.data.is_some() && self.tag.is_none() {
             return Err(Error::MissingParameter("tag"));
         }
-        if self.inputs.is_some() && self.outputs.is_empty() {
+        if self.inputs.is_some() && self.outputs.is_empty() && self.burn.is_none() {
             return Err(Error::MissingParameter("output"));
         }
-        if !self.outputs.is_empty() {
+        if !self.outputs.is_empty() || self.burn.is_some() {
             if self.secret_manager.is_none() && self.inputs.is_none() {
                 return Err(Error::MissingParameter("seed"));
             }
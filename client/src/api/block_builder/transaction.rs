@@ -5,21 +5,31 @@
 
 use std::collections::HashSet;
 
+use hashbrown::HashMap;
 use iota_types::block::{
     input::{Input, UtxoInput},
-    output::{dto::OutputDto, AliasId, InputsCommitment, Output, OutputId},
+    output::{dto::OutputDto, AliasId, ChainId, InputsCommitment, Output, OutputId, TokenId},
     payload::{
-        transaction::{RegularTransactionEssence, TransactionEssence, TransactionPayload},
+        transaction::{RegularTransactionEssence, TransactionEssence, TransactionId, TransactionPayload},
         Payload, TaggedDataPayload,
     },
-    semantic::{semantic_validation, ConflictReason, ValidationContext},
-    signature::Ed25519Signature,
-    Block, BlockId,
+    protocol::ProtocolParameters,
+    semantic::{
+        semantic_validation, semantic_validation_report, ConflictReason, SemanticValidationReport, ValidationContext,
+    },
+    signature::{Ed25519Signature, Signature},
+    unlock::{SignatureUnlock, Unlock, Unlocks},
+    Block, BlockId, Error as BlockError,
 };
 use packable::PackableExt;
+use primitive_types::U256;
 
 use crate::{
-    api::{types::PreparedTransactionData, ClientBlockBuilder},
+    api::{
+        block_builder::input_selection::{core::requirement::alias::is_alias_state_transition, Selected},
+        types::{PreparedTransactionData, RemainderData},
+        ClientBlockBuilder,
+    },
     secret::{types::InputSigningData, SecretManageExt},
     Error, Result,
 };
@@ -36,7 +46,20 @@ impl<'a> ClientBlockBuilder<'a> {
     pub async fn prepare_transaction(&self) -> Result<PreparedTransactionData> {
         log::debug!("[prepare_transaction]");
         let protocol_parameters = self.client.get_protocol_parameters().await?;
-        let token_supply = self.client.get_token_supply().await?;
+        let selected_transaction_data = self.select_transaction_inputs(&protocol_parameters).await?;
+
+        prepare_transaction_data(
+            selected_transaction_data.inputs,
+            selected_transaction_data.outputs,
+            selected_transaction_data.remainder,
+            self.tagged_data_payload()?,
+            &protocol_parameters,
+        )
+    }
+
+    /// Selects the inputs, either the custom ones or automatically, and creates the remainder of a transaction.
+    pub(crate) async fn select_transaction_inputs(&self, protocol_parameters: &ProtocolParameters) -> Result<Selected> {
+        let token_supply = protocol_parameters.token_supply();
 
         let mut governance_transition: Option<HashSet<AliasId>> = None;
         for output in &self.outputs {
@@ -61,48 +84,25 @@ impl<'a> ClientBlockBuilder<'a> {
         }
 
         // Input selection
-        let selected_transaction_data = if self.inputs.is_some() {
-            self.get_custom_inputs(governance_transition, &protocol_parameters, self.allow_burning)
-                .await?
+        if self.inputs.is_some() {
+            self.get_custom_inputs(governance_transition, protocol_parameters, self.allow_burning)
+                .await
         } else {
-            self.get_inputs(&protocol_parameters).await?
-        };
-
-        // Build transaction payload
-        let inputs_commitment = InputsCommitment::new(selected_transaction_data.inputs.iter().map(|i| &i.output));
-
-        let mut essence = RegularTransactionEssence::builder(self.client.get_network_id().await?, inputs_commitment);
-        let inputs = selected_transaction_data
-            .inputs
-            .iter()
-            .map(|i| {
-                Ok(Input::Utxo(UtxoInput::new(
-                    *i.output_metadata.transaction_id(),
-                    i.output_metadata.output_index(),
-                )?))
-            })
-            .collect::<Result<Vec<Input>>>()?;
-        essence = essence.with_inputs(inputs);
-
-        essence = essence.with_outputs(selected_transaction_data.outputs);
-
-        // Add tagged data payload if tag set
-        if let Some(index) = self.tag.clone() {
-            let tagged_data_payload = TaggedDataPayload::new(index.to_vec(), self.data.clone().unwrap_or_default())?;
-            essence = essence.with_payload(Payload::from(tagged_data_payload));
+            self.get_inputs(protocol_parameters).await
         }
+    }
 
-        let regular_essence = essence.finish(&self.client.get_protocol_parameters().await?)?;
-
-        validate_regular_transaction_essence_length(&regular_essence)?;
-
-        let essence = TransactionEssence::Regular(regular_essence);
-
-        Ok(PreparedTransactionData {
-            essence,
-            inputs_data: selected_transaction_data.inputs,
-            remainder: selected_transaction_data.remainder,
-        })
+    /// Returns the tagged data payload of the transaction, if a tag is set.
+    pub(crate) fn tagged_data_payload(&self) -> Result<Option<Payload>> {
+        self.tag
+            .clone()
+            .map(|tag| {
+                Ok(Payload::from(TaggedDataPayload::new(
+                    tag,
+                    self.data.clone().unwrap_or_default(),
+                )?))
+            })
+            .transpose()
     }
 
     /// Sign the transaction
@@ -122,6 +122,14 @@ impl<'a> ClientBlockBuilder<'a> {
 
         if conflict != ConflictReason::None {
             log::debug!("[sign_transaction] conflict: {conflict:?} for {:#?}", tx_payload);
+            if let Ok(report) =
+                verify_semantic_report(&prepared_transaction_data.inputs_data, &tx_payload, current_time)
+            {
+                log::debug!(
+                    "[sign_transaction] semantic validation failures: {:?}",
+                    report.failures()
+                );
+            }
             return Err(Error::TransactionSemantic(conflict));
         }
 
@@ -129,6 +137,44 @@ impl<'a> ClientBlockBuilder<'a> {
     }
 }
 
+/// Builds the essence of a transaction from its inputs and outputs.
+pub(crate) fn prepare_transaction_data(
+    inputs_data: Vec<InputSigningData>,
+    outputs: Vec<Output>,
+    remainder: Option<RemainderData>,
+    payload: Option<Payload>,
+    protocol_parameters: &ProtocolParameters,
+) -> Result<PreparedTransactionData> {
+    let inputs_commitment = InputsCommitment::new(inputs_data.iter().map(|i| &i.output));
+    let inputs = inputs_data
+        .iter()
+        .map(|i| {
+            Ok(Input::Utxo(UtxoInput::new(
+                *i.output_metadata.transaction_id(),
+                i.output_metadata.output_index(),
+            )?))
+        })
+        .collect::<Result<Vec<Input>>>()?;
+
+    let mut essence = RegularTransactionEssence::builder(protocol_parameters.network_id(), inputs_commitment)
+        .with_inputs(inputs)
+        .with_outputs(outputs);
+
+    if let Some(payload) = payload {
+        essence = essence.with_payload(payload);
+    }
+
+    let regular_essence = essence.finish(protocol_parameters)?;
+
+    validate_regular_transaction_essence_length(&regular_essence)?;
+
+    Ok(PreparedTransactionData {
+        essence: TransactionEssence::Regular(regular_essence),
+        inputs_data,
+        remainder,
+    })
+}
+
 // TODO @thibault-martinez: this is very cumbersome with the current state, will refactor.
 /// Verifies the semantic of a prepared transaction.
 pub fn verify_semantic(
@@ -136,6 +182,101 @@ pub fn verify_semantic(
     transaction: &TransactionPayload,
     current_time: u32,
 ) -> crate::Result<ConflictReason> {
+    validate_semantic(input_signing_data, transaction, current_time, semantic_validation)
+}
+
+/// Verifies the semantic of a prepared transaction, running all the checks instead of stopping at the first failure.
+pub fn verify_semantic_report(
+    input_signing_data: &[InputSigningData],
+    transaction: &TransactionPayload,
+    current_time: u32,
+) -> crate::Result<SemanticValidationReport> {
+    validate_semantic(
+        input_signing_data,
+        transaction,
+        current_time,
+        semantic_validation_report,
+    )
+}
+
+/// Verifies the state transitions of the alias, foundry and NFT outputs of a prepared transaction, to reject invalid
+/// transitions before signing. The inputs are assumed to be unlocked.
+pub fn verify_state_transitions(prepared_transaction_data: &PreparedTransactionData, current_time: u32) -> Result<()> {
+    let TransactionEssence::Regular(essence) = &prepared_transaction_data.essence;
+    let inputs = prepared_transaction_data
+        .inputs_data
+        .iter()
+        .map(|input| (*input.output_id(), &input.output))
+        .collect::<Vec<(OutputId, &Output)>>();
+    // The state transition rules don't look at the unlocks.
+    let unlocks = Unlocks::new(vec![Unlock::Signature(SignatureUnlock::new(Signature::Ed25519(
+        Ed25519Signature::new(
+            [0; Ed25519Signature::PUBLIC_KEY_LENGTH],
+            [0; Ed25519Signature::SIGNATURE_LENGTH],
+        ),
+    )))])?;
+    let mut context = ValidationContext::new(
+        &TransactionId::null(),
+        essence,
+        inputs.iter().map(|(id, input)| (id, *input)),
+        &unlocks,
+        current_time,
+    );
+
+    for input in &prepared_transaction_data.inputs_data {
+        let alias_state_transition =
+            is_alias_state_transition(input, essence.outputs())?.map_or(false, |(state, _)| state);
+        let (required_address, unlocked_address) =
+            input
+                .output
+                .required_and_unlocked_address(current_time, input.output_id(), alias_state_transition)?;
+
+        context.unlocked_addresses.insert(required_address);
+        context.unlocked_addresses.extend(unlocked_address);
+        add_native_tokens(&mut context.input_native_tokens, &input.output);
+    }
+
+    for output in essence.outputs() {
+        add_native_tokens(&mut context.output_native_tokens, output);
+    }
+
+    let chain_ids = context
+        .input_chains
+        .keys()
+        .chain(context.output_chains.keys())
+        .copied()
+        .collect::<HashSet<ChainId>>();
+
+    for chain_id in chain_ids {
+        Output::verify_state_transition(
+            context.input_chains.get(&chain_id).copied(),
+            context.output_chains.get(&chain_id).copied(),
+            &context,
+        )
+        .map_err(|reason| Error::InvalidStateTransition { chain_id, reason })?;
+    }
+
+    Ok(())
+}
+
+fn add_native_tokens(native_tokens: &mut HashMap<TokenId, U256>, output: &Output) {
+    for native_token in output
+        .native_tokens()
+        .into_iter()
+        .flat_map(|native_tokens| native_tokens.iter())
+    {
+        let amount = native_tokens.entry(*native_token.token_id()).or_default();
+
+        *amount = amount.saturating_add(native_token.amount());
+    }
+}
+
+fn validate_semantic<T>(
+    input_signing_data: &[InputSigningData],
+    transaction: &TransactionPayload,
+    current_time: u32,
+    validate: impl FnOnce(ValidationContext<'_>, &[(OutputId, &Output)], &Unlocks) -> std::result::Result<T, BlockError>,
+) -> crate::Result<T> {
     let transaction_id = transaction.id();
     let TransactionEssence::Regular(essence) = transaction.essence();
     let output_ids = input_signing_data.iter().map(|input| *input.output_id());
@@ -156,7 +297,7 @@ pub fn verify_semantic(
         current_time,
     );
 
-    semantic_validation(context, inputs.as_slice(), transaction.unlocks()).map_err(Error::BlockError)
+    validate(context, inputs.as_slice(), transaction.unlocks()).map_err(Error::BlockError)
 }
 
 /// Verifies that the transaction payload doesn't exceed the block size limit with 8 parents.
@@ -0,0 +1,252 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Splitting of transactions requiring more than [`INPUT_COUNT_MAX`] inputs into a chain of transactions
+
+use iota_types::block::{
+    input::{Input, UtxoInput, INPUT_COUNT_MAX},
+    output::{
+        unlock_condition::{AddressUnlockCondition, UnlockCondition},
+        BasicOutputBuilder, NativeTokensBuilder, Output, OutputId,
+    },
+    parent::Parents,
+    payload::{
+        transaction::{RegularTransactionEssence, TransactionEssence, TransactionId},
+        Payload,
+    },
+    protocol::ProtocolParameters,
+    Block, BlockId,
+};
+
+use crate::{
+    api::{
+        block_builder::{input_selection::Selected, transaction::prepare_transaction_data},
+        types::PreparedTransactionData,
+        ClientBlockBuilder,
+    },
+    secret::types::{InputSigningData, OutputMetadata},
+    Error, Result,
+};
+
+impl<'a> ClientBlockBuilder<'a> {
+    /// Prepares a chain of transactions: consolidation steps, if the transaction requires more than
+    /// [`INPUT_COUNT_MAX`] inputs, followed by the transaction with the outputs of the builder.
+    /// The steps have to be signed and sent in order, see [`ClientBlockBuilder::sign_transaction_chain()`].
+    pub async fn prepare_transaction_chain(&self) -> Result<Vec<PreparedTransactionData>> {
+        log::debug!("[prepare_transaction_chain]");
+        let protocol_parameters = self.client.get_protocol_parameters().await?;
+        let selected_transaction_data = self.select_transaction_inputs(&protocol_parameters).await?;
+
+        plan_transaction_chain(
+            selected_transaction_data,
+            self.tagged_data_payload()?,
+            &protocol_parameters,
+        )
+    }
+
+    /// Signs the steps of a transaction chain in order, replacing the placeholder transaction IDs of the inputs
+    /// spending outputs of previous steps with the actual ones.
+    pub async fn sign_transaction_chain(&self, mut steps: Vec<PreparedTransactionData>) -> Result<Vec<Payload>> {
+        log::debug!("[sign_transaction_chain]");
+        let placeholders = transaction_chain_placeholders(&steps);
+        let mut payloads = Vec::with_capacity(steps.len());
+
+        for index in 0..steps.len() {
+            let payload = self.sign_transaction(steps[index].clone()).await?;
+
+            if let Payload::Transaction(transaction) = &payload {
+                let transaction_id = transaction.id();
+
+                for step in &mut steps[index + 1..] {
+                    resolve_transaction_chain_step(step, &placeholders[index], &transaction_id)?;
+                }
+            }
+
+            payloads.push(payload);
+        }
+
+        Ok(payloads)
+    }
+
+    /// Consume the builder and send the transaction chain, each block having the block of the previous step as
+    /// parent, so that the nodes process the steps in order.
+    pub async fn finish_transaction_chain(self) -> Result<Vec<Block>> {
+        if self.outputs.is_empty() {
+            return Err(Error::MissingParameter("output"));
+        }
+        if self.secret_manager.is_none() {
+            return Err(Error::MissingParameter("secret manager"));
+        }
+
+        let steps = self.prepare_transaction_chain().await?;
+        let payloads = self.sign_transaction_chain(steps).await?;
+        let mut blocks: Vec<Block> = Vec::with_capacity(payloads.len());
+
+        for payload in payloads {
+            let parents = match blocks.last() {
+                Some(previous) => Some(Parents::new(vec![previous.id()])?),
+                None => self.parents.clone(),
+            };
+            let block = self.client.finish_block_builder(parents, Some(payload)).await?;
+            let block_id = self.client.post_block_raw(&block).await?;
+
+            // The node changes the nonce if we use remote PoW.
+            blocks.push(if self.client.get_local_pow() {
+                block
+            } else {
+                self.client.get_block(&block_id).await?
+            });
+        }
+
+        Ok(blocks)
+    }
+}
+
+/// Plans the chain of transactions spending selected inputs. If there are more than [`INPUT_COUNT_MAX`] of them,
+/// basic outputs only locked to an Ed25519 address are consolidated by preceding steps into a single output each,
+/// sent to the address of the first consolidated input, until the last transaction fits.
+///
+/// The transaction ID of a step is only known once it is signed, so the inputs spending outputs created by a previous
+/// step use a placeholder transaction ID instead, see [`transaction_chain_placeholders()`].
+pub fn plan_transaction_chain(
+    selected: Selected,
+    payload: Option<Payload>,
+    protocol_parameters: &ProtocolParameters,
+) -> Result<Vec<PreparedTransactionData>> {
+    let input_count_max = INPUT_COUNT_MAX as usize;
+    let (mut consolidable, mut inputs): (Vec<_>, Vec<_>) = selected.inputs.into_iter().partition(is_consolidable);
+    let mut steps = Vec::new();
+
+    while inputs.len() + consolidable.len() > input_count_max {
+        if consolidable.len() < 2 {
+            return Err(Error::ConsolidationRequired(inputs.len() + consolidable.len()));
+        }
+
+        // Consolidating n inputs into a single one saves n - 1 of them.
+        let excess = inputs.len() + consolidable.len() - input_count_max;
+        let count = (excess + 1).min(input_count_max).min(consolidable.len());
+        let step_inputs = consolidable.drain(..count).collect::<Vec<_>>();
+        let (step, consolidated) = consolidation_step(step_inputs, protocol_parameters)?;
+
+        consolidable.push(consolidated);
+        steps.push(step);
+    }
+
+    inputs.extend(consolidable);
+
+    steps.push(prepare_transaction_data(
+        inputs,
+        selected.outputs,
+        selected.remainder,
+        payload,
+        protocol_parameters,
+    )?);
+
+    Ok(steps)
+}
+
+/// Returns the placeholder transaction IDs used by the later steps of a transaction chain, planned by
+/// [`plan_transaction_chain()`], to spend the outputs of each step. They have to be computed before any step is
+/// resolved with [`resolve_transaction_chain_step()`].
+pub fn transaction_chain_placeholders(steps: &[PreparedTransactionData]) -> Vec<TransactionId> {
+    steps.iter().map(placeholder_transaction_id).collect()
+}
+
+/// Replaces a placeholder transaction ID in the inputs of a step of a transaction chain with the ID of the signed
+/// transaction it stands for.
+pub fn resolve_transaction_chain_step(
+    step: &mut PreparedTransactionData,
+    placeholder: &TransactionId,
+    transaction_id: &TransactionId,
+) -> Result<()> {
+    if !step
+        .inputs_data
+        .iter()
+        .any(|input| input.output_metadata.transaction_id() == placeholder)
+    {
+        return Ok(());
+    }
+
+    for input in &mut step.inputs_data {
+        if input.output_metadata.transaction_id() == placeholder {
+            let output_id = OutputId::new(*transaction_id, input.output_metadata.output_index())?;
+            input.output_metadata = unbooked_output_metadata(output_id);
+        }
+    }
+
+    let TransactionEssence::Regular(essence) = &step.essence;
+    let inputs = step
+        .inputs_data
+        .iter()
+        .map(|input| Input::Utxo(UtxoInput::from(*input.output_id())))
+        .collect();
+    let mut builder = RegularTransactionEssence::builder(essence.network_id(), *essence.inputs_commitment())
+        .with_inputs(inputs)
+        .with_outputs(essence.outputs().to_vec());
+
+    if let Some(payload) = essence.payload() {
+        builder = builder.with_payload(payload.clone());
+    }
+
+    step.essence = TransactionEssence::Regular(builder.finish_unverified()?);
+
+    Ok(())
+}
+
+// Only spending basic outputs without other unlock conditions than an Ed25519 address is guaranteed to be unlockable
+// with the same signature in a consolidation step.
+fn is_consolidable(input: &InputSigningData) -> bool {
+    match &input.output {
+        Output::Basic(output) => {
+            output.unlock_conditions().len() == 1
+                && output
+                    .unlock_conditions()
+                    .address()
+                    .is_some_and(|unlock_condition| unlock_condition.address().is_ed25519())
+        }
+        _ => false,
+    }
+}
+
+fn placeholder_transaction_id(step: &PreparedTransactionData) -> TransactionId {
+    TransactionId::new(step.essence.hash())
+}
+
+fn unbooked_output_metadata(output_id: OutputId) -> OutputMetadata {
+    OutputMetadata::new(BlockId::null(), output_id, false, None, None, None, 0, 0, 0)
+}
+
+fn consolidation_step(
+    inputs: Vec<InputSigningData>,
+    protocol_parameters: &ProtocolParameters,
+) -> Result<(PreparedTransactionData, InputSigningData)> {
+    // PANIC: consolidation steps have at least 2 inputs.
+    let first = inputs[0].clone();
+    let mut amount = 0;
+    let mut native_tokens = NativeTokensBuilder::new();
+
+    for input in &inputs {
+        amount += input.output.amount();
+
+        if let Some(input_native_tokens) = input.output.native_tokens() {
+            native_tokens.add_native_tokens(input_native_tokens.clone())?;
+        }
+    }
+
+    // PANIC: consolidable inputs have an address unlock condition.
+    let address = *first.output.unlock_conditions().unwrap().address().unwrap().address();
+    let output = BasicOutputBuilder::new_with_amount(amount)?
+        .with_native_tokens(native_tokens.finish()?)
+        .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)))
+        .finish_output(protocol_parameters.token_supply())?;
+
+    let step = prepare_transaction_data(inputs, vec![output.clone()], None, None, protocol_parameters)?;
+    let consolidated = InputSigningData {
+        output,
+        output_metadata: unbooked_output_metadata(OutputId::new(placeholder_transaction_id(&step), 0)?),
+        chain: first.chain,
+        bech32_address: first.bech32_address,
+    };
+
+    Ok((step, consolidated))
+}
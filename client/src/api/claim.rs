@@ -0,0 +1,253 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Classify and claim outputs with expiration, storage deposit return or timelock unlock conditions.
+
+use std::collections::HashSet;
+
+use iota_types::block::{
+    address::Address,
+    input::UtxoInput,
+    output::{
+        feature::Feature,
+        unlock_condition::{AddressUnlockCondition, UnlockCondition},
+        BasicOutputBuilder, NativeTokensBuilder, NftOutputBuilder, Output, OutputId, OUTPUT_COUNT_MAX,
+    },
+    Block,
+};
+
+use crate::{
+    api::GetAddressesBuilderOptions, node_api::indexer::query_parameters::QueryParameter, secret::SecretManager,
+    Client, Error, Result,
+};
+
+// Claiming an output creates at most an NFT output and a storage deposit return output, aside from the sweep output.
+const CLAIMED_OUTPUTS_PER_TRANSACTION_MAX: usize = (OUTPUT_COUNT_MAX as usize - 1) / 2;
+
+/// An output with additional unlock conditions that concerns one of the given addresses.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimableOutput {
+    /// The ID of the output.
+    pub output_id: OutputId,
+    /// The output.
+    pub output: Output,
+    /// The address, among the given ones, that can unlock the output or, for outputs returning to the sender, that
+    /// the output was sent to.
+    pub address: Address,
+    /// The milestone timestamp from which the output can be claimed, for outputs that can only be claimed later.
+    pub claimable_from: Option<u32>,
+}
+
+/// Outputs with expiration, storage deposit return or timelock unlock conditions, classified by when they can be
+/// claimed.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ClaimableOutputs {
+    /// Outputs that can be claimed now.
+    pub now: Vec<ClaimableOutput>,
+    /// Outputs that can be claimed once their timelock or, for outputs sent by the addresses, their expiration is
+    /// reached.
+    pub later: Vec<ClaimableOutput>,
+    /// Outputs sent to the addresses that expired and can now only be claimed by the sender.
+    pub returning_to_sender: Vec<ClaimableOutput>,
+}
+
+impl Client {
+    /// Returns the basic and NFT outputs with expiration, storage deposit return or timelock unlock conditions that
+    /// are owned by or expire to the given addresses, classified by when they can be claimed.
+    pub async fn claimable_outputs(&self, addresses: &[Address]) -> Result<ClaimableOutputs> {
+        let bech32_hrp = self.get_bech32_hrp().await?;
+        let token_supply = self.get_token_supply().await?;
+        let current_time = self.get_time_checked().await?;
+        let mut output_ids = HashSet::new();
+
+        for address in addresses {
+            let bech32_address = address.to_bech32(&bech32_hrp);
+            let queries = [
+                vec![
+                    QueryParameter::Address(bech32_address.clone()),
+                    QueryParameter::HasStorageDepositReturn(true),
+                ],
+                vec![
+                    QueryParameter::Address(bech32_address.clone()),
+                    QueryParameter::HasExpiration(true),
+                ],
+                vec![
+                    QueryParameter::Address(bech32_address.clone()),
+                    QueryParameter::HasTimelock(true),
+                ],
+                vec![
+                    QueryParameter::ExpirationReturnAddress(bech32_address),
+                    QueryParameter::HasExpiration(true),
+                ],
+            ];
+
+            for query in queries {
+                output_ids.extend(self.basic_output_ids(query.clone()).await?);
+                output_ids.extend(self.nft_output_ids(query).await?);
+            }
+        }
+
+        let mut claimable_outputs = ClaimableOutputs::default();
+
+        for output_response in self.get_outputs(output_ids.into_iter().collect()).await? {
+            let output_id = output_response.metadata.output_id()?;
+            let output = Output::try_from_dto(&output_response.output, token_supply)?;
+
+            classify_output(&mut claimable_outputs, output_id, output, addresses, current_time);
+        }
+
+        Ok(claimable_outputs)
+    }
+
+    /// Claims outputs, e.g. the ones that [`Client::claimable_outputs()`] classified as claimable now, fulfilling
+    /// their storage deposit returns and sweeping the rest, with their native tokens and NFTs, to the address with the
+    /// lowest index in the range of the address builder options. Spent outputs are ignored.
+    pub async fn claim_outputs(
+        &self,
+        secret_manager: &SecretManager,
+        output_ids: Vec<OutputId>,
+        address_builder_options: GetAddressesBuilderOptions,
+    ) -> Result<Vec<Block>> {
+        let protocol_parameters = self.get_protocol_parameters().await?;
+        let token_supply = protocol_parameters.token_supply();
+        let coin_type = address_builder_options.coin_type;
+        let account_index = address_builder_options.account_index;
+        let input_range = address_builder_options.range.clone().unwrap_or(0..1);
+        let addresses = self
+            .get_addresses(secret_manager)
+            .set_options(address_builder_options)?
+            .finish()
+            .await?;
+        let sweep_address = Address::try_from_bech32(addresses.first().ok_or(Error::MissingParameter("address"))?)?.1;
+
+        let mut inputs = Vec::new();
+
+        for output_response in self.get_outputs(output_ids).await? {
+            if !output_response.metadata.is_spent {
+                inputs.push((
+                    output_response.metadata.output_id()?,
+                    Output::try_from_dto(&output_response.output, token_supply)?,
+                ));
+            }
+        }
+
+        let mut blocks = Vec::new();
+
+        for chunk in inputs.chunks(CLAIMED_OUTPUTS_PER_TRANSACTION_MAX) {
+            let mut block_builder = self
+                .block()
+                .with_secret_manager(secret_manager)
+                .with_input_range(input_range.clone());
+
+            if let Some(coin_type) = coin_type {
+                block_builder = block_builder.with_coin_type(coin_type);
+            }
+            if let Some(account_index) = account_index {
+                block_builder = block_builder.with_account_index(account_index);
+            }
+
+            let mut outputs = Vec::new();
+            let mut sweep_amount = 0;
+            let mut sweep_native_tokens = NativeTokensBuilder::new();
+
+            for (output_id, output) in chunk {
+                block_builder = block_builder.with_input(UtxoInput::from(*output_id))?;
+                sweep_amount += output.amount();
+
+                if let Some(native_tokens) = output.native_tokens() {
+                    sweep_native_tokens.add_native_tokens(native_tokens.clone())?;
+                }
+                // The input selection creates the storage deposit return outputs.
+                if let Some(storage_deposit_return) = output
+                    .unlock_conditions()
+                    .and_then(|unlock_conditions| unlock_conditions.storage_deposit_return())
+                {
+                    sweep_amount = sweep_amount.saturating_sub(storage_deposit_return.amount());
+                }
+
+                if let Output::Nft(nft_output) = output {
+                    // The sender feature of the received NFT can't be kept as the sender doesn't unlock an input.
+                    let nft_output = NftOutputBuilder::from(nft_output)
+                        .with_minimum_storage_deposit(protocol_parameters.rent_structure().clone())
+                        .with_nft_id(nft_output.nft_id_non_null(output_id))
+                        .with_native_tokens([])
+                        .with_unlock_conditions([UnlockCondition::Address(AddressUnlockCondition::new(sweep_address))])
+                        .with_features(
+                            nft_output
+                                .features()
+                                .iter()
+                                .filter(|feature| !matches!(feature, Feature::Sender(_)))
+                                .cloned(),
+                        )
+                        .finish_output(token_supply)?;
+
+                    sweep_amount = sweep_amount.saturating_sub(nft_output.amount());
+                    outputs.push(nft_output);
+                }
+            }
+
+            let sweep_native_tokens = sweep_native_tokens.finish()?;
+
+            if sweep_amount > 0 || !sweep_native_tokens.is_empty() {
+                outputs.push(
+                    BasicOutputBuilder::new_with_amount(sweep_amount)?
+                        .with_native_tokens(sweep_native_tokens)
+                        .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(sweep_address)))
+                        .finish_output(token_supply)?,
+                );
+            }
+
+            blocks.push(block_builder.with_outputs(outputs)?.finish().await?);
+        }
+
+        Ok(blocks)
+    }
+}
+
+fn classify_output(
+    claimable_outputs: &mut ClaimableOutputs,
+    output_id: OutputId,
+    output: Output,
+    addresses: &[Address],
+    current_time: u32,
+) {
+    let Some(unlock_conditions) = output.unlock_conditions() else {
+        return;
+    };
+    let Some(address) = unlock_conditions
+        .address()
+        .map(|unlock_condition| *unlock_condition.address())
+    else {
+        return;
+    };
+    let unlocking_address = *unlock_conditions.locked_address(&address, current_time);
+    let timelock = unlock_conditions.timelock().map(|timelock| timelock.timestamp());
+
+    let (list, address, claimable_from) = if addresses.contains(&unlocking_address) {
+        if unlock_conditions.is_time_locked(current_time) {
+            (&mut claimable_outputs.later, unlocking_address, timelock)
+        } else {
+            (&mut claimable_outputs.now, unlocking_address, None)
+        }
+    } else if addresses.contains(&address) {
+        (&mut claimable_outputs.returning_to_sender, address, None)
+    } else if let Some(expiration) = unlock_conditions.expiration() {
+        // The output was sent by one of the addresses and didn't expire yet.
+        let claimable_from = expiration.timestamp().max(timelock.unwrap_or_default());
+
+        (
+            &mut claimable_outputs.later,
+            *expiration.return_address(),
+            Some(claimable_from),
+        )
+    } else {
+        return;
+    };
+
+    list.push(ClaimableOutput {
+        output_id,
+        output,
+        address,
+        claimable_from,
+    });
+}
@@ -0,0 +1,245 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Creation of native tokens with foundries, and minting, melting and burning of native tokens.
+
+use iota_types::block::{
+    address::{Address, AliasAddress},
+    output::{
+        feature::Feature,
+        irc::Irc30Metadata,
+        unlock_condition::{AddressUnlockCondition, ImmutableAliasAddressUnlockCondition, UnlockCondition},
+        AliasId, AliasOutputBuilder, BasicOutputBuilder, FoundryId, FoundryOutputBuilder, NativeToken, Output,
+        SimpleTokenScheme, TokenId, TokenScheme,
+    },
+    payload::transaction::TransactionId,
+    protocol::ProtocolParameters,
+    BlockId,
+};
+use primitive_types::U256;
+
+use super::{block_builder::input_selection::Burn, nft::transaction_id};
+use crate::{secret::SecretManager, Client, Result};
+
+/// A transaction creating, minting, melting or burning a native token.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NativeTokenTransaction {
+    /// The ID of the token.
+    pub token_id: TokenId,
+    /// The ID of the transaction.
+    pub transaction_id: TransactionId,
+    /// The ID of the block the transaction was sent with.
+    pub block_id: BlockId,
+}
+
+impl Client {
+    /// Creates a native token with a new foundry controlled by an alias, and sends the circulating supply to the
+    /// state controller of the alias. The alias is transitioned to its next state with an incremented foundry counter,
+    /// which gives the serial number of the foundry.
+    pub async fn create_native_token(
+        &self,
+        secret_manager: &SecretManager,
+        alias_id: AliasId,
+        circulating_supply: U256,
+        maximum_supply: U256,
+        metadata: Option<&Irc30Metadata>,
+    ) -> Result<NativeTokenTransaction> {
+        let protocol_parameters = self.get_protocol_parameters().await?;
+        let token_supply = protocol_parameters.token_supply();
+        let (alias, state_controller, serial_number) = self
+            .alias_state_transition(&protocol_parameters, alias_id, true)
+            .await?;
+
+        let token_scheme = TokenScheme::Simple(SimpleTokenScheme::new(
+            circulating_supply,
+            U256::zero(),
+            maximum_supply,
+        )?);
+        let mut foundry = FoundryOutputBuilder::new_with_minimum_storage_deposit(
+            protocol_parameters.rent_structure().clone(),
+            serial_number,
+            token_scheme,
+        )?
+        .add_unlock_condition(UnlockCondition::ImmutableAliasAddress(
+            ImmutableAliasAddressUnlockCondition::new(AliasAddress::new(alias_id)),
+        ));
+
+        if let Some(metadata) = metadata {
+            foundry = foundry.with_irc_30_metadata(metadata)?;
+        }
+
+        let foundry = foundry.finish(token_supply)?;
+        let token_id = foundry.token_id();
+        let mut outputs = vec![alias, Output::Foundry(foundry)];
+
+        if !circulating_supply.is_zero() {
+            outputs.push(native_token_output(
+                &protocol_parameters,
+                token_id,
+                circulating_supply,
+                state_controller,
+            )?);
+        }
+
+        self.send_native_token_transaction(secret_manager, token_id, outputs, None)
+            .await
+    }
+
+    /// Mints an amount of a native token and sends it to the state controller of the alias controlling its foundry.
+    pub async fn mint_native_token(
+        &self,
+        secret_manager: &SecretManager,
+        token_id: TokenId,
+        amount: U256,
+    ) -> Result<NativeTokenTransaction> {
+        let protocol_parameters = self.get_protocol_parameters().await?;
+        let foundry = self.foundry_output(FoundryId::new(*token_id)).await?;
+        let (alias, state_controller, _) = self
+            .alias_state_transition(&protocol_parameters, *foundry.alias_address().alias_id(), false)
+            .await?;
+        let TokenScheme::Simple(token_scheme) = foundry.token_scheme();
+        // The token scheme rejects amounts exceeding the maximum supply.
+        let token_scheme = SimpleTokenScheme::new(
+            token_scheme.minted_tokens().saturating_add(amount),
+            token_scheme.melted_tokens(),
+            token_scheme.maximum_supply(),
+        )?;
+        let outputs = vec![
+            alias,
+            FoundryOutputBuilder::from(&foundry)
+                .with_token_scheme(TokenScheme::Simple(token_scheme))
+                .finish_output(protocol_parameters.token_supply())?,
+            native_token_output(&protocol_parameters, token_id, amount, state_controller)?,
+        ];
+
+        self.send_native_token_transaction(secret_manager, token_id, outputs, None)
+            .await
+    }
+
+    /// Melts an amount of a native token held by the addresses of the secret manager, decreasing its circulating
+    /// supply.
+    pub async fn melt_native_token(
+        &self,
+        secret_manager: &SecretManager,
+        token_id: TokenId,
+        amount: U256,
+    ) -> Result<NativeTokenTransaction> {
+        let protocol_parameters = self.get_protocol_parameters().await?;
+        let foundry = self.foundry_output(FoundryId::new(*token_id)).await?;
+        let (alias, _, _) = self
+            .alias_state_transition(&protocol_parameters, *foundry.alias_address().alias_id(), false)
+            .await?;
+        let TokenScheme::Simple(token_scheme) = foundry.token_scheme();
+        // The token scheme rejects melting more than the circulating supply.
+        let token_scheme = SimpleTokenScheme::new(
+            token_scheme.minted_tokens(),
+            token_scheme.melted_tokens().saturating_add(amount),
+            token_scheme.maximum_supply(),
+        )?;
+        // Input selection provides the melted tokens.
+        let outputs = vec![
+            alias,
+            FoundryOutputBuilder::from(&foundry)
+                .with_token_scheme(TokenScheme::Simple(token_scheme))
+                .finish_output(protocol_parameters.token_supply())?,
+        ];
+
+        self.send_native_token_transaction(secret_manager, token_id, outputs, None)
+            .await
+    }
+
+    /// Burns an amount of a native token held by the addresses of the secret manager. Unlike melting, burning
+    /// doesn't need the foundry, but the burnt tokens stay part of the circulating supply.
+    pub async fn burn_native_token(
+        &self,
+        secret_manager: &SecretManager,
+        token_id: TokenId,
+        amount: U256,
+    ) -> Result<NativeTokenTransaction> {
+        self.send_native_token_transaction(
+            secret_manager,
+            token_id,
+            Vec::new(),
+            Some(Burn::new().add_native_token(token_id, amount)),
+        )
+        .await
+    }
+
+    // Returns the next state of an alias, optionally with one more foundry, along with its state controller and its
+    // foundry counter.
+    async fn alias_state_transition(
+        &self,
+        protocol_parameters: &ProtocolParameters,
+        alias_id: AliasId,
+        add_foundry: bool,
+    ) -> Result<(Output, Address, u32)> {
+        let token_supply = protocol_parameters.token_supply();
+        let alias = self.alias_output(alias_id).await?;
+        let foundry_counter = alias.foundry_counter() + u32::from(add_foundry);
+        // The sender of a previous transition doesn't unlock an input of this transaction.
+        let builder = AliasOutputBuilder::from(&alias)
+            .with_alias_id(alias_id)
+            .with_state_index(alias.state_index() + 1)
+            .with_foundry_counter(foundry_counter)
+            .with_features(
+                alias
+                    .features()
+                    .iter()
+                    .filter(|feature| !matches!(feature, Feature::Sender(_)))
+                    .cloned(),
+            );
+        let minimum_amount = builder
+            .clone()
+            .with_minimum_storage_deposit(protocol_parameters.rent_structure().clone())
+            .finish_output(token_supply)?
+            .amount();
+
+        Ok((
+            builder
+                .with_amount(alias.amount().max(minimum_amount))?
+                .finish_output(token_supply)?,
+            *alias.state_controller_address(),
+            foundry_counter,
+        ))
+    }
+
+    async fn send_native_token_transaction(
+        &self,
+        secret_manager: &SecretManager,
+        token_id: TokenId,
+        outputs: Vec<Output>,
+        burn: Option<Burn>,
+    ) -> Result<NativeTokenTransaction> {
+        let mut builder = self.block().with_secret_manager(secret_manager);
+
+        if !outputs.is_empty() {
+            builder = builder.with_outputs(outputs)?;
+        }
+        if let Some(burn) = burn {
+            builder = builder.with_burn(burn);
+        }
+
+        let block = builder.finish().await?;
+
+        Ok(NativeTokenTransaction {
+            token_id,
+            transaction_id: transaction_id(&block),
+            block_id: block.id(),
+        })
+    }
+}
+
+// Returns a basic output holding an amount of a native token, with the minimum storage deposit.
+fn native_token_output(
+    protocol_parameters: &ProtocolParameters,
+    token_id: TokenId,
+    amount: U256,
+    address: Address,
+) -> Result<Output> {
+    Ok(
+        BasicOutputBuilder::new_with_minimum_storage_deposit(protocol_parameters.rent_structure().clone())?
+            .add_native_token(NativeToken::new(token_id, amount)?)
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)))
+            .finish_output(protocol_parameters.token_supply())?,
+    )
+}
@@ -0,0 +1,131 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Traverse the blocks confirmed by a milestone.
+
+use std::collections::{HashMap, HashSet};
+
+use iota_types::{
+    api::response::BlockMetadataResponse,
+    block::{parent::Parents, Block, BlockId},
+};
+
+use crate::{Client, Error, Result};
+
+/// A block confirmed by a milestone, with its metadata.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneConeBlock {
+    /// The id of the block.
+    pub block_id: BlockId,
+    /// The block.
+    pub block: Block,
+    /// The metadata of the block.
+    pub metadata: BlockMetadataResponse,
+}
+
+impl Client {
+    /// Returns the blocks confirmed by the milestone with the given index, in white-flag order.
+    ///
+    /// The past cone of the milestone is walked from its parents, fetching the blocks of each level in parallel, and
+    /// stops at blocks referenced by earlier milestones. The white-flag order is the order in which the blocks were
+    /// applied to the ledger, a depth-first post-order traversal visiting the parents of every block in order, and
+    /// is computed locally so that the merkle roots of the milestone can be checked against it.
+    pub async fn milestone_cone(&self, index: u32) -> Result<Vec<MilestoneConeBlock>> {
+        let milestone = self.get_milestone_by_index(index).await?;
+        let parents = milestone.essence().parents();
+        let mut visited = parents.iter().copied().collect::<HashSet<_>>();
+        let mut next_block_ids = parents.iter().copied().collect::<Vec<_>>();
+        let mut cone = HashMap::new();
+
+        while !next_block_ids.is_empty() {
+            let mut parent_block_ids = Vec::new();
+
+            for cone_block in self.get_cone_blocks(next_block_ids, index).await?.into_iter().flatten() {
+                for parent in cone_block.block.parents().iter() {
+                    if visited.insert(*parent) {
+                        parent_block_ids.push(*parent);
+                    }
+                }
+                cone.insert(cone_block.block_id, cone_block);
+            }
+
+            next_block_ids = parent_block_ids;
+        }
+
+        Ok(white_flag_order(parents, cone))
+    }
+
+    /// Requests the given blocks in parallel, `None` for the ones that are not referenced by the milestone.
+    async fn get_cone_blocks(&self, block_ids: Vec<BlockId>, index: u32) -> Result<Vec<Option<MilestoneConeBlock>>> {
+        #[cfg(target_family = "wasm")]
+        {
+            let mut cone_blocks = Vec::new();
+
+            for block_id in block_ids {
+                cone_blocks.push(self.get_cone_block(block_id, index).await?);
+            }
+            Ok(cone_blocks)
+        }
+
+        #[cfg(not(target_family = "wasm"))]
+        self.parallel_requests(block_ids, move |client, block_id| async move {
+            client.get_cone_block(block_id, index).await
+        })
+        .await
+    }
+
+    async fn get_cone_block(&self, block_id: BlockId, index: u32) -> Result<Option<MilestoneConeBlock>> {
+        let metadata = match self.get_block_metadata(&block_id).await {
+            Ok(metadata) => metadata,
+            // Pruned blocks and solid entry points were referenced by earlier milestones.
+            Err(Error::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        if metadata.referenced_by_milestone_index != Some(index) {
+            return Ok(None);
+        }
+
+        let block = self.get_block(&block_id).await?;
+
+        Ok(Some(MilestoneConeBlock {
+            block_id,
+            block,
+            metadata,
+        }))
+    }
+}
+
+/// Orders the blocks of a milestone cone with a depth-first post-order traversal from the milestone parents.
+fn white_flag_order(parents: &Parents, mut cone: HashMap<BlockId, MilestoneConeBlock>) -> Vec<MilestoneConeBlock> {
+    let mut ordered = Vec::with_capacity(cone.len());
+    let mut visited = HashSet::new();
+    // The flag tells whether the parents of the block were already traversed.
+    let mut stack = Vec::new();
+    push_parents(&mut stack, parents);
+
+    while let Some((block_id, traversed)) = stack.pop() {
+        if traversed {
+            if let Some(cone_block) = cone.remove(&block_id) {
+                ordered.push(cone_block);
+            }
+            continue;
+        }
+        if !visited.insert(block_id) {
+            continue;
+        }
+        if let Some(cone_block) = cone.get(&block_id) {
+            stack.push((block_id, true));
+            push_parents(&mut stack, cone_block.block.parents());
+        }
+    }
+
+    ordered
+}
+
+/// Pushes the parents in reverse order so that they are popped in order.
+fn push_parents(stack: &mut Vec<(BlockId, bool)>, parents: &Parents) {
+    let first = stack.len();
+    stack.extend(parents.iter().map(|parent| (*parent, false)));
+    stack[first..].reverse();
+}
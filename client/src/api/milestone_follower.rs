@@ -0,0 +1,234 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Follow the confirmed milestones and their UTXO changes.
+
+use std::{str::FromStr, time::Duration};
+
+use futures::Stream;
+use iota_types::{
+    api::response::UtxoChangesResponse,
+    block::{
+        output::{Output, OutputId},
+        payload::milestone::MilestonePayload,
+    },
+};
+
+use crate::{constants::DEFAULT_MILESTONE_FOLLOWER_POLL_INTERVAL, db::DatabaseProvider, Client, Result};
+
+/// The key under which a [`MilestoneFollower`] stores the index of the next milestone to follow.
+pub const MILESTONE_FOLLOWER_STATE_KEY: &[u8] = b"milestone-follower/next-index";
+
+/// A confirmed milestone with the changes it applied to the ledger.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneUpdate {
+    /// The milestone.
+    pub milestone: MilestonePayload,
+    /// The ids of the outputs created and consumed by the milestone.
+    pub utxo_changes: UtxoChangesResponse,
+    /// The outputs created by the milestone.
+    pub created_outputs: Vec<(OutputId, Output)>,
+    /// The outputs consumed by the milestone.
+    pub consumed_outputs: Vec<(OutputId, Output)>,
+}
+
+/// Follows the confirmed milestones, in order and without gaps, starting at a given index.
+///
+/// Milestones that were confirmed while the follower wasn't running or couldn't reach a node are fetched as soon as
+/// possible. With [`with_resume_state()`](MilestoneFollower::with_resume_state), the index of the next milestone is
+/// persisted so that following can be resumed later on.
+#[must_use]
+pub struct MilestoneFollower {
+    client: Client,
+    start_index: u32,
+    poll_interval: Duration,
+    state_db: Option<Box<dyn DatabaseProvider + Send + Sync>>,
+    #[cfg(feature = "mqtt")]
+    use_mqtt: bool,
+}
+
+impl Client {
+    /// Creates a [`MilestoneFollower`] starting at the milestone with the given index.
+    pub fn milestone_follower(&self, start_index: u32) -> MilestoneFollower {
+        MilestoneFollower::new(self.clone(), start_index)
+    }
+}
+
+impl MilestoneFollower {
+    /// Creates a [`MilestoneFollower`] starting at the milestone with the given index.
+    pub fn new(client: Client, start_index: u32) -> Self {
+        Self {
+            client,
+            start_index,
+            poll_interval: DEFAULT_MILESTONE_FOLLOWER_POLL_INTERVAL,
+            state_db: None,
+            #[cfg(feature = "mqtt")]
+            use_mqtt: false,
+        }
+    }
+
+    /// Sets the interval in which the node is asked for new milestones, default is 5 seconds.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Persists the index of the next milestone to follow under [`MILESTONE_FOLLOWER_STATE_KEY`] in the given
+    /// database. If an index is already stored, following resumes from there instead of the start index.
+    ///
+    /// The index is stored when the next milestone is requested from the stream, so every milestone is yielded at
+    /// least once even if the follower is stopped while a milestone is processed.
+    pub fn with_resume_state(mut self, db: impl DatabaseProvider + Send + Sync + 'static) -> Self {
+        self.state_db.replace(Box::new(db));
+        self
+    }
+
+    /// Listens to the confirmed milestones topic over MQTT to learn about new milestones without waiting for the
+    /// next poll.
+    #[cfg(feature = "mqtt")]
+    pub fn with_mqtt(mut self) -> Self {
+        self.use_mqtt = true;
+        self
+    }
+
+    /// Starts following the milestones.
+    ///
+    /// The stream never ends. Errors, e.g. when no node can be reached, are yielded and the failed milestone is tried
+    /// again on the next poll, after the poll interval.
+    pub async fn into_stream(mut self) -> Result<impl Stream<Item = Result<MilestoneUpdate>>> {
+        let mut next_index = self.start_index;
+
+        if let Some(db) = &mut self.state_db {
+            if let Some(bytes) = db.get(MILESTONE_FOLLOWER_STATE_KEY).await? {
+                next_index =
+                    u32::from_le_bytes(bytes.try_into().map_err(|_| {
+                        crate::Error::CorruptedDatabase("invalid milestone follower state".to_string())
+                    })?);
+            }
+        }
+
+        #[cfg(feature = "mqtt")]
+        let notify = if self.use_mqtt {
+            let notify = std::sync::Arc::new(tokio::sync::Notify::new());
+            let notify_ = std::sync::Arc::downgrade(&notify);
+            self.client
+                .subscribe(
+                    vec![crate::Topic::try_new("milestone-info/confirmed".to_string())?],
+                    move |_| {
+                        if let Some(notify) = notify_.upgrade() {
+                            notify.notify_one();
+                        }
+                    },
+                )
+                .await?;
+            Some(notify)
+        } else {
+            None
+        };
+
+        let state = FollowerState {
+            client: self.client,
+            next_index,
+            confirmed_index: 0,
+            persisted_index: None,
+            poll_interval: self.poll_interval,
+            state_db: self.state_db,
+            failed: false,
+            #[cfg(feature = "mqtt")]
+            notify,
+        };
+
+        Ok(futures::stream::unfold(state, |mut state| async move {
+            if state.failed {
+                state.wait().await;
+            }
+            let update = state.next().await;
+            state.failed = update.is_err();
+            Some((update, state))
+        }))
+    }
+}
+
+struct FollowerState {
+    client: Client,
+    next_index: u32,
+    // The latest confirmed milestone index known to the follower.
+    confirmed_index: u32,
+    persisted_index: Option<u32>,
+    poll_interval: Duration,
+    state_db: Option<Box<dyn DatabaseProvider + Send + Sync>>,
+    failed: bool,
+    #[cfg(feature = "mqtt")]
+    notify: Option<std::sync::Arc<tokio::sync::Notify>>,
+}
+
+impl FollowerState {
+    async fn next(&mut self) -> Result<MilestoneUpdate> {
+        if let Some(db) = &mut self.state_db {
+            if self.persisted_index != Some(self.next_index) {
+                db.insert(MILESTONE_FOLLOWER_STATE_KEY, &self.next_index.to_le_bytes())
+                    .await?;
+                self.persisted_index = Some(self.next_index);
+            }
+        }
+
+        loop {
+            if self.next_index <= self.confirmed_index {
+                let update = self.fetch(self.next_index).await?;
+                self.next_index += 1;
+                return Ok(update);
+            }
+
+            self.confirmed_index = self.client.get_info().await?.node_info.status.confirmed_milestone.index;
+
+            if self.next_index > self.confirmed_index {
+                self.wait().await;
+            }
+        }
+    }
+
+    async fn fetch(&self, index: u32) -> Result<MilestoneUpdate> {
+        let milestone = self.client.get_milestone_by_index(index).await?;
+        let utxo_changes = self.client.get_utxo_changes_by_index(index).await?;
+        let created_outputs = self.get_outputs(&utxo_changes.created_outputs).await?;
+        let consumed_outputs = self.get_outputs(&utxo_changes.consumed_outputs).await?;
+
+        Ok(MilestoneUpdate {
+            milestone,
+            utxo_changes,
+            created_outputs,
+            consumed_outputs,
+        })
+    }
+
+    async fn get_outputs(&self, output_ids: &[String]) -> Result<Vec<(OutputId, Output)>> {
+        let output_ids = output_ids
+            .iter()
+            .map(|output_id| OutputId::from_str(output_id))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let token_supply = self.client.get_token_supply().await?;
+
+        self.client
+            .get_outputs(output_ids.clone())
+            .await?
+            .iter()
+            .zip(output_ids)
+            .map(|(response, output_id)| Ok((output_id, Output::try_from_dto(&response.output, token_supply)?)))
+            .collect()
+    }
+
+    async fn wait(&self) {
+        #[cfg(feature = "mqtt")]
+        if let Some(notify) = &self.notify {
+            // Still poll in case the MQTT connection is lost.
+            let _ = tokio::time::timeout(self.poll_interval, notify.notified()).await;
+            return;
+        }
+
+        #[cfg(target_family = "wasm")]
+        gloo_timers::future::TimeoutFuture::new(self.poll_interval.as_millis().try_into().unwrap_or(u32::MAX)).await;
+
+        #[cfg(not(target_family = "wasm"))]
+        tokio::time::sleep(self.poll_interval).await;
+    }
+}
@@ -3,11 +3,23 @@
 
 //! High level APIs
 
+mod account;
 mod address;
+mod alias;
 mod block_builder;
+mod claim;
 mod consolidation;
+mod foundry;
+mod milestone_cone;
+mod milestone_follower;
+mod native_tokens;
+mod nft;
+mod payment_batcher;
 mod types;
 
-pub use self::{address::*, block_builder::*, types::*};
+pub use self::{
+    account::*, address::*, block_builder::*, claim::*, foundry::*, milestone_cone::*, milestone_follower::*,
+    native_tokens::*, nft::*, payment_batcher::*, types::*,
+};
 
 const ADDRESS_GAP_RANGE: u32 = 20;
@@ -0,0 +1,183 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Native token balances of addresses, with the foundries and metadata of the tokens.
+
+use std::collections::{BTreeMap, HashSet};
+
+use iota_types::block::{
+    address::Address,
+    output::{irc::Irc30Metadata, FoundryId, FoundryOutput, Output, OutputId, TokenId},
+};
+use primitive_types::U256;
+
+use crate::{node_api::indexer::query_parameters::QueryParameter, Client, Error, Result};
+
+/// The name, symbol and decimals of a native token, from the IRC30 metadata of its foundry.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NativeTokenMetadata {
+    /// The name of the token.
+    pub name: String,
+    /// The symbol of the token.
+    pub symbol: String,
+    /// The number of decimals of the token amounts.
+    pub decimals: u32,
+}
+
+impl NativeTokenMetadata {
+    /// Decodes the IRC30 metadata held by the metadata feature of a foundry, if any.
+    pub fn from_foundry(foundry: &FoundryOutput) -> Option<Self> {
+        foundry
+            .immutable_features()
+            .metadata()
+            .or_else(|| foundry.features().metadata())
+            .and_then(|metadata| Self::from_irc30(metadata.data()))
+    }
+
+    /// Decodes IRC30 JSON metadata, returning `None` if it doesn't follow the standard.
+    pub fn from_irc30(data: &[u8]) -> Option<Self> {
+        Irc30Metadata::from_json(data)
+            .ok()
+            .map(|metadata| Self::from(&metadata))
+    }
+}
+
+impl From<&Irc30Metadata> for NativeTokenMetadata {
+    fn from(metadata: &Irc30Metadata) -> Self {
+        Self {
+            name: metadata.name().to_string(),
+            symbol: metadata.symbol().to_string(),
+            decimals: metadata.decimals(),
+        }
+    }
+}
+
+/// The balance of a native token held by a set of addresses.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NativeTokenBalance {
+    /// The ID of the token.
+    pub token_id: TokenId,
+    /// The amount that can be spent now.
+    pub unlocked: U256,
+    /// The amount held by outputs that are time-locked, or that can be claimed now but still carry an expiration or
+    /// a storage deposit return unlock condition.
+    pub locked: U256,
+    /// The ID of the foundry output of the token, unless the foundry was destroyed.
+    pub foundry_output_id: Option<OutputId>,
+    /// The foundry output of the token, unless the foundry was destroyed.
+    pub foundry: Option<FoundryOutput>,
+    /// The IRC30 metadata of the token, if its foundry has some.
+    pub metadata: Option<NativeTokenMetadata>,
+}
+
+impl NativeTokenBalance {
+    /// Returns the sum of the unlocked and locked amounts.
+    pub fn total(&self) -> U256 {
+        self.unlocked.saturating_add(self.locked)
+    }
+}
+
+impl Client {
+    /// Returns the balances of the native tokens held by basic and NFT outputs that the given addresses can unlock
+    /// and by alias outputs they are the state controller of, ordered by token ID. Each token is resolved to its
+    /// foundry output and the IRC30 metadata of the foundry.
+    pub async fn native_token_balances(&self, addresses: &[Address]) -> Result<Vec<NativeTokenBalance>> {
+        let bech32_hrp = self.get_bech32_hrp().await?;
+        let token_supply = self.get_token_supply().await?;
+        let current_time = self.get_time_checked().await?;
+        let mut output_ids = HashSet::new();
+
+        for address in addresses {
+            let bech32_address = address.to_bech32(&bech32_hrp);
+
+            for query in [
+                QueryParameter::Address(bech32_address.clone()),
+                QueryParameter::ExpirationReturnAddress(bech32_address.clone()),
+            ] {
+                let query = vec![query, QueryParameter::HasNativeTokens(true)];
+
+                output_ids.extend(self.basic_output_ids(query.clone()).await?);
+                output_ids.extend(self.nft_output_ids(query).await?);
+            }
+
+            output_ids.extend(
+                self.alias_output_ids(vec![
+                    QueryParameter::StateController(bech32_address),
+                    QueryParameter::HasNativeTokens(true),
+                ])
+                .await?,
+            );
+        }
+
+        let mut balances = BTreeMap::<TokenId, (U256, U256)>::new();
+
+        for output_response in self.get_outputs(output_ids.into_iter().collect()).await? {
+            let output = Output::try_from_dto(&output_response.output, token_supply)?;
+            let Some(locked) = is_locked(&output, addresses, current_time) else {
+                continue;
+            };
+
+            for native_token in output
+                .native_tokens()
+                .into_iter()
+                .flat_map(|native_tokens| native_tokens.iter())
+            {
+                let (unlocked_amount, locked_amount) = balances.entry(*native_token.token_id()).or_default();
+                let amount = if locked { locked_amount } else { unlocked_amount };
+
+                *amount = amount.saturating_add(native_token.amount());
+            }
+        }
+
+        let mut native_token_balances = Vec::with_capacity(balances.len());
+
+        for (token_id, (unlocked, locked)) in balances {
+            let (foundry_output_id, foundry) = match self.foundry_output_id(FoundryId::new(*token_id)).await {
+                Ok(output_id) => {
+                    let output_response = self.get_output(&output_id).await?;
+
+                    match Output::try_from_dto(&output_response.output, token_supply)? {
+                        Output::Foundry(foundry) => (Some(output_id), Some(foundry)),
+                        _ => (Some(output_id), None),
+                    }
+                }
+                // The foundry was destroyed.
+                Err(Error::NotFound(_)) => (None, None),
+                Err(e) => return Err(e),
+            };
+
+            native_token_balances.push(NativeTokenBalance {
+                token_id,
+                unlocked,
+                locked,
+                foundry_output_id,
+                metadata: foundry.as_ref().and_then(NativeTokenMetadata::from_foundry),
+                foundry,
+            });
+        }
+
+        Ok(native_token_balances)
+    }
+}
+
+// Returns whether the native tokens of an output are locked, or `None` if none of the addresses can unlock the output.
+fn is_locked(output: &Output, addresses: &[Address], current_time: u32) -> Option<bool> {
+    let unlock_conditions = output.unlock_conditions()?;
+
+    if let Output::Alias(alias) = output {
+        return addresses.contains(alias.state_controller_address()).then_some(false);
+    }
+
+    let address = unlock_conditions.address()?.address();
+    let unlocking_address = unlock_conditions.locked_address(address, current_time);
+
+    if !addresses.contains(unlocking_address) {
+        return None;
+    }
+
+    // Once expired, the output belongs to the return address without any further condition.
+    let conditional = unlocking_address == address
+        && (unlock_conditions.expiration().is_some() || unlock_conditions.storage_deposit_return().is_some());
+
+    Some(unlock_conditions.is_time_locked(current_time) || conditional)
+}
@@ -0,0 +1,225 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Minting of NFT collections and transfer of NFTs.
+
+use iota_types::block::{
+    address::{Address, NftAddress},
+    output::{
+        feature::{Feature, IssuerFeature},
+        irc::Irc27Metadata,
+        unlock_condition::{AddressUnlockCondition, ExpirationUnlockCondition, UnlockCondition},
+        NftId, NftOutputBuilder, Output, OutputId, OUTPUT_COUNT_MAX,
+    },
+    payload::{transaction::TransactionId, Payload},
+    Block, BlockId,
+};
+use packable::PackableExt;
+
+use crate::{secret::SecretManager, Client, Result};
+
+/// The maximum number of NFTs created in a single transaction, leaving room for the collection NFT and a remainder
+/// output.
+pub const NFTS_PER_TRANSACTION_MAX: usize = OUTPUT_COUNT_MAX as usize - 2;
+
+// Room left in a block for the parts of a transaction other than its NFT outputs: the block and transaction headers
+// and the inputs with their unlocks.
+pub(crate) const TRANSACTION_OVERHEAD_LENGTH: usize = 4096;
+
+/// NFTs created by the same transaction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintedNfts {
+    /// The ID of the transaction.
+    pub transaction_id: TransactionId,
+    /// The ID of the block the transaction was sent with.
+    pub block_id: BlockId,
+    /// The IDs of the created NFTs, in the order of their metadata.
+    pub nft_ids: Vec<NftId>,
+}
+
+/// A transfer of an NFT to an address.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NftTransfer {
+    /// The ID of the NFT.
+    pub nft_id: NftId,
+    /// The address receiving the NFT.
+    pub address: Address,
+    /// The milestone timestamp from which the NFT returns to its current owner if the receiver didn't claim it.
+    pub expiration: Option<u32>,
+}
+
+impl NftTransfer {
+    /// Creates a new [`NftTransfer`] of an NFT to an address.
+    pub fn new(nft_id: NftId, address: Address) -> Self {
+        Self {
+            nft_id,
+            address,
+            expiration: None,
+        }
+    }
+
+    /// Gifts the NFT: it returns to its current owner if the receiver doesn't claim it before the milestone
+    /// timestamp.
+    pub fn with_expiration(mut self, expiration: u32) -> Self {
+        self.expiration.replace(expiration);
+        self
+    }
+}
+
+impl Client {
+    /// Mints an NFT per metadata, issued by a collection NFT and sent to the owner of the collection NFT.
+    ///
+    /// As many NFTs as fit are created per transaction. Each transaction spends the collection NFT, so the next one is
+    /// only sent once the previous one is included.
+    pub async fn mint_nfts(
+        &self,
+        secret_manager: &SecretManager,
+        collection_id: NftId,
+        metadata: Vec<Irc27Metadata>,
+    ) -> Result<Vec<MintedNfts>> {
+        let protocol_parameters = self.get_protocol_parameters().await?;
+        let token_supply = protocol_parameters.token_supply();
+        let collection = self.nft_output(collection_id).await?;
+        let owner = *collection.address();
+        let issuer = Feature::Issuer(IssuerFeature::new(Address::Nft(NftAddress::new(collection_id))));
+
+        let outputs = metadata
+            .iter()
+            .map(|metadata| {
+                Ok(NftOutputBuilder::new_with_minimum_storage_deposit(
+                    protocol_parameters.rent_structure().clone(),
+                    NftId::null(),
+                )?
+                .with_irc_27_metadata(metadata)?
+                .add_immutable_feature(issuer.clone())
+                .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(owner)))
+                .finish_output(token_supply)?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let chunks = chunk_nft_outputs(
+            outputs,
+            NFTS_PER_TRANSACTION_MAX,
+            collection.packed_len() + TRANSACTION_OVERHEAD_LENGTH,
+        );
+        let mut minted: Vec<MintedNfts> = Vec::with_capacity(chunks.len());
+
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            if let Some(previous) = minted.last() {
+                self.retry_until_included(&previous.block_id, None, None).await?;
+            }
+
+            let count = chunk.len();
+            let block = self
+                .block()
+                .with_secret_manager(secret_manager)
+                .with_outputs(chunk)?
+                .finish()
+                .await?;
+            let transaction_id = transaction_id(&block);
+
+            log::debug!("[mint_nfts] sent transaction {transaction_id} minting chunk {index}");
+
+            // The minted NFTs are the first outputs, in order.
+            minted.push(MintedNfts {
+                transaction_id,
+                block_id: block.id(),
+                nft_ids: (0..count as u16)
+                    .map(|index| Ok(NftId::from(&OutputId::new(transaction_id, index)?)))
+                    .collect::<Result<_>>()?,
+            });
+        }
+
+        Ok(minted)
+    }
+
+    /// Sends NFTs to addresses, as many per transaction as fit. NFTs sent with an expiration return to their current
+    /// owner if the receiver didn't claim them in time.
+    pub async fn send_nfts(&self, secret_manager: &SecretManager, transfers: Vec<NftTransfer>) -> Result<Vec<Block>> {
+        let protocol_parameters = self.get_protocol_parameters().await?;
+        let token_supply = protocol_parameters.token_supply();
+        let mut outputs = Vec::with_capacity(transfers.len());
+
+        for transfer in transfers {
+            let nft = self.nft_output(transfer.nft_id).await?;
+            let mut unlock_conditions = vec![UnlockCondition::Address(AddressUnlockCondition::new(transfer.address))];
+
+            if let Some(expiration) = transfer.expiration {
+                unlock_conditions.push(UnlockCondition::Expiration(ExpirationUnlockCondition::new(
+                    *nft.address(),
+                    expiration,
+                )?));
+            }
+
+            // The sender of a previous transfer doesn't unlock an input of this transaction.
+            let builder = NftOutputBuilder::from(&nft)
+                .with_nft_id(transfer.nft_id)
+                .with_unlock_conditions(unlock_conditions)
+                .with_features(
+                    nft.features()
+                        .iter()
+                        .filter(|feature| !matches!(feature, Feature::Sender(_)))
+                        .cloned(),
+                );
+            let minimum_amount = builder
+                .clone()
+                .with_minimum_storage_deposit(protocol_parameters.rent_structure().clone())
+                .finish_output(token_supply)?
+                .amount();
+
+            outputs.push(
+                builder
+                    .with_amount(nft.amount().max(minimum_amount))?
+                    .finish_output(token_supply)?,
+            );
+        }
+
+        let mut blocks = Vec::new();
+
+        for chunk in chunk_nft_outputs(outputs, OUTPUT_COUNT_MAX as usize - 1, TRANSACTION_OVERHEAD_LENGTH) {
+            blocks.push(
+                self.block()
+                    .with_secret_manager(secret_manager)
+                    .with_outputs(chunk)?
+                    .finish()
+                    .await?,
+            );
+        }
+
+        Ok(blocks)
+    }
+}
+
+// Splits NFT outputs into chunks with at most `count_max` outputs that fit in a block along with `reserved_length`
+// bytes.
+fn chunk_nft_outputs(outputs: Vec<Output>, count_max: usize, reserved_length: usize) -> Vec<Vec<Output>> {
+    let length_max = Block::LENGTH_MAX.saturating_sub(reserved_length);
+    let mut chunks = Vec::new();
+    let mut chunk = Vec::new();
+    let mut chunk_length = 0;
+
+    for output in outputs {
+        let length = output.packed_len();
+
+        if !chunk.is_empty() && (chunk.len() == count_max || chunk_length + length > length_max) {
+            chunks.push(std::mem::take(&mut chunk));
+            chunk_length = 0;
+        }
+
+        chunk_length += length;
+        chunk.push(output);
+    }
+
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+
+    chunks
+}
+
+pub(crate) fn transaction_id(block: &Block) -> TransactionId {
+    match block.payload() {
+        Some(Payload::Transaction(transaction)) => transaction.id(),
+        // Blocks sent with outputs always carry a transaction.
+        _ => unreachable!(),
+    }
+}
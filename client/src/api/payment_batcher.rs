@@ -0,0 +1,506 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Batch payments into as few transactions as possible.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    str::FromStr,
+    time::Duration,
+};
+
+use instant::Instant;
+use iota_types::{
+    api::dto::LedgerInclusionStateDto,
+    block::{
+        address::Address,
+        input::Input,
+        output::{
+            feature::Feature,
+            unlock_condition::{AddressUnlockCondition, UnlockCondition},
+            BasicOutputBuilder, NativeToken, Output, OutputId, OUTPUT_COUNT_MAX,
+        },
+        payload::{
+            transaction::{TransactionEssence, TransactionId},
+            Payload, TransactionPayload,
+        },
+        Block, BlockId,
+    },
+};
+use packable::PackableExt;
+
+use crate::{
+    api::nft::TRANSACTION_OVERHEAD_LENGTH, constants::DEFAULT_PAYMENT_BATCHER_MAX_DELAY, secret::SecretManager, Client,
+    Error, Result,
+};
+
+/// The maximum number of payments sent in a single transaction, leaving room for a remainder output.
+pub const PAYMENTS_PER_TRANSACTION_MAX: usize = OUTPUT_COUNT_MAX as usize - 1;
+
+/// The identifier of a payment queued in a [`PaymentBatcher`].
+pub type PaymentId = u64;
+
+/// A payment, sent as a basic output.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Payment {
+    /// The address receiving the payment.
+    pub address: Address,
+    /// The amount of the payment.
+    pub amount: u64,
+    /// The native tokens of the payment.
+    pub native_tokens: Vec<NativeToken>,
+    /// The features of the payment output, e.g. a tag or metadata.
+    pub features: Vec<Feature>,
+}
+
+impl Payment {
+    /// Creates a new [`Payment`] of an amount to an address.
+    pub fn new(address: Address, amount: u64) -> Self {
+        Self {
+            address,
+            amount,
+            native_tokens: Vec::new(),
+            features: Vec::new(),
+        }
+    }
+
+    /// Adds native tokens to the payment.
+    pub fn with_native_tokens(mut self, native_tokens: impl IntoIterator<Item = NativeToken>) -> Self {
+        self.native_tokens.extend(native_tokens);
+        self
+    }
+
+    /// Adds features to the payment output.
+    pub fn with_features(mut self, features: impl IntoIterator<Item = Feature>) -> Self {
+        self.features.extend(features);
+        self
+    }
+
+    fn to_output(&self, token_supply: u64) -> Result<Output> {
+        Ok(BasicOutputBuilder::new_with_amount(self.amount)?
+            .with_native_tokens(self.native_tokens.clone())
+            .with_features(self.features.clone())
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(self.address)))
+            .finish_output(token_supply)?)
+    }
+}
+
+/// The transaction and block a payment was sent with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PaymentReceipt {
+    /// The identifier of the payment.
+    pub payment_id: PaymentId,
+    /// The transaction the payment was sent with.
+    pub transaction_id: TransactionId,
+    /// The block the transaction was sent with.
+    pub block_id: BlockId,
+}
+
+/// The changes of the sent payments since the previous [`PaymentBatcher::poll_pending()`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PaymentBatchStatus {
+    /// The payments whose transaction got confirmed.
+    pub confirmed: Vec<PaymentReceipt>,
+    /// The payments whose transaction became conflicting and that were queued again.
+    pub requeued: Vec<PaymentId>,
+    /// The payments whose transaction was sent again in a new block, because the node asked for a reattachment or
+    /// doesn't know the block it was sent with.
+    pub reattached: Vec<PaymentReceipt>,
+}
+
+#[derive(Debug)]
+struct QueuedPayment {
+    id: PaymentId,
+    payment: Payment,
+    queued_at: Instant,
+}
+
+#[derive(Debug)]
+struct PendingTransaction {
+    transaction_id: TransactionId,
+    block_id: BlockId,
+    payload: TransactionPayload,
+    inputs: HashSet<OutputId>,
+    payments: Vec<QueuedPayment>,
+}
+
+// The state of a pending transaction learnt from the node.
+enum TransactionState {
+    Pending,
+    // The transaction was included by the given block, which may be a reattachment.
+    Confirmed(BlockId),
+    Conflicting,
+    Reattach,
+}
+
+/// Collects payments and sends them with as few transactions as [`OUTPUT_COUNT_MAX`] allows, once enough of them are
+/// queued or the oldest one waited long enough.
+///
+/// Sent payments stay pending until [`poll_pending()`](PaymentBatcher::poll_pending) learns that their transaction
+/// got confirmed, or queues them again if it became conflicting. Inputs spent by pending transactions are not
+/// selected again by later batches, so that a payment is never paid twice.
+#[must_use]
+pub struct PaymentBatcher<'a> {
+    client: &'a Client,
+    secret_manager: &'a SecretManager,
+    coin_type: Option<u32>,
+    account_index: Option<u32>,
+    flush_threshold: usize,
+    max_delay: Duration,
+    next_id: PaymentId,
+    queue: VecDeque<QueuedPayment>,
+    pending: Vec<PendingTransaction>,
+}
+
+impl Client {
+    /// Creates a [`PaymentBatcher`] sending payments with the funds of a secret manager.
+    pub fn payment_batcher<'a>(&'a self, secret_manager: &'a SecretManager) -> PaymentBatcher<'a> {
+        PaymentBatcher::new(self, secret_manager)
+    }
+}
+
+impl<'a> PaymentBatcher<'a> {
+    /// Creates a [`PaymentBatcher`] sending payments with the funds of a secret manager.
+    pub fn new(client: &'a Client, secret_manager: &'a SecretManager) -> Self {
+        Self {
+            client,
+            secret_manager,
+            coin_type: None,
+            account_index: None,
+            flush_threshold: PAYMENTS_PER_TRANSACTION_MAX,
+            max_delay: DEFAULT_PAYMENT_BATCHER_MAX_DELAY,
+            next_id: 0,
+            queue: VecDeque::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Sets the coin type of the addresses the funds are taken from.
+    pub fn with_coin_type(mut self, coin_type: u32) -> Self {
+        self.coin_type.replace(coin_type);
+        self
+    }
+
+    /// Sets the account index of the addresses the funds are taken from.
+    pub fn with_account_index(mut self, account_index: u32) -> Self {
+        self.account_index.replace(account_index);
+        self
+    }
+
+    /// Sets the number of queued payments from which the queue is due to be flushed, default is
+    /// [`PAYMENTS_PER_TRANSACTION_MAX`].
+    pub fn with_flush_threshold(mut self, flush_threshold: usize) -> Self {
+        self.flush_threshold = flush_threshold.max(1);
+        self
+    }
+
+    /// Sets the maximum time a payment waits in the queue before it is due to be flushed, default is 60 seconds.
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Queues a payment and returns its identifier.
+    pub fn enqueue(&mut self, payment: Payment) -> PaymentId {
+        let id = self.next_id;
+
+        self.next_id += 1;
+        self.queue.push_back(QueuedPayment {
+            id,
+            payment,
+            queued_at: Instant::now(),
+        });
+
+        id
+    }
+
+    /// Returns the number of queued payments.
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns the sent payments whose transaction is neither confirmed nor conflicting yet.
+    pub fn pending(&self) -> Vec<PaymentReceipt> {
+        self.pending
+            .iter()
+            .flat_map(|transaction| transaction.receipts())
+            .collect()
+    }
+
+    /// Checks whether the queue is due to be flushed, because the flush threshold is reached or the oldest payment
+    /// waited for the maximum delay.
+    pub fn is_due(&self) -> bool {
+        self.queue.len() >= self.flush_threshold
+            || self
+                .queue
+                .iter()
+                .map(|queued| queued.queued_at)
+                .min()
+                .is_some_and(|queued_at| queued_at.elapsed() >= self.max_delay)
+    }
+
+    /// Flushes the queue if it is due, see [`is_due()`](PaymentBatcher::is_due).
+    pub async fn flush_if_due(&mut self) -> Result<Vec<PaymentReceipt>> {
+        if self.is_due() {
+            self.flush().await
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Sends all the queued payments, at most [`PAYMENTS_PER_TRANSACTION_MAX`] and as many as fit in a block per
+    /// transaction, and returns their receipts.
+    ///
+    /// If a transaction can't be built, its payments and the following ones stay queued and the error is returned;
+    /// the payments sent before are pending, see [`pending()`](PaymentBatcher::pending). If posting the block of a
+    /// transaction fails, the node may still have processed it: its payments are kept pending under the block ID
+    /// rather than queued again, and [`poll_pending()`](PaymentBatcher::poll_pending) sends the transaction again if
+    /// the node doesn't know it.
+    pub async fn flush(&mut self) -> Result<Vec<PaymentReceipt>> {
+        let mut receipts = Vec::new();
+
+        if self.queue.is_empty() {
+            return Ok(receipts);
+        }
+
+        let token_supply = self.client.get_token_supply().await?;
+
+        while !self.queue.is_empty() {
+            let count = self.batch_size(token_supply);
+            let payments = self.queue.drain(..count).collect::<Vec<_>>();
+
+            // Nothing was posted yet, the payments can be queued again.
+            let (block, payload) = match self.build(&payments, token_supply).await {
+                Ok(built) => built,
+                Err(e) => {
+                    for payment in payments.into_iter().rev() {
+                        self.queue.push_front(payment);
+                    }
+
+                    return Err(e);
+                }
+            };
+            let mut transaction = PendingTransaction::new(block.id(), payload, payments);
+
+            match self.client.post_block_raw(&block).await {
+                Ok(block_id) => {
+                    // The block ID changes with remote PoW.
+                    transaction.block_id = block_id;
+                    receipts.extend(transaction.receipts());
+                    self.pending.push(transaction);
+                }
+                Err(e) => {
+                    log::warn!(
+                        "[PaymentBatcher] posting transaction {} failed, keeping its payments pending: {e}",
+                        transaction.transaction_id
+                    );
+                    self.pending.push(transaction);
+
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(receipts)
+    }
+
+    /// Checks the inclusion state of the pending transactions. Payments of confirmed transactions are no longer
+    /// pending and payments of conflicting transactions are queued again, ahead of the other queued payments.
+    ///
+    /// Transactions whose block should be reattached, or whose block is unknown to the node because posting it
+    /// failed, are sent again in a new block and stay pending. A reattachment that can't be posted is tried again by
+    /// the next poll.
+    pub async fn poll_pending(&mut self) -> Result<PaymentBatchStatus> {
+        let mut states = Vec::with_capacity(self.pending.len());
+
+        for transaction in &self.pending {
+            states.push(self.transaction_state(transaction).await?);
+        }
+
+        let mut status = PaymentBatchStatus::default();
+        let mut requeued = Vec::new();
+
+        for (mut transaction, state) in std::mem::take(&mut self.pending).into_iter().zip(states) {
+            match state {
+                TransactionState::Pending => self.pending.push(transaction),
+                TransactionState::Confirmed(block_id) => {
+                    transaction.block_id = block_id;
+                    status.confirmed.extend(transaction.receipts());
+                }
+                TransactionState::Conflicting => {
+                    log::debug!(
+                        "[PaymentBatcher] transaction {} is conflicting, queuing its payments again",
+                        transaction.transaction_id
+                    );
+                    status
+                        .requeued
+                        .extend(transaction.payments.iter().map(|queued| queued.id));
+                    requeued.extend(transaction.payments);
+                }
+                TransactionState::Reattach => {
+                    match self.reattach(&transaction.payload).await {
+                        Ok(block_id) => {
+                            log::debug!(
+                                "[PaymentBatcher] reattached transaction {} with block {block_id}",
+                                transaction.transaction_id
+                            );
+                            transaction.block_id = block_id;
+                            status.reattached.extend(transaction.receipts());
+                        }
+                        Err(e) => log::warn!(
+                            "[PaymentBatcher] reattaching transaction {} failed: {e}",
+                            transaction.transaction_id
+                        ),
+                    }
+                    self.pending.push(transaction);
+                }
+            }
+        }
+
+        for payment in requeued.into_iter().rev() {
+            self.queue.push_front(payment);
+        }
+
+        Ok(status)
+    }
+
+    async fn transaction_state(&self, transaction: &PendingTransaction) -> Result<TransactionState> {
+        let metadata = match self.client.get_block_metadata(&transaction.block_id).await {
+            Ok(metadata) => metadata,
+            // Posting the block failed before the node processed it, or the node changed it with remote PoW.
+            Err(Error::NotFound(_)) => {
+                return Ok(self
+                    .included_block_id(&transaction.transaction_id)
+                    .await?
+                    .map_or(TransactionState::Reattach, TransactionState::Confirmed));
+            }
+            Err(e) => return Err(e),
+        };
+
+        Ok(match metadata.ledger_inclusion_state {
+            Some(LedgerInclusionStateDto::Included) => TransactionState::Confirmed(transaction.block_id),
+            // A reattachment of the transaction conflicts with the block that included it.
+            Some(LedgerInclusionStateDto::Conflicting) => self
+                .included_block_id(&transaction.transaction_id)
+                .await?
+                .map_or(TransactionState::Conflicting, TransactionState::Confirmed),
+            _ if metadata.should_reattach.unwrap_or(false) => TransactionState::Reattach,
+            _ => TransactionState::Pending,
+        })
+    }
+
+    async fn included_block_id(&self, transaction_id: &TransactionId) -> Result<Option<BlockId>> {
+        match self.client.get_included_block_metadata(transaction_id).await {
+            Ok(metadata) if metadata.ledger_inclusion_state == Some(LedgerInclusionStateDto::Included) => {
+                Ok(Some(BlockId::from_str(&metadata.block_id)?))
+            }
+            Ok(_) | Err(Error::NotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Sending the same transaction again can't pay twice, as only one of its blocks can be included.
+    async fn reattach(&self, payload: &TransactionPayload) -> Result<BlockId> {
+        let block = self
+            .client
+            .finish_block_builder(None, Some(Payload::from(payload.clone())))
+            .await?;
+
+        self.client.post_block_raw(&block).await
+    }
+
+    // The number of queued payments sent by the next transaction, the payments with large features leave room for fewer
+    // payments.
+    fn batch_size(&self, token_supply: u64) -> usize {
+        let length_max = Block::LENGTH_MAX.saturating_sub(TRANSACTION_OVERHEAD_LENGTH);
+        let mut length = 0;
+        let mut count = 0;
+
+        for queued in self.queue.iter().take(PAYMENTS_PER_TRANSACTION_MAX) {
+            // Invalid payments are reported when building the transaction.
+            length += queued
+                .payment
+                .to_output(token_supply)
+                .map_or(0, |output| output.packed_len());
+
+            if count > 0 && length > length_max {
+                break;
+            }
+
+            count += 1;
+        }
+
+        count
+    }
+
+    async fn build(&self, payments: &[QueuedPayment], token_supply: u64) -> Result<(Block, TransactionPayload)> {
+        let outputs = payments
+            .iter()
+            .map(|queued| queued.payment.to_output(token_supply))
+            .collect::<Result<Vec<_>>>()?;
+        let forbidden_inputs = self
+            .pending
+            .iter()
+            .flat_map(|transaction| transaction.inputs.iter().copied())
+            .collect();
+
+        let mut builder = self
+            .client
+            .block()
+            .with_secret_manager(self.secret_manager)
+            .with_forbidden_inputs(forbidden_inputs);
+
+        if let Some(coin_type) = self.coin_type {
+            builder = builder.with_coin_type(coin_type);
+        }
+        if let Some(account_index) = self.account_index {
+            builder = builder.with_account_index(account_index);
+        }
+
+        let builder = builder.with_outputs(outputs)?;
+        let prepared_transaction_data = builder.prepare_transaction().await?;
+
+        match builder.sign_transaction(prepared_transaction_data).await? {
+            Payload::Transaction(payload) => {
+                let block = self
+                    .client
+                    .finish_block_builder(None, Some(Payload::Transaction(payload.clone())))
+                    .await?;
+
+                Ok((block, *payload))
+            }
+            // Signing a transaction always results in a transaction payload.
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl PendingTransaction {
+    fn new(block_id: BlockId, payload: TransactionPayload, payments: Vec<QueuedPayment>) -> Self {
+        let TransactionEssence::Regular(essence) = payload.essence();
+        let inputs = essence
+            .inputs()
+            .iter()
+            .filter_map(|input| match input {
+                Input::Utxo(input) => Some(*input.output_id()),
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            transaction_id: payload.id(),
+            block_id,
+            payload,
+            inputs,
+            payments,
+        }
+    }
+
+    fn receipts(&self) -> impl Iterator<Item = PaymentReceipt> + '_ {
+        self.payments.iter().map(|queued| PaymentReceipt {
+            payment_id: queued.id,
+            transaction_id: self.transaction_id,
+            block_id: self.block_id,
+        })
+    }
+}
@@ -12,12 +12,16 @@ use iota_types::block::{
         TransactionPayload,
     },
     protocol::ProtocolParameters,
+    unlock::{dto::UnlockDto, Unlock, Unlocks},
     DtoError,
 };
 
 use crate::{
     crypto::keys::slip10::Chain,
-    secret::types::{InputSigningData, InputSigningDataDto},
+    secret::{
+        reference_unlocks,
+        types::{InputSigningData, InputSigningDataDto},
+    },
 };
 
 /// Helper struct for offline signing
@@ -165,6 +169,147 @@ impl SignedTransactionData {
     }
 }
 
+/// Helper struct for signing a transaction whose inputs are controlled by multiple secret managers
+///
+/// Every secret manager provides the signature unlocks of the inputs it controls, see
+/// [`SecretManageExt::sign_transaction_essence_partially()`](crate::secret::SecretManageExt::sign_transaction_essence_partially),
+/// and the partially signed transactions are then merged until every input that needs a signature unlock has one.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PartiallySignedTransaction {
+    /// The prepared transaction data
+    #[serde(rename = "preparedTransactionData")]
+    pub prepared_transaction_data: PreparedTransactionData,
+    /// The signature unlocks provided so far, by input index
+    #[serde(rename = "signatureUnlocks")]
+    pub signature_unlocks: Vec<Option<Unlock>>,
+}
+
+/// PartiallySignedTransaction Dto
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PartiallySignedTransactionDto {
+    /// The prepared transaction data
+    #[serde(rename = "preparedTransactionData")]
+    pub prepared_transaction_data: PreparedTransactionDataDto,
+    /// The signature unlocks provided so far, by input index
+    #[serde(rename = "signatureUnlocks")]
+    pub signature_unlocks: Vec<Option<UnlockDto>>,
+}
+
+impl From<&PartiallySignedTransaction> for PartiallySignedTransactionDto {
+    fn from(value: &PartiallySignedTransaction) -> Self {
+        PartiallySignedTransactionDto {
+            prepared_transaction_data: PreparedTransactionDataDto::from(&value.prepared_transaction_data),
+            signature_unlocks: value
+                .signature_unlocks
+                .iter()
+                .map(|unlock| unlock.as_ref().map(UnlockDto::from))
+                .collect(),
+        }
+    }
+}
+
+impl PartiallySignedTransaction {
+    /// Creates a [`PartiallySignedTransaction`] without any signature unlock.
+    pub fn new(prepared_transaction_data: PreparedTransactionData) -> Self {
+        Self {
+            signature_unlocks: vec![None; prepared_transaction_data.inputs_data.len()],
+            prepared_transaction_data,
+        }
+    }
+
+    /// Returns the indexes of the inputs that still need a signature unlock.
+    pub fn missing_signatures(&self) -> crate::Result<Vec<usize>> {
+        Ok(reference_unlocks(&self.prepared_transaction_data.inputs_data)?
+            .iter()
+            .zip(&self.signature_unlocks)
+            .enumerate()
+            .filter(|(_, (reference_unlock, signature_unlock))| {
+                reference_unlock.is_none() && signature_unlock.is_none()
+            })
+            .map(|(index, _)| index)
+            .collect())
+    }
+
+    /// Adds the signature unlocks of another [`PartiallySignedTransaction`] of the same transaction.
+    pub fn merge(&mut self, other: &Self) -> crate::Result<()> {
+        if self.prepared_transaction_data.essence != other.prepared_transaction_data.essence
+            || self.signature_unlocks.len() != other.signature_unlocks.len()
+        {
+            return Err(crate::Error::PartiallySignedTransactionMismatch);
+        }
+
+        for (index, (signature_unlock, other_signature_unlock)) in self
+            .signature_unlocks
+            .iter_mut()
+            .zip(&other.signature_unlocks)
+            .enumerate()
+        {
+            match (&signature_unlock, other_signature_unlock) {
+                (_, None) => {}
+                (None, Some(_)) => *signature_unlock = other_signature_unlock.clone(),
+                (Some(unlock), Some(other_unlock)) => {
+                    if unlock != other_unlock {
+                        return Err(crate::Error::ConflictingSignatureUnlocks(index));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Combines the signature unlocks with the unlocks referencing them into the final [`Unlocks`].
+    pub fn unlocks(&self) -> crate::Result<Unlocks> {
+        let blocks = reference_unlocks(&self.prepared_transaction_data.inputs_data)?
+            .into_iter()
+            .zip(&self.signature_unlocks)
+            .enumerate()
+            .map(|(index, (reference_unlock, signature_unlock))| {
+                reference_unlock
+                    .or_else(|| signature_unlock.clone())
+                    .ok_or(crate::Error::MissingSignatureUnlock(index))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(Unlocks::new(blocks)?)
+    }
+
+    /// Conversion from [`PartiallySignedTransactionDto`] to [`PartiallySignedTransaction`].
+    pub fn try_from_dto(
+        value: &PartiallySignedTransactionDto,
+        protocol_parameters: &ProtocolParameters,
+    ) -> Result<Self, DtoError> {
+        Ok(PartiallySignedTransaction {
+            prepared_transaction_data: PreparedTransactionData::try_from_dto(
+                &value.prepared_transaction_data,
+                protocol_parameters,
+            )?,
+            signature_unlocks: signature_unlocks_from_dto(&value.signature_unlocks)?,
+        })
+    }
+
+    /// Unverified conversion from [`PartiallySignedTransactionDto`] to [`PartiallySignedTransaction`].
+    pub fn try_from_dto_unverified(value: &PartiallySignedTransactionDto) -> Result<Self, DtoError> {
+        Ok(PartiallySignedTransaction {
+            prepared_transaction_data: PreparedTransactionData::try_from_dto_unverified(
+                &value.prepared_transaction_data,
+            )?,
+            signature_unlocks: signature_unlocks_from_dto(&value.signature_unlocks)?,
+        })
+    }
+}
+
+fn signature_unlocks_from_dto(signature_unlocks: &[Option<UnlockDto>]) -> Result<Vec<Option<Unlock>>, DtoError> {
+    signature_unlocks
+        .iter()
+        .map(|unlock| match unlock {
+            Some(unlock @ UnlockDto::Signature(_)) => Ok(Some(Unlock::try_from(unlock)?)),
+            Some(_) => Err(DtoError::InvalidField("signatureUnlocks")),
+            None => Ok(None),
+        })
+        .collect()
+}
+
 /// Data for a remainder output, used for ledger nano
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct RemainderData {
@@ -20,10 +20,15 @@ use crate::node_api::mqtt::{BrokerOptions, MqttEvent};
 use crate::{
     client::Client,
     constants::{DEFAULT_API_TIMEOUT, DEFAULT_REMOTE_POW_API_TIMEOUT, DEFAULT_TIPS_INTERVAL},
+    db::{DatabaseProvider, ResponseCache},
     error::Result,
     node_manager::{
         builder::validate_url,
         node::{Node, NodeAuth},
+        quorum::QuorumComparator,
+        rate_limit::RateLimit,
+        retry::{CircuitBreaker, RetryPolicy},
+        scoreboard::{NodeScoreboard, NodeSelectionPolicy},
     },
 };
 
@@ -130,6 +135,9 @@ pub struct ClientBuilder {
     /// The amount of threads to be used for proof of work
     #[serde(rename = "powWorkerCount", default)]
     pub pow_worker_count: Option<usize>,
+    /// Cache of immutable node data, it can't be serialized and needs to be set again after deserialization
+    #[serde(skip)]
+    pub response_cache: Option<ResponseCache>,
 }
 
 fn default_api_timeout() -> Duration {
@@ -163,6 +171,7 @@ impl Default for ClientBuilder {
             api_timeout: DEFAULT_API_TIMEOUT,
             remote_pow_timeout: DEFAULT_REMOTE_POW_API_TIMEOUT,
             pow_worker_count: None,
+            response_cache: None,
         }
     }
 }
@@ -247,6 +256,42 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets the policy ordering the nodes a request is sent to, after the primary node. The default policy prefers
+    /// the nodes with the best [score](crate::node_manager::scoreboard::NodeScore::score).
+    pub fn with_node_selection_policy(mut self, node_selection_policy: NodeSelectionPolicy) -> Self {
+        self.node_manager_builder = self
+            .node_manager_builder
+            .with_node_selection_policy(node_selection_policy);
+        self
+    }
+
+    /// Sets how a failed request is retried on the same node before the next node is tried, by default it isn't.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.node_manager_builder = self.node_manager_builder.with_retry_policy(retry_policy);
+        self
+    }
+
+    /// Skips the nodes with too many consecutive failed requests until a cool-down period passed.
+    pub fn with_circuit_breaker(mut self, circuit_breaker: CircuitBreaker) -> Self {
+        self.node_manager_builder = self.node_manager_builder.with_circuit_breaker(circuit_breaker);
+        self
+    }
+
+    /// Sets the maximum number of requests sent at once to a node. Bulk requests like
+    /// [`get_outputs()`](crate::Client::get_outputs) are scheduled within this limit.
+    pub fn with_max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.node_manager_builder = self
+            .node_manager_builder
+            .with_max_concurrent_requests(max_concurrent_requests);
+        self
+    }
+
+    /// Sets the token bucket rate limit of the requests to a node.
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.node_manager_builder = self.node_manager_builder.with_rate_limit(rate_limit);
+        self
+    }
+
     /// Ignores the node health status.
     /// Every node will be considered healthy and ready to use.
     pub fn with_ignore_node_health(mut self) -> Self {
@@ -273,6 +318,20 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets how the responses of the nodes to an endpoint are compared for a quorum, instead of comparing them as a
+    /// whole. Endpoints are paths like `api/core/v2/outputs/{outputId}/metadata`, segments in braces matching any
+    /// value. By default, whether a block should be promoted or reattached isn't compared.
+    ///
+    /// Output metadata is only requested with a quorum once a comparator is set for
+    /// `api/core/v2/outputs/{outputId}/metadata`, e.g. `IgnoreFields(vec!["/ledgerIndex".to_string()])`, as the ledger
+    /// index differs between synced nodes.
+    pub fn with_quorum_comparator(mut self, endpoint: &str, comparator: impl QuorumComparator + 'static) -> Self {
+        self.node_manager_builder = self
+            .node_manager_builder
+            .with_quorum_comparator(endpoint, Arc::new(comparator));
+        self
+    }
+
     /// Sets the MQTT broker options.
     #[cfg(feature = "mqtt")]
     pub fn with_mqtt_broker_options(mut self, options: BrokerOptions) -> Self {
@@ -323,10 +382,18 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets a cache for immutable node data like blocks, milestones and spent outputs, so that repeated requests are
+    /// served from the given database instead of the nodes. Database errors are logged, the nodes are requested instead.
+    pub fn with_response_cache(mut self, db: impl DatabaseProvider + Send + Sync + 'static) -> Self {
+        self.response_cache.replace(ResponseCache::new(db));
+        self
+    }
+
     /// Build the Client instance.
     pub fn finish(self) -> Result<Client> {
         let network_info = Arc::new(RwLock::new(self.network_info));
         let healthy_nodes = Arc::new(RwLock::new(HashMap::new()));
+        let scoreboard = NodeScoreboard::default();
 
         #[cfg(not(target_family = "wasm"))]
         let (runtime, sync_handle) = {
@@ -340,6 +407,7 @@ impl ClientBuilder {
 
             let healthy_nodes_ = healthy_nodes.clone();
             let network_info_ = network_info.clone();
+            let scoreboard_ = scoreboard.clone();
 
             let (runtime, sync_handle) = std::thread::spawn(move || {
                 let runtime = Runtime::new().expect("failed to create Tokio runtime");
@@ -347,6 +415,7 @@ impl ClientBuilder {
                     &healthy_nodes_,
                     &nodes,
                     &network_info_,
+                    &scoreboard_,
                     self.node_manager_builder.ignore_node_health,
                 )) {
                     panic!("failed to sync nodes: {e:?}");
@@ -357,6 +426,7 @@ impl ClientBuilder {
                     nodes,
                     self.node_manager_builder.node_sync_interval,
                     network_info_,
+                    scoreboard_,
                     self.node_manager_builder.ignore_node_health,
                 );
                 (runtime, sync_handle)
@@ -369,7 +439,7 @@ impl ClientBuilder {
         #[cfg(feature = "mqtt")]
         let (mqtt_event_tx, mqtt_event_rx) = tokio::sync::watch::channel(MqttEvent::Connected);
         let client = Client {
-            node_manager: self.node_manager_builder.build(healthy_nodes),
+            node_manager: self.node_manager_builder.build(healthy_nodes, scoreboard),
             #[cfg(not(target_family = "wasm"))]
             runtime,
             #[cfg(not(target_family = "wasm"))]
@@ -386,6 +456,7 @@ impl ClientBuilder {
             api_timeout: self.api_timeout,
             remote_pow_timeout: self.remote_pow_timeout,
             pow_worker_count: self.pow_worker_count,
+            response_cache: self.response_cache,
         };
         Ok(client)
     }
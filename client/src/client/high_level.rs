@@ -7,13 +7,13 @@ use iota_types::{
     api::{dto::LedgerInclusionStateDto, response::OutputWithMetadataResponse},
     block::{
         input::{Input, UtxoInput, INPUT_COUNT_MAX},
-        output::{Output, OutputId},
+        output::{AliasId, AliasOutput, FoundryId, FoundryOutput, NftId, NftOutput, Output, OutputId},
         parent::Parents,
         payload::{
             transaction::{TransactionEssence, TransactionId},
             Payload,
         },
-        Block, BlockId,
+        Block, BlockId, Error as BlockError,
     },
 };
 
@@ -81,10 +81,20 @@ impl Client {
         }
 
         // Use `get_block()` API to get the `Block`.
+        #[cfg(target_family = "wasm")]
         for block_id in block_ids_to_query {
             let block = self.get_block(&block_id).await?;
             blocks.push(block);
         }
+
+        #[cfg(not(target_family = "wasm"))]
+        blocks.extend(
+            self.parallel_requests(block_ids_to_query, |client, block_id| async move {
+                client.get_block(&block_id).await
+            })
+            .await?,
+        );
+
         Ok(blocks)
     }
 
@@ -277,6 +287,37 @@ impl Client {
         Ok(output_responses.clone())
     }
 
+    /// Get the current output of an alias.
+    pub(crate) async fn alias_output(&self, alias_id: AliasId) -> Result<AliasOutput> {
+        match self.chain_output(self.alias_output_id(alias_id).await?).await? {
+            Output::Alias(alias) => Ok(alias),
+            output => Err(BlockError::InvalidOutputKind(output.kind()).into()),
+        }
+    }
+
+    /// Get the current output of a foundry.
+    pub(crate) async fn foundry_output(&self, foundry_id: FoundryId) -> Result<FoundryOutput> {
+        match self.chain_output(self.foundry_output_id(foundry_id).await?).await? {
+            Output::Foundry(foundry) => Ok(foundry),
+            output => Err(BlockError::InvalidOutputKind(output.kind()).into()),
+        }
+    }
+
+    /// Get the current output of an NFT.
+    pub(crate) async fn nft_output(&self, nft_id: NftId) -> Result<NftOutput> {
+        match self.chain_output(self.nft_output_id(nft_id).await?).await? {
+            Output::Nft(nft) => Ok(nft),
+            output => Err(BlockError::InvalidOutputKind(output.kind()).into()),
+        }
+    }
+
+    async fn chain_output(&self, output_id: OutputId) -> Result<Output> {
+        Ok(Output::try_from_dto(
+            &self.get_output(&output_id).await?.output,
+            self.get_token_supply().await?,
+        )?)
+    }
+
     /// Reattaches blocks for provided block id. Blocks can be reattached only if they are valid and haven't been
     /// confirmed for a while.
     pub async fn reattach(&self, block_id: &BlockId) -> Result<(BlockId, Block)> {
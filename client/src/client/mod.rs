@@ -52,6 +52,8 @@ pub struct Client {
     #[allow(dead_code)] // not used for wasm
     /// pow_worker_count for local PoW.
     pub(crate) pow_worker_count: Option<usize>,
+    /// Cache of immutable node data.
+    pub(crate) response_cache: Option<crate::db::ResponseCache>,
 }
 
 impl std::fmt::Debug for Client {
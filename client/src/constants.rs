@@ -20,6 +20,10 @@ pub(crate) const DEFAULT_QUORUM_THRESHOLD: usize = 66;
 pub(crate) const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 #[cfg(not(target_family = "wasm"))]
 pub(crate) const MAX_PARALLEL_API_REQUESTS: usize = 100;
+/// Interval in which a milestone follower checks for new milestones
+pub(crate) const DEFAULT_MILESTONE_FOLLOWER_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Maximum time a payment waits in a payment batcher before the queue is due to be flushed
+pub(crate) const DEFAULT_PAYMENT_BATCHER_MAX_DELAY: Duration = Duration::from_secs(60);
 /// Max allowed difference between the local time and latest milestone time, 5 minutes in seconds
 pub(crate) const FIVE_MINUTES_IN_SECONDS: u32 = 300;
 
@@ -0,0 +1,194 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A cache of immutable node data, stored in a [`DatabaseProvider`].
+
+use std::sync::Arc;
+
+use futures::lock::Mutex;
+use iota_types::{
+    api::response::OutputMetadataResponse,
+    block::{
+        output::{Output, OutputId},
+        payload::{
+            milestone::{MilestoneId, MilestonePayload},
+            transaction::TransactionId,
+        },
+        Block, BlockId,
+    },
+};
+use packable::{error::UnpackErrorExt, PackableExt};
+
+use super::DatabaseProvider;
+use crate::Result;
+
+// Key prefixes of the different kinds of cached data.
+const BLOCK_KEY_PREFIX: u8 = 0;
+const MILESTONE_KEY_PREFIX: u8 = 1;
+const MILESTONE_INDEX_KEY_PREFIX: u8 = 2;
+const INCLUDED_BLOCK_KEY_PREFIX: u8 = 3;
+const OUTPUT_KEY_PREFIX: u8 = 4;
+const SPENT_OUTPUT_METADATA_KEY_PREFIX: u8 = 5;
+
+/// A cache of node data that can't change anymore, backed by a [`DatabaseProvider`].
+///
+/// Blocks, milestones, included blocks of transactions and outputs are stored as packed bytes, keyed by their id.
+/// Output metadata is only cached once the output is spent, as it is still subject to change before that.
+#[derive(Clone)]
+pub struct ResponseCache {
+    db: Arc<Mutex<Box<dyn DatabaseProvider + Send + Sync>>>,
+}
+
+impl ResponseCache {
+    /// Creates a new [`ResponseCache`] storing its data in the given database.
+    pub fn new(db: impl DatabaseProvider + Send + Sync + 'static) -> Self {
+        Self {
+            db: Arc::new(Mutex::new(Box::new(db))),
+        }
+    }
+
+    /// Gets a cached block.
+    pub(crate) async fn get_block(&self, block_id: &BlockId) -> Result<Option<Block>> {
+        self.get(&key(BLOCK_KEY_PREFIX, block_id.as_ref()))
+            .await?
+            .map(|bytes| Ok(Block::unpack_unverified(bytes)?))
+            .transpose()
+    }
+
+    /// Caches a block.
+    pub(crate) async fn insert_block(&self, block: &Block) -> Result<()> {
+        self.insert(&key(BLOCK_KEY_PREFIX, block.id().as_ref()), &block.pack_to_vec())
+            .await
+    }
+
+    /// Gets a cached milestone.
+    pub(crate) async fn get_milestone(&self, milestone_id: &MilestoneId) -> Result<Option<MilestonePayload>> {
+        self.get(&key(MILESTONE_KEY_PREFIX, milestone_id.as_ref()))
+            .await?
+            .map(|bytes| Ok(MilestonePayload::unpack_unverified(bytes)?))
+            .transpose()
+    }
+
+    /// Gets a cached milestone by its index.
+    pub(crate) async fn get_milestone_by_index(&self, index: u32) -> Result<Option<MilestonePayload>> {
+        match self.get(&key(MILESTONE_INDEX_KEY_PREFIX, &index.to_le_bytes())).await? {
+            Some(bytes) => {
+                self.get_milestone(&MilestoneId::unpack_unverified(bytes).coerce::<iota_types::block::Error>()?)
+                    .await
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Caches a milestone, also making it available by its index.
+    pub(crate) async fn insert_milestone(&self, milestone: &MilestonePayload) -> Result<()> {
+        let milestone_id = milestone.id();
+
+        self.insert(
+            &key(MILESTONE_KEY_PREFIX, milestone_id.as_ref()),
+            &milestone.pack_to_vec(),
+        )
+        .await?;
+        self.insert(
+            &key(MILESTONE_INDEX_KEY_PREFIX, &milestone.essence().index().to_le_bytes()),
+            milestone_id.as_ref(),
+        )
+        .await
+    }
+
+    /// Gets the cached block that was included in the ledger for a given transaction.
+    pub(crate) async fn get_included_block(&self, transaction_id: &TransactionId) -> Result<Option<Block>> {
+        match self
+            .get(&key(INCLUDED_BLOCK_KEY_PREFIX, transaction_id.as_ref()))
+            .await?
+        {
+            Some(bytes) => {
+                self.get_block(&BlockId::unpack_unverified(bytes).coerce::<iota_types::block::Error>()?)
+                    .await
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Caches the block that was included in the ledger for a given transaction.
+    pub(crate) async fn insert_included_block(&self, transaction_id: &TransactionId, block: &Block) -> Result<()> {
+        self.insert_block(block).await?;
+        self.insert(
+            &key(INCLUDED_BLOCK_KEY_PREFIX, transaction_id.as_ref()),
+            block.id().as_ref(),
+        )
+        .await
+    }
+
+    /// Gets a cached output.
+    pub(crate) async fn get_output(&self, output_id: &OutputId) -> Result<Option<Output>> {
+        self.get(&key(OUTPUT_KEY_PREFIX, &output_id.pack_to_vec()))
+            .await?
+            .map(|bytes| Ok(Output::unpack_unverified(bytes)?))
+            .transpose()
+    }
+
+    /// Caches an output.
+    pub(crate) async fn insert_output(&self, output_id: &OutputId, output: &Output) -> Result<()> {
+        self.insert(&key(OUTPUT_KEY_PREFIX, &output_id.pack_to_vec()), &output.pack_to_vec())
+            .await
+    }
+
+    /// Gets the cached metadata of a spent output.
+    pub(crate) async fn get_spent_output_metadata(
+        &self,
+        output_id: &OutputId,
+    ) -> Result<Option<OutputMetadataResponse>> {
+        self.get(&key(SPENT_OUTPUT_METADATA_KEY_PREFIX, &output_id.pack_to_vec()))
+            .await?
+            .map(|bytes| Ok(serde_json::from_slice(&bytes)?))
+            .transpose()
+    }
+
+    /// Caches the metadata of an output if it is spent, the metadata of unspent outputs is left out as it can still
+    /// change.
+    pub(crate) async fn insert_output_metadata(
+        &self,
+        output_id: &OutputId,
+        metadata: &OutputMetadataResponse,
+    ) -> Result<()> {
+        if metadata.is_spent {
+            self.insert(
+                &key(SPENT_OUTPUT_METADATA_KEY_PREFIX, &output_id.pack_to_vec()),
+                &serde_json::to_vec(metadata)?,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, k: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.db.lock().await.get(k).await
+    }
+
+    async fn insert(&self, k: &[u8], v: &[u8]) -> Result<()> {
+        self.db.lock().await.insert(k, v).await.map(|_| ())
+    }
+}
+
+impl std::fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseCache").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for ResponseCache {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.db, &other.db)
+    }
+}
+
+impl Eq for ResponseCache {}
+
+fn key(prefix: u8, id: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(1 + id.len());
+    key.push(prefix);
+    key.extend_from_slice(id);
+    key
+}
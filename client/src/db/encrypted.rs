@@ -0,0 +1,120 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A `DatabaseProvider` wrapper encrypting the values at rest.
+
+use async_trait::async_trait;
+use crypto::ciphers::{chacha::XChaCha20Poly1305, traits::Aead};
+use zeroize::Zeroizing;
+
+use super::{DatabaseProvider, WriteBatch, WriteOperation};
+use crate::{Error, Result};
+
+/// A [`DatabaseProvider`] wrapper encrypting the values with XChaCha20-Poly1305 before they reach the inner database.
+///
+/// Keys are stored in plain text so that prefix scans keep working; they are authenticated along with the values so
+/// that a value can't be moved under another key.
+pub struct EncryptedDatabaseProvider<D> {
+    db: D,
+    key: Zeroizing<[u8; 32]>,
+}
+
+impl<D> EncryptedDatabaseProvider<D> {
+    /// Wraps `db`, encrypting its values with `key`.
+    pub fn new(db: D, key: [u8; 32]) -> Self {
+        Self {
+            db,
+            key: Zeroizing::new(key),
+        }
+    }
+
+    /// Returns the inner database.
+    pub fn into_inner(self) -> D {
+        self.db
+    }
+
+    fn encrypt(&self, k: &[u8], v: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0; XChaCha20Poly1305::NONCE_LENGTH];
+        crypto::utils::rand::fill(&mut nonce)?;
+
+        let mut tag = [0; XChaCha20Poly1305::TAG_LENGTH];
+        let mut ciphertext = vec![0; v.len()];
+        XChaCha20Poly1305::try_encrypt(self.key.as_ref(), &nonce, k, v, &mut ciphertext, &mut tag)?;
+
+        Ok([nonce.as_ref(), tag.as_ref(), &ciphertext].concat())
+    }
+
+    fn decrypt(&self, k: &[u8], encrypted: &[u8]) -> Result<Vec<u8>> {
+        let header_length = XChaCha20Poly1305::NONCE_LENGTH + XChaCha20Poly1305::TAG_LENGTH;
+
+        if encrypted.len() < header_length {
+            return Err(Error::CorruptedDatabase("encrypted value too short".to_string()));
+        }
+
+        let (nonce, tag) = encrypted[..header_length].split_at(XChaCha20Poly1305::NONCE_LENGTH);
+        let ciphertext = &encrypted[header_length..];
+        let mut plaintext = vec![0; ciphertext.len()];
+        XChaCha20Poly1305::try_decrypt(self.key.as_ref(), nonce, k, &mut plaintext, ciphertext, tag)?;
+
+        Ok(plaintext)
+    }
+}
+
+impl<D: std::fmt::Debug> std::fmt::Debug for EncryptedDatabaseProvider<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptedDatabaseProvider")
+            .field("db", &self.db)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<D: DatabaseProvider + Send + Sync> DatabaseProvider for EncryptedDatabaseProvider<D> {
+    async fn get(&mut self, k: &[u8]) -> Result<Option<Vec<u8>>> {
+        match self.db.get(k).await? {
+            Some(v) => Ok(Some(self.decrypt(k, &v)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn insert(&mut self, k: &[u8], v: &[u8]) -> Result<Option<Vec<u8>>> {
+        let encrypted = self.encrypt(k, v)?;
+
+        match self.db.insert(k, &encrypted).await? {
+            Some(previous) => Ok(Some(self.decrypt(k, &previous)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn delete(&mut self, k: &[u8]) -> Result<Option<Vec<u8>>> {
+        match self.db.delete(k).await? {
+            Some(previous) => Ok(Some(self.decrypt(k, &previous)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn scan_prefix(&mut self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        self.db
+            .scan_prefix(prefix)
+            .await?
+            .into_iter()
+            .map(|(k, v)| {
+                let v = self.decrypt(&k, &v)?;
+                Ok((k, v))
+            })
+            .collect()
+    }
+
+    async fn write_batch(&mut self, batch: WriteBatch) -> Result<()> {
+        let mut encrypted = WriteBatch::new();
+
+        for operation in batch {
+            match operation {
+                WriteOperation::Insert(k, v) => encrypted.insert(&k, &self.encrypt(&k, &v)?),
+                WriteOperation::Delete(k) => encrypted.delete(&k),
+            };
+        }
+
+        self.db.write_batch(encrypted).await
+    }
+}
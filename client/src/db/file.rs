@@ -0,0 +1,300 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A `DatabaseProvider` persisting its records in an append-only log file.
+//!
+//! The file starts with [`MAGIC`] and is followed by frames, one per write. A frame is made of the length of its
+//! payload as a little-endian `u32`, the Blake2b-256 checksum of the payload and the payload itself, a list of write
+//! operations. A frame that was only partially written, e.g. because of a crash, is discarded when the file is opened,
+//! which makes every write, including batches, atomic.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use crypto::hashes::{blake2b::Blake2b256, Digest};
+
+use super::{DatabaseProvider, MemoryDatabaseProvider, WriteBatch, WriteOperation};
+use crate::{Error, Result};
+
+/// Identifies the file format and its version.
+const MAGIC: &[u8; 8] = b"iotadb\0\x01";
+const CHECKSUM_LENGTH: usize = 32;
+const FRAME_HEADER_LENGTH: usize = 4 + CHECKSUM_LENGTH;
+const INSERT_TAG: u8 = 0;
+const DELETE_TAG: u8 = 1;
+/// The log is compacted once it holds at least this many records and twice as many records as there are live ones.
+const COMPACTION_MIN_RECORDS: usize = 1_000;
+
+/// A [`DatabaseProvider`] persisting its records in an append-only log file.
+///
+/// All the records are also kept in memory. Overwritten and deleted records stay in the file until it gets compacted,
+/// which happens automatically when they outnumber the live ones, or manually with
+/// [`compact()`](FileDatabaseProvider::compact).
+#[derive(Debug)]
+pub struct FileDatabaseProvider {
+    path: PathBuf,
+    file: File,
+    file_length: u64,
+    memory: MemoryDatabaseProvider,
+    log_records: usize,
+}
+
+impl FileDatabaseProvider {
+    /// Opens the database file at `path`, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        if bytes.is_empty() {
+            file.write_all(MAGIC)?;
+            file.sync_data()?;
+
+            return Ok(Self {
+                path,
+                file,
+                file_length: MAGIC.len() as u64,
+                memory: MemoryDatabaseProvider::new(),
+                log_records: 0,
+            });
+        }
+
+        if !bytes.starts_with(MAGIC) {
+            return Err(Error::CorruptedDatabase(format!(
+                "{} is not a database file",
+                path.display()
+            )));
+        }
+
+        let mut memory = MemoryDatabaseProvider::new();
+        let mut log_records = 0;
+        let mut offset = MAGIC.len();
+
+        while offset < bytes.len() {
+            match read_frame(&bytes[offset..])? {
+                Some((operations, frame_length)) => {
+                    log_records += operations.len();
+                    for operation in operations {
+                        memory.apply(operation);
+                    }
+                    offset += frame_length;
+                }
+                None => {
+                    // The last write was interrupted, it never happened.
+                    file.set_len(offset as u64)?;
+                    file.sync_data()?;
+                    break;
+                }
+            }
+        }
+
+        Ok(Self {
+            path,
+            file,
+            file_length: offset as u64,
+            memory,
+            log_records,
+        })
+    }
+
+    /// Rewrites the file with the live records only.
+    pub fn compact(&mut self) -> Result<()> {
+        let mut compacted_path = self.path.clone().into_os_string();
+        compacted_path.push(".compact");
+        let compacted_path = PathBuf::from(compacted_path);
+
+        let mut bytes = MAGIC.to_vec();
+        for (k, v) in self.memory.records() {
+            bytes.extend(frame(&[WriteOperation::Insert(k.clone(), v.clone())]));
+        }
+
+        let mut compacted = File::create(&compacted_path)?;
+        compacted.write_all(&bytes)?;
+        compacted.sync_all()?;
+        fs::rename(&compacted_path, &self.path)?;
+
+        self.file = OpenOptions::new().read(true).append(true).open(&self.path)?;
+        self.file_length = bytes.len() as u64;
+        self.log_records = self.memory.len();
+
+        Ok(())
+    }
+
+    /// Appends the operations to the log, then applies them to the in-memory records.
+    fn write(&mut self, operations: Vec<WriteOperation>) -> Result<Vec<Option<Vec<u8>>>> {
+        if operations.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let frame = frame(&operations);
+
+        if let Err(e) = self.file.write_all(&frame).and_then(|_| self.file.sync_data()) {
+            // Remove what may have been written of the frame so that the following writes are still readable.
+            self.file.set_len(self.file_length).ok();
+            return Err(e.into());
+        }
+
+        self.file_length += frame.len() as u64;
+        self.log_records += operations.len();
+
+        let previous = operations
+            .into_iter()
+            .map(|operation| self.memory.apply(operation))
+            .collect();
+
+        // The write is durable at this point, a failed compaction is retried by the next write.
+        if self.log_records >= COMPACTION_MIN_RECORDS && self.log_records > 2 * self.memory.len() {
+            if let Err(e) = self.compact() {
+                log::warn!("couldn't compact {}: {e}", self.path.display());
+            }
+        }
+
+        Ok(previous)
+    }
+}
+
+#[async_trait]
+impl DatabaseProvider for FileDatabaseProvider {
+    async fn get(&mut self, k: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.memory.records().get(k).cloned())
+    }
+
+    async fn insert(&mut self, k: &[u8], v: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .write(vec![WriteOperation::Insert(k.to_vec(), v.to_vec())])?
+            .pop()
+            .flatten())
+    }
+
+    async fn delete(&mut self, k: &[u8]) -> Result<Option<Vec<u8>>> {
+        if !self.memory.records().contains_key(k) {
+            return Ok(None);
+        }
+
+        Ok(self.write(vec![WriteOperation::Delete(k.to_vec())])?.pop().flatten())
+    }
+
+    async fn scan_prefix(&mut self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        Ok(self.memory.scan(prefix))
+    }
+
+    async fn write_batch(&mut self, batch: WriteBatch) -> Result<()> {
+        self.write(batch.into_iter().collect()).map(|_| ())
+    }
+}
+
+fn frame(operations: &[WriteOperation]) -> Vec<u8> {
+    let mut payload = Vec::new();
+
+    for operation in operations {
+        match operation {
+            WriteOperation::Insert(k, v) => {
+                payload.push(INSERT_TAG);
+                payload.extend((k.len() as u32).to_le_bytes());
+                payload.extend(k);
+                payload.extend((v.len() as u32).to_le_bytes());
+                payload.extend(v);
+            }
+            WriteOperation::Delete(k) => {
+                payload.push(DELETE_TAG);
+                payload.extend((k.len() as u32).to_le_bytes());
+                payload.extend(k);
+            }
+        }
+    }
+
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LENGTH + payload.len());
+    frame.extend((payload.len() as u32).to_le_bytes());
+    frame.extend(Blake2b256::digest(&payload));
+    frame.extend(payload);
+    frame
+}
+
+/// Returns whether a complete frame with a valid checksum starts anywhere in `bytes`.
+fn contains_frame(bytes: &[u8]) -> bool {
+    (0..bytes.len().saturating_sub(FRAME_HEADER_LENGTH - 1)).any(|offset| {
+        let bytes = &bytes[offset..];
+        let frame_length = FRAME_HEADER_LENGTH + u32::from_le_bytes(bytes[..4].try_into().unwrap()) as usize;
+
+        bytes.len() >= frame_length
+            && Blake2b256::digest(&bytes[FRAME_HEADER_LENGTH..frame_length]).as_slice()
+                == &bytes[4..FRAME_HEADER_LENGTH]
+    })
+}
+
+/// Reads the frame at the start of `bytes` and returns its operations and length, or `None` if it was only partially
+/// written.
+fn read_frame(bytes: &[u8]) -> Result<Option<(Vec<WriteOperation>, usize)>> {
+    if bytes.len() < FRAME_HEADER_LENGTH {
+        return Ok(None);
+    }
+
+    let payload_length = u32::from_le_bytes(bytes[..4].try_into().unwrap()) as usize;
+    let frame_length = FRAME_HEADER_LENGTH + payload_length;
+
+    if bytes.len() < frame_length {
+        // Only the last frame can be partially written, a complete frame after this header means that its length is
+        // corrupted.
+        return if contains_frame(&bytes[FRAME_HEADER_LENGTH..]) {
+            Err(Error::CorruptedDatabase("invalid frame length".to_string()))
+        } else {
+            Ok(None)
+        };
+    }
+
+    let payload = &bytes[FRAME_HEADER_LENGTH..frame_length];
+
+    if Blake2b256::digest(payload).as_slice() != &bytes[4..FRAME_HEADER_LENGTH] {
+        // Only the last frame can be partially written, any other invalid frame is a corruption.
+        return if bytes.len() == frame_length {
+            Ok(None)
+        } else {
+            Err(Error::CorruptedDatabase("invalid frame checksum".to_string()))
+        };
+    }
+
+    let mut operations = Vec::new();
+    let mut payload = payload;
+
+    while let Some((&tag, rest)) = payload.split_first() {
+        let (k, rest) = read_bytes(rest)?;
+        payload = match tag {
+            INSERT_TAG => {
+                let (v, rest) = read_bytes(rest)?;
+                operations.push(WriteOperation::Insert(k, v));
+                rest
+            }
+            DELETE_TAG => {
+                operations.push(WriteOperation::Delete(k));
+                rest
+            }
+            tag => return Err(Error::CorruptedDatabase(format!("invalid operation tag {tag}"))),
+        };
+    }
+
+    Ok(Some((operations, frame_length)))
+}
+
+fn read_bytes(bytes: &[u8]) -> Result<(Vec<u8>, &[u8])> {
+    let invalid_length = || Error::CorruptedDatabase("invalid operation length".to_string());
+
+    if bytes.len() < 4 {
+        return Err(invalid_length());
+    }
+
+    let (length, rest) = bytes.split_at(4);
+    let length = u32::from_le_bytes(length.try_into().unwrap()) as usize;
+
+    if rest.len() < length {
+        return Err(invalid_length());
+    }
+
+    let (value, rest) = rest.split_at(length);
+
+    Ok((value.to_vec(), rest))
+}
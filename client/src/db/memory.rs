@@ -0,0 +1,81 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A `DatabaseProvider` keeping all its records in memory.
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+
+use super::{DatabaseProvider, WriteBatch, WriteOperation};
+use crate::Result;
+
+/// A [`DatabaseProvider`] keeping all its records in memory, they are lost when it is dropped.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryDatabaseProvider {
+    records: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl MemoryDatabaseProvider {
+    /// Creates an empty [`MemoryDatabaseProvider`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of records.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns whether there are no records.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub(crate) fn records(&self) -> &BTreeMap<Vec<u8>, Vec<u8>> {
+        &self.records
+    }
+
+    pub(crate) fn apply(&mut self, operation: WriteOperation) -> Option<Vec<u8>> {
+        match operation {
+            WriteOperation::Insert(k, v) => self.records.insert(k, v),
+            WriteOperation::Delete(k) => self.records.remove(&k),
+        }
+    }
+
+    pub(crate) fn scan(&self, prefix: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+        self.records
+            .range(prefix.to_vec()..)
+            .take_while(|(k, _)| k.starts_with(prefix))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DatabaseProvider for MemoryDatabaseProvider {
+    async fn get(&mut self, k: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.records.get(k).cloned())
+    }
+
+    async fn insert(&mut self, k: &[u8], v: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.records.insert(k.to_vec(), v.to_vec()))
+    }
+
+    async fn delete(&mut self, k: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.records.remove(k))
+    }
+
+    async fn scan_prefix(&mut self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        Ok(self.scan(prefix))
+    }
+
+    async fn write_batch(&mut self, batch: WriteBatch) -> Result<()> {
+        // Nothing can fail while applying the batch, so it is atomic.
+        for operation in batch {
+            self.apply(operation);
+        }
+
+        Ok(())
+    }
+}
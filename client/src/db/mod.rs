@@ -3,14 +3,22 @@
 
 //! Database provider interfaces and implementations.
 
+mod cache;
+mod encrypted;
+#[cfg(not(target_family = "wasm"))]
+mod file;
+mod memory;
 #[cfg(feature = "stronghold")]
 mod stronghold;
 
 use async_trait::async_trait;
 
+#[cfg(not(target_family = "wasm"))]
+pub use self::file::FileDatabaseProvider;
 #[cfg(feature = "stronghold")]
 pub use self::stronghold::StrongholdDatabaseProvider;
-use crate::Result;
+pub use self::{cache::ResponseCache, encrypted::EncryptedDatabaseProvider, memory::MemoryDatabaseProvider};
+use crate::{Error, Result};
 
 /// The interface for database providers.
 #[async_trait]
@@ -27,4 +35,109 @@ pub trait DatabaseProvider {
     ///
     /// The deleted value is returned.
     async fn delete(&mut self, k: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Get all the records whose key starts with `prefix`, ordered by key.
+    ///
+    /// Returns [`Error::UnsupportedOperation`] by default, as keys can't be listed with the other methods.
+    async fn scan_prefix(&mut self, _prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        Err(Error::UnsupportedOperation("scan_prefix"))
+    }
+
+    /// Apply a batch of writes atomically: either all of them are applied or none.
+    ///
+    /// By default, the writes are applied one by one and, if one of them fails, the previous values of the keys written
+    /// so far are restored.
+    async fn write_batch(&mut self, batch: WriteBatch) -> Result<()> {
+        let mut previous = Vec::with_capacity(batch.len());
+
+        for operation in batch {
+            let (k, result) = match operation {
+                WriteOperation::Insert(k, v) => {
+                    let result = self.insert(&k, &v).await;
+                    (k, result)
+                }
+                WriteOperation::Delete(k) => {
+                    let result = self.delete(&k).await;
+                    (k, result)
+                }
+            };
+
+            match result {
+                Ok(v) => previous.push((k, v)),
+                Err(e) => {
+                    for (k, v) in previous.into_iter().rev() {
+                        let restored = match v {
+                            Some(v) => self.insert(&k, &v).await,
+                            None => self.delete(&k).await,
+                        };
+                        if let Err(e) = restored {
+                            log::warn!("couldn't roll back a partially applied write batch: {e}");
+                        }
+                    }
+
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A single write of a [`WriteBatch`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WriteOperation {
+    /// Insert a value under a key, replacing the existing one.
+    Insert(Vec<u8>, Vec<u8>),
+    /// Delete the value under a key.
+    Delete(Vec<u8>),
+}
+
+/// A list of writes to be applied atomically by [`DatabaseProvider::write_batch`], in insertion order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WriteBatch {
+    operations: Vec<WriteOperation>,
+}
+
+impl WriteBatch {
+    /// Creates an empty [`WriteBatch`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the insertion of a value to the batch.
+    pub fn insert(&mut self, k: &[u8], v: &[u8]) -> &mut Self {
+        self.operations.push(WriteOperation::Insert(k.to_vec(), v.to_vec()));
+        self
+    }
+
+    /// Adds the deletion of a value to the batch.
+    pub fn delete(&mut self, k: &[u8]) -> &mut Self {
+        self.operations.push(WriteOperation::Delete(k.to_vec()));
+        self
+    }
+
+    /// Returns the writes of the batch.
+    pub fn operations(&self) -> &[WriteOperation] {
+        &self.operations
+    }
+
+    /// Returns the number of writes in the batch.
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Returns whether the batch is empty.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+}
+
+impl IntoIterator for WriteBatch {
+    type Item = WriteOperation;
+    type IntoIter = std::vec::IntoIter<WriteOperation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.operations.into_iter()
+    }
 }
@@ -6,14 +6,14 @@
 use std::fmt::{Debug, Display};
 
 use iota_types::block::{
-    output::{NativeTokens, OutputId, TokenId},
+    output::{ChainId, NativeTokens, OutputId, StateTransitionError, TokenId},
     semantic::ConflictReason,
 };
 use packable::error::UnexpectedEOF;
 use primitive_types::U256;
 use serde::{ser::Serializer, Serialize};
 
-use crate::{api::input_selection::Requirement, node_api::indexer::QueryParameter};
+use crate::{api::input_selection::Requirement, node_api::indexer::QueryParameter, node_manager::quorum::QuorumReport};
 
 /// Type alias of `Result` in iota-client
 pub type Result<T> = std::result::Result<T, Error>;
@@ -41,10 +41,20 @@ pub enum Error {
     /// The wallet account has enough funds, but split on too many outputs
     #[error("the wallet account has enough funds, but split on too many outputs: {0}, max. is 128, consolidate them")]
     ConsolidationRequired(usize),
+    /// Partially signed transactions provide different signature unlocks for the same input
+    #[error("conflicting signature unlocks for input {0}")]
+    ConflictingSignatureUnlocks(usize),
+    /// The data of a database provider is corrupted
+    #[error("corrupted database: {0}")]
+    CorruptedDatabase(String),
     /// Crypto.rs error
     #[error("{0}")]
     #[serde(serialize_with = "display_string")]
     CryptoError(#[from] crypto::Error),
+    /// IRC27 or IRC30 metadata error
+    #[error("{0}")]
+    #[serde(serialize_with = "display_string")]
+    IrcMetadata(#[from] iota_types::block::output::irc::IrcMetadataError),
     /// Address not found
     #[error("address: {address} not found in range: {range}")]
     InputAddressNotFound {
@@ -70,6 +80,16 @@ pub enum Error {
         /// The max supported length.
         max_length: usize,
     },
+    /// A chain output of a transaction doesn't follow the state transition rules
+    #[error("invalid state transition of chain {chain_id}: {reason:?}")]
+    InvalidStateTransition {
+        /// The ID of the chain.
+        #[serde(serialize_with = "display_string")]
+        chain_id: ChainId,
+        /// The violated rule.
+        #[serde(serialize_with = "debug_string")]
+        reason: StateTransitionError,
+    },
     /// The transaction payload is too large
     #[error("the transaction payload is too large. Its length is {length}, max length is {max_length}")]
     InvalidTransactionPayloadLength {
@@ -78,6 +98,10 @@ pub enum Error {
         /// The max length.
         max_length: usize,
     },
+    /// IO error
+    #[error("`{0}`")]
+    #[serde(serialize_with = "display_string")]
+    IoError(#[from] std::io::Error),
     /// JSON error
     #[error("{0}")]
     #[serde(serialize_with = "display_string")]
@@ -91,9 +115,15 @@ pub enum Error {
     /// No input with matching ed25519 address provided
     #[error("no input with matching ed25519 address provided")]
     MissingInputWithEd25519Address,
+    /// A partially signed transaction lacks the signature unlock of an input
+    #[error("missing signature unlock for input {0}")]
+    MissingSignatureUnlock(usize),
     /// Error on API request
     #[error("node error: {0}")]
     NodeError(String),
+    /// Requests to a node are skipped until the cool-down period of its circuit breaker ends
+    #[error("node {0} is skipped until the cool-down period of its circuit breaker ends")]
+    NodeCircuitOpen(String),
     /// The block doesn't need to be promoted or reattached
     #[error("block ID `{0}` doesn't need to be promoted or reattached")]
     NoNeedPromoteOrReattach(String),
@@ -140,6 +170,8 @@ pub enum Error {
         quorum_size: usize,
         /// The minimum quorum threshold.
         minimum_threshold: usize,
+        /// Which nodes agreed and which differed.
+        report: Box<QuorumReport>,
     },
     /// Error from RestAPI calls with unexpected status code response
     #[error("response error with status code {code}: {text}, URL: {url}")]
@@ -155,6 +187,9 @@ pub enum Error {
     #[error("{0}")]
     #[serde(serialize_with = "display_string")]
     ReqwestError(#[from] reqwest::Error),
+    /// Partially signed transactions that don't sign the same transaction can't be combined
+    #[error("partially signed transactions don't sign the same transaction")]
+    PartiallySignedTransactionMismatch,
     /// Specifically used for `TryInfo` implementations for `SecretManager`.
     #[error("cannot unwrap a SecretManager: type mismatch!")]
     SecretManagerMismatch,
@@ -188,6 +223,9 @@ pub enum Error {
     /// Unexpected API response error
     #[error("unexpected API response")]
     UnexpectedApiResponse,
+    /// An operation is not supported by the implementation.
+    #[error("unsupported operation: {0}")]
+    UnsupportedOperation(&'static str),
     /// An indexer API request contains a query parameter not supported by the endpoint.
     #[error("an indexer API request contains a query parameter not supported by the endpoint: {0}.")]
     UnsupportedQueryParameter(QueryParameter),
@@ -251,11 +289,6 @@ pub enum Error {
     #[cfg(feature = "participation")]
     #[error("invalid participations")]
     InvalidParticipations,
-    /// IO error
-    #[cfg(feature = "participation")]
-    #[error("`{0}`")]
-    #[serde(serialize_with = "display_string")]
-    IoError(#[from] std::io::Error),
 
     //////////////////////////////////////////////////////////////////////
     // Ledger Nano
@@ -298,6 +331,14 @@ pub enum Error {
     #[error("mQTT connection not found (all nodes have the MQTT plugin disabled)")]
     MqttConnectionNotFound,
 
+    //////////////////////////////////////////////////////////////////////
+    // Testing
+    //////////////////////////////////////////////////////////////////////
+    /// Mock node error
+    #[cfg(feature = "testing")]
+    #[error("mock node error: {0}")]
+    MockNode(String),
+
     //////////////////////////////////////////////////////////////////////
     // Stronghold
     //////////////////////////////////////////////////////////////////////
@@ -363,3 +404,12 @@ where
 {
     value.to_string().serialize(serializer)
 }
+
+/// Use this to serialize Error variants that implements Debug but neither Display nor Serialize
+fn debug_string<T, S>(value: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    T: Debug,
+    S: Serializer,
+{
+    format!("{value:?}").serialize(serializer)
+}
@@ -52,6 +52,8 @@ pub mod node_manager;
 pub mod secret;
 #[cfg(feature = "stronghold")]
 pub mod stronghold;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod utils;
 
 pub use crypto::{self, keys::slip10::Seed};
@@ -118,6 +118,10 @@ pub enum Message {
     },
     /// Get a node candidate from the healthy node pool.
     GetNode,
+    /// Returns the live scores of the nodes.
+    GetNodeScores,
+    /// Returns the report of the latest request sent to several nodes for a quorum.
+    GetLastQuorumReport,
     /// Gets the network related information such as network_id and min_pow_score
     GetNetworkInfo,
     /// Gets the network id of the node we're connecting to.
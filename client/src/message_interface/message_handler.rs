@@ -287,6 +287,8 @@ impl ClientMessageHandler {
                 Ok(Response::BlockIdWithBlock(block_id, BlockDto::from(&block)))
             }
             Message::GetNode => Ok(Response::Node(self.client.get_node()?)),
+            Message::GetNodeScores => Ok(Response::NodeScores(self.client.get_node_scores()?)),
+            Message::GetLastQuorumReport => Ok(Response::QuorumReport(self.client.last_quorum_report()?)),
             Message::GetNetworkInfo => Ok(Response::NetworkInfo(self.client.get_network_info().await?.into())),
             Message::GetNetworkId => Ok(Response::NetworkId(self.client.get_network_id().await?)),
             Message::GetBech32Hrp => Ok(Response::Bech32Hrp(self.client.get_bech32_hrp().await?)),
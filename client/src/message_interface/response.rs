@@ -28,7 +28,11 @@ use serde::Serialize;
 
 #[cfg(feature = "ledger_nano")]
 use crate::secret::LedgerNanoStatus;
-use crate::{api::PreparedTransactionDataDto, node_manager::node::Node, Error, NetworkInfoDto, NodeInfoWrapper};
+use crate::{
+    api::PreparedTransactionDataDto,
+    node_manager::{node::Node, quorum::QuorumReport, scoreboard::NodeScore},
+    Error, NetworkInfoDto, NodeInfoWrapper,
+};
 
 /// The response message.
 #[derive(Serialize, Debug)]
@@ -47,6 +51,12 @@ pub enum Response {
     /// - [`GetNode`](crate::message_interface::Message::GetNode)
     Node(Node),
     /// Response for:
+    /// - [`GetNodeScores`](crate::message_interface::Message::GetNodeScores)
+    NodeScores(Vec<NodeScore>),
+    /// Response for:
+    /// - [`GetLastQuorumReport`](crate::message_interface::Message::GetLastQuorumReport)
+    QuorumReport(Option<QuorumReport>),
+    /// Response for:
     /// - [`GetNetworkInfo`](crate::message_interface::Message::GetNetworkInfo)
     NetworkInfo(NetworkInfoDto),
     /// Response for:
@@ -9,41 +9,32 @@ use iota_types::{
     api::response::{OutputMetadataResponse, OutputWithMetadataResponse},
     block::output::OutputId,
 };
-
 #[cfg(not(target_family = "wasm"))]
-use crate::constants::MAX_PARALLEL_API_REQUESTS;
+use {
+    crate::constants::MAX_PARALLEL_API_REQUESTS,
+    futures::{Future, StreamExt, TryStreamExt},
+};
+
 use crate::{Client, Result};
 
 impl Client {
     /// Request outputs by their output ID in parallel
     pub async fn get_outputs(&self, output_ids: Vec<OutputId>) -> Result<Vec<OutputWithMetadataResponse>> {
-        let mut outputs = Vec::new();
-
         #[cfg(target_family = "wasm")]
-        for output_id in output_ids {
-            outputs.push(self.get_output(&output_id).await?);
-        }
+        {
+            let mut outputs = Vec::new();
 
-        #[cfg(not(target_family = "wasm"))]
-        for output_ids_chunk in output_ids.chunks(MAX_PARALLEL_API_REQUESTS).map(<[OutputId]>::to_vec) {
-            let mut tasks = Vec::new();
-            for output_id in output_ids_chunk {
-                let client_ = self.clone();
-
-                tasks.push(async move {
-                    tokio::spawn(async move {
-                        let output_response = client_.get_output(&output_id).await?;
-                        crate::Result::Ok(output_response)
-                    })
-                    .await
-                });
-            }
-            for res in futures::future::try_join_all(tasks).await? {
-                let output_response = res?;
-                outputs.push(output_response);
+            for output_id in output_ids {
+                outputs.push(self.get_output(&output_id).await?);
             }
+            Ok(outputs)
         }
-        Ok(outputs)
+
+        #[cfg(not(target_family = "wasm"))]
+        self.parallel_requests(output_ids, |client, output_id| async move {
+            client.get_output(&output_id).await
+        })
+        .await
     }
 
     /// Request outputs by their output ID in parallel, ignoring failed requests
@@ -59,17 +50,15 @@ impl Client {
         }
 
         #[cfg(not(target_family = "wasm"))]
-        for output_ids_chunk in output_ids.chunks(MAX_PARALLEL_API_REQUESTS).map(<[OutputId]>::to_vec) {
-            let mut tasks = Vec::new();
-            for output_id in output_ids_chunk {
-                let client_ = self.clone();
+        outputs.extend(
+            self.parallel_requests(output_ids, |client, output_id| async move {
+                Ok(client.get_output(&output_id).await.ok())
+            })
+            .await?
+            .into_iter()
+            .flatten(),
+        );
 
-                tasks.push(async move { tokio::spawn(async move { client_.get_output(&output_id).await.ok() }).await });
-            }
-            for output_response in (futures::future::try_join_all(tasks).await?).into_iter().flatten() {
-                outputs.push(output_response);
-            }
-        }
         Ok(outputs)
     }
 
@@ -85,20 +74,41 @@ impl Client {
         }
 
         #[cfg(not(target_family = "wasm"))]
-        for output_ids_chunk in output_ids.chunks(MAX_PARALLEL_API_REQUESTS).map(<[OutputId]>::to_vec) {
-            let mut tasks = Vec::new();
-            for output_id in output_ids_chunk {
-                let client_ = self.clone();
-
-                tasks.push(async move {
-                    tokio::spawn(async move { client_.get_output_metadata(&output_id).await.ok() }).await
-                });
-            }
-            for output_metadata_response in (futures::future::try_join_all(tasks).await?).into_iter().flatten() {
-                output_metadata_responses.push(output_metadata_response);
-            }
-        }
+        output_metadata_responses.extend(
+            self.parallel_requests(output_ids, |client, output_id| async move {
+                Ok(client.get_output_metadata(&output_id).await.ok())
+            })
+            .await?
+            .into_iter()
+            .flatten(),
+        );
 
         Ok(output_metadata_responses)
     }
+
+    // Sends requests in parallel, as many at once as a node accepts, and returns their results in order. It stops at
+    // the first failed request.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) async fn parallel_requests<I, T, F>(
+        &self,
+        items: impl IntoIterator<Item = I>,
+        request: impl Fn(Client, I) -> F,
+    ) -> Result<Vec<T>>
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T>> + Send + 'static,
+    {
+        let max_parallel_requests = self
+            .node_manager
+            .http_client
+            .max_concurrent_requests()
+            .map_or(MAX_PARALLEL_API_REQUESTS, |max| max.clamp(1, MAX_PARALLEL_API_REQUESTS));
+
+        // Tasks are only spawned when the stream polls them.
+        futures::stream::iter(items.into_iter().map(|item| tokio::spawn(request(self.clone(), item))))
+            .buffered(max_parallel_requests)
+            .map(|result| result?)
+            .try_collect()
+            .await
+    }
 }
@@ -15,7 +15,7 @@ use iota_types::{
         },
     },
     block::{
-        output::OutputId,
+        output::{dto::OutputDto, Output, OutputId},
         payload::{
             milestone::{MilestoneId, MilestonePayload},
             transaction::TransactionId,
@@ -280,6 +280,12 @@ impl Client {
     /// Finds a block by its BlockId. This method returns the given block object.
     /// GET /api/core/v2/blocks/{BlockId}
     pub async fn get_block(&self, block_id: &BlockId) -> Result<Block> {
+        if let Some(cache) = &self.response_cache {
+            if let Some(block) = cached(cache.get_block(block_id).await) {
+                return Ok(block);
+            }
+        }
+
         let path = &format!("api/core/v2/blocks/{block_id}");
 
         let resp = self
@@ -287,10 +293,16 @@ impl Client {
             .get_request::<BlockResponse>(path, None, self.get_timeout(), false, true)
             .await?;
 
-        match resp {
-            BlockResponse::Json(dto) => Ok(Block::try_from_dto(&dto, &self.get_protocol_parameters().await?)?),
-            BlockResponse::Raw(_) => Err(crate::Error::UnexpectedApiResponse),
+        let block = match resp {
+            BlockResponse::Json(dto) => Block::try_from_dto(&dto, &self.get_protocol_parameters().await?)?,
+            BlockResponse::Raw(_) => return Err(crate::Error::UnexpectedApiResponse),
+        };
+
+        if let Some(cache) = &self.response_cache {
+            log_cache_insertion_error(cache.insert_block(&block).await);
         }
+
+        Ok(block)
     }
 
     /// Finds a block by its BlockId. This method returns the given block raw data.
@@ -318,11 +330,30 @@ impl Client {
     /// Finds an output, as JSON, by its OutputId (TransactionId + output_index).
     /// GET /api/core/v2/outputs/{outputId}
     pub async fn get_output(&self, output_id: &OutputId) -> Result<OutputWithMetadataResponse> {
+        if let Some(cache) = &self.response_cache {
+            if let Some(output) = cached(cache.get_output(output_id).await) {
+                // The output can't change but its metadata can, until it is spent.
+                return Ok(OutputWithMetadataResponse {
+                    metadata: self.get_output_metadata(output_id).await?,
+                    output: OutputDto::from(&output),
+                });
+            }
+        }
+
         let path = &format!("api/core/v2/outputs/{output_id}");
 
-        self.node_manager
+        let resp: OutputWithMetadataResponse = self
+            .node_manager
             .get_request(path, None, self.get_timeout(), false, true)
-            .await
+            .await?;
+
+        if let Some(cache) = &self.response_cache {
+            let output = Output::try_from_dto(&resp.output, self.get_token_supply().await?)?;
+            log_cache_insertion_error(cache.insert_output(output_id, &output).await);
+            log_cache_insertion_error(cache.insert_output_metadata(output_id, &resp.metadata).await);
+        }
+
+        Ok(resp)
     }
 
     /// Finds an output, as raw bytes, by its OutputId (TransactionId + output_index).
@@ -338,11 +369,26 @@ impl Client {
     /// Get the metadata for a given `OutputId` (TransactionId + output_index).
     /// GET /api/core/v2/outputs/{outputId}/metadata
     pub async fn get_output_metadata(&self, output_id: &OutputId) -> Result<OutputMetadataResponse> {
+        if let Some(cache) = &self.response_cache {
+            if let Some(metadata) = cached(cache.get_spent_output_metadata(output_id).await) {
+                return Ok(metadata);
+            }
+        }
+
         let path = &format!("api/core/v2/outputs/{output_id}/metadata");
+        // The ledger index differs between synced nodes, so the quorum is only used with a comparator for it.
+        let need_quorum = self.node_manager.quorum_comparators.contains(path);
 
-        self.node_manager
-            .get_request::<OutputMetadataResponse>(path, None, self.get_timeout(), false, true)
-            .await
+        let metadata = self
+            .node_manager
+            .get_request::<OutputMetadataResponse>(path, None, self.get_timeout(), need_quorum, true)
+            .await?;
+
+        if let Some(cache) = &self.response_cache {
+            log_cache_insertion_error(cache.insert_output_metadata(output_id, &metadata).await);
+        }
+
+        Ok(metadata)
     }
 
     /// Gets all stored receipts.
@@ -385,6 +431,12 @@ impl Client {
     /// Returns the block, as object, that was included in the ledger for a given TransactionId.
     /// GET /api/core/v2/transactions/{transactionId}/included-block
     pub async fn get_included_block(&self, transaction_id: &TransactionId) -> Result<Block> {
+        if let Some(cache) = &self.response_cache {
+            if let Some(block) = cached(cache.get_included_block(transaction_id).await) {
+                return Ok(block);
+            }
+        }
+
         let path = &format!("api/core/v2/transactions/{transaction_id}/included-block");
 
         let resp = self
@@ -392,10 +444,16 @@ impl Client {
             .get_request::<BlockResponse>(path, None, self.get_timeout(), true, true)
             .await?;
 
-        match resp {
-            BlockResponse::Json(dto) => Ok(Block::try_from_dto(&dto, &self.get_protocol_parameters().await?)?),
-            BlockResponse::Raw(_) => Err(crate::Error::UnexpectedApiResponse),
+        let block = match resp {
+            BlockResponse::Json(dto) => Block::try_from_dto(&dto, &self.get_protocol_parameters().await?)?,
+            BlockResponse::Raw(_) => return Err(crate::Error::UnexpectedApiResponse),
+        };
+
+        if let Some(cache) = &self.response_cache {
+            log_cache_insertion_error(cache.insert_included_block(transaction_id, &block).await);
         }
+
+        Ok(block)
     }
 
     /// Returns the block, as raw bytes, that was included in the ledger for a given TransactionId.
@@ -423,6 +481,12 @@ impl Client {
     /// Gets the milestone by the given milestone id.
     /// GET /api/core/v2/milestones/{milestoneId}
     pub async fn get_milestone_by_id(&self, milestone_id: &MilestoneId) -> Result<MilestonePayload> {
+        if let Some(cache) = &self.response_cache {
+            if let Some(milestone) = cached(cache.get_milestone(milestone_id).await) {
+                return Ok(milestone);
+            }
+        }
+
         let path = &format!("api/core/v2/milestones/{milestone_id}");
 
         let resp = self
@@ -430,13 +494,18 @@ impl Client {
             .get_request::<MilestoneResponse>(path, None, self.get_timeout(), false, true)
             .await?;
 
-        match resp {
-            MilestoneResponse::Json(dto) => Ok(MilestonePayload::try_from_dto(
-                &dto,
-                &self.get_protocol_parameters().await?,
-            )?),
-            MilestoneResponse::Raw(_) => Err(crate::Error::UnexpectedApiResponse),
+        let milestone = match resp {
+            MilestoneResponse::Json(dto) => {
+                MilestonePayload::try_from_dto(&dto, &self.get_protocol_parameters().await?)?
+            }
+            MilestoneResponse::Raw(_) => return Err(crate::Error::UnexpectedApiResponse),
+        };
+
+        if let Some(cache) = &self.response_cache {
+            log_cache_insertion_error(cache.insert_milestone(&milestone).await);
         }
+
+        Ok(milestone)
     }
 
     /// Gets the milestone by the given milestone id.
@@ -462,6 +531,12 @@ impl Client {
     /// Gets the milestone by the given milestone index.
     /// GET /api/core/v2/milestones/{index}
     pub async fn get_milestone_by_index(&self, index: u32) -> Result<MilestonePayload> {
+        if let Some(cache) = &self.response_cache {
+            if let Some(milestone) = cached(cache.get_milestone_by_index(index).await) {
+                return Ok(milestone);
+            }
+        }
+
         let path = &format!("api/core/v2/milestones/by-index/{index}");
 
         let resp = self
@@ -469,13 +544,18 @@ impl Client {
             .get_request::<MilestoneResponse>(path, None, self.get_timeout(), false, true)
             .await?;
 
-        match resp {
-            MilestoneResponse::Json(dto) => Ok(MilestonePayload::try_from_dto(
-                &dto,
-                &self.get_protocol_parameters().await?,
-            )?),
-            MilestoneResponse::Raw(_) => Err(crate::Error::UnexpectedApiResponse),
+        let milestone = match resp {
+            MilestoneResponse::Json(dto) => {
+                MilestonePayload::try_from_dto(&dto, &self.get_protocol_parameters().await?)?
+            }
+            MilestoneResponse::Raw(_) => return Err(crate::Error::UnexpectedApiResponse),
+        };
+
+        if let Some(cache) = &self.response_cache {
+            log_cache_insertion_error(cache.insert_milestone(&milestone).await);
         }
+
+        Ok(milestone)
     }
 
     /// Gets the milestone by the given milestone index.
@@ -532,3 +612,18 @@ impl Client {
     // // POST creates a snapshot (full, delta or both).
     // RouteControlSnapshotsCreate = "/control/snapshots/create"
 }
+
+// The cache only spares requests to the nodes, which are requested instead when reading the cache fails.
+fn cached<T>(result: Result<Option<T>>) -> Option<T> {
+    result.unwrap_or_else(|e| {
+        log::warn!("[ResponseCache] reading failed, requesting the nodes instead: {e}");
+        None
+    })
+}
+
+// A response that can't be cached is still returned.
+fn log_cache_insertion_error(result: Result<()>) {
+    if let Err(e) = result {
+        log::warn!("[ResponseCache] caching a response failed: {e}");
+    }
+}
@@ -19,6 +19,10 @@ use crate::{
     node_manager::{
         http_client::HttpClient,
         node::{Node, NodeAuth, NodeDto},
+        quorum::{QuorumComparator, QuorumComparators},
+        rate_limit::{NodeLimiter, RateLimit},
+        retry::{CircuitBreaker, RetryPolicy},
+        scoreboard::{NodeScoreboard, NodeSelectionPolicy},
         NodeManager,
     },
 };
@@ -44,6 +48,21 @@ pub struct NodeManagerBuilder {
     /// gets updated
     #[serde(rename = "nodeSyncInterval", default = "default_node_sync_interval")]
     pub node_sync_interval: Duration,
+    /// The policy ordering the nodes a request is sent to, after the primary node
+    #[serde(rename = "nodeSelectionPolicy", default)]
+    pub node_selection_policy: NodeSelectionPolicy,
+    /// How a failed request is retried on the same node
+    #[serde(rename = "retryPolicy", default)]
+    pub retry_policy: RetryPolicy,
+    /// Skips the nodes with too many consecutive failed requests
+    #[serde(rename = "circuitBreaker", default)]
+    pub circuit_breaker: Option<CircuitBreaker>,
+    /// The maximum number of requests sent at once to a node
+    #[serde(rename = "maxConcurrentRequests", default)]
+    pub max_concurrent_requests: Option<usize>,
+    /// The rate limit of the requests to a node
+    #[serde(rename = "rateLimit", default)]
+    pub rate_limit: Option<RateLimit>,
     /// If node quorum is enabled. Will compare the responses from multiple nodes and only returns the response if
     /// `quorum_threshold`% of the nodes return the same one
     #[serde(default)]
@@ -54,6 +73,10 @@ pub struct NodeManagerBuilder {
     /// % of nodes that have to return the same response so it gets accepted
     #[serde(rename = "quorumThreshold", default = "default_quorum_threshold")]
     pub quorum_threshold: usize,
+    /// How the responses of the nodes are compared for a quorum, per endpoint, it can't be serialized and needs to be
+    /// set again after deserialization
+    #[serde(skip)]
+    pub(crate) quorum_comparators: QuorumComparators,
     /// The User-Agent header for requests
     #[serde(rename = "userAgent", default = "default_user_agent")]
     pub user_agent: String,
@@ -197,6 +220,31 @@ impl NodeManagerBuilder {
         self
     }
 
+    pub(crate) fn with_node_selection_policy(mut self, node_selection_policy: NodeSelectionPolicy) -> Self {
+        self.node_selection_policy = node_selection_policy;
+        self
+    }
+
+    pub(crate) fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub(crate) fn with_circuit_breaker(mut self, circuit_breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker.replace(circuit_breaker);
+        self
+    }
+
+    pub(crate) fn with_max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.max_concurrent_requests.replace(max_concurrent_requests);
+        self
+    }
+
+    pub(crate) fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit.replace(rate_limit);
+        self
+    }
+
     pub(crate) fn with_quorum(mut self, quorum: bool) -> Self {
         self.quorum = quorum;
         self
@@ -212,12 +260,21 @@ impl NodeManagerBuilder {
         self
     }
 
+    pub(crate) fn with_quorum_comparator(mut self, endpoint: &str, comparator: Arc<dyn QuorumComparator>) -> Self {
+        self.quorum_comparators.insert(endpoint, comparator);
+        self
+    }
+
     pub(crate) fn with_user_agent(mut self, user_agent: String) -> Self {
         self.user_agent = user_agent;
         self
     }
 
-    pub(crate) fn build(self, healthy_nodes: Arc<RwLock<HashMap<Node, InfoResponse>>>) -> NodeManager {
+    pub(crate) fn build(
+        self,
+        healthy_nodes: Arc<RwLock<HashMap<Node, InfoResponse>>>,
+        scoreboard: NodeScoreboard,
+    ) -> NodeManager {
         NodeManager {
             primary_node: self.primary_node.map(|node| node.into()),
             primary_pow_node: self.primary_pow_node.map(|node| node.into()),
@@ -228,10 +285,18 @@ impl NodeManagerBuilder {
             ignore_node_health: self.ignore_node_health,
             node_sync_interval: self.node_sync_interval,
             healthy_nodes,
+            node_selection_policy: self.node_selection_policy,
+            scoreboard: scoreboard.clone(),
             quorum: self.quorum,
             min_quorum_size: self.min_quorum_size,
             quorum_threshold: self.quorum_threshold,
-            http_client: HttpClient::new(self.user_agent),
+            quorum_comparators: self.quorum_comparators,
+            last_quorum_report: Default::default(),
+            http_client: HttpClient::new(self.user_agent)
+                .with_scoreboard(scoreboard)
+                .with_retry_policy(self.retry_policy)
+                .with_circuit_breaker(self.circuit_breaker)
+                .with_limiter(NodeLimiter::new(self.max_concurrent_requests, self.rate_limit)),
         }
     }
 }
@@ -245,9 +310,15 @@ impl Default for NodeManagerBuilder {
             permanodes: None,
             ignore_node_health: false,
             node_sync_interval: NODE_SYNC_INTERVAL,
+            node_selection_policy: NodeSelectionPolicy::default(),
+            retry_policy: RetryPolicy::default(),
+            circuit_breaker: None,
+            max_concurrent_requests: None,
+            rate_limit: None,
             quorum: false,
             min_quorum_size: DEFAULT_MIN_QUORUM_SIZE,
             quorum_threshold: DEFAULT_QUORUM_THRESHOLD,
+            quorum_comparators: QuorumComparators::default(),
             user_agent: DEFAULT_USER_AGENT.to_string(),
         }
     }
@@ -11,7 +11,12 @@ use serde_json::Value;
 
 use crate::{
     error::{Error, Result},
-    node_manager::node::Node,
+    node_manager::{
+        node::Node,
+        rate_limit::NodeLimiter,
+        retry::{sleep, CircuitBreaker, RetryPolicy},
+        scoreboard::NodeScoreboard,
+    },
 };
 pub(crate) struct Response(reqwest::Response);
 
@@ -37,6 +42,10 @@ impl Response {
 pub(crate) struct HttpClient {
     client: reqwest::Client,
     user_agent: String,
+    scoreboard: Option<NodeScoreboard>,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Option<CircuitBreaker>,
+    limiter: NodeLimiter,
 }
 
 impl HttpClient {
@@ -44,9 +53,42 @@ impl HttpClient {
         Self {
             client: reqwest::Client::new(),
             user_agent,
+            scoreboard: None,
+            retry_policy: RetryPolicy::default(),
+            circuit_breaker: None,
+            limiter: NodeLimiter::default(),
         }
     }
 
+    /// Records the latency and the errors of the requests in a scoreboard.
+    pub(crate) fn with_scoreboard(mut self, scoreboard: NodeScoreboard) -> Self {
+        self.scoreboard.replace(scoreboard);
+        self
+    }
+
+    /// Retries the failed requests to a node.
+    pub(crate) fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Skips the nodes with too many consecutive failed requests, which requires a scoreboard.
+    pub(crate) fn with_circuit_breaker(mut self, circuit_breaker: Option<CircuitBreaker>) -> Self {
+        self.circuit_breaker = circuit_breaker;
+        self
+    }
+
+    /// Caps the concurrent requests and the request rate of each node.
+    pub(crate) fn with_limiter(mut self, limiter: NodeLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Returns how many requests can be sent at once to a node, `None` if it isn't capped.
+    pub(crate) fn max_concurrent_requests(&self) -> Option<usize> {
+        self.limiter.max_concurrent_requests()
+    }
+
     async fn parse_response(response: reqwest::Response, url: &url::Url) -> Result<Response> {
         let status = response.status();
         if status.is_success() {
@@ -75,13 +117,58 @@ impl HttpClient {
         request_builder
     }
 
-    pub(crate) async fn get(&self, node: Node, timeout: Duration) -> Result<Response> {
-        let mut request_builder = self.client.get(node.url.clone());
-        request_builder = self.build_request(request_builder, &node, timeout);
+    // Sends a request to a node, retrying it according to the retry policy. Requests that aren't idempotent are only
+    // retried if the node certainly didn't process them.
+    async fn send(&self, node: &Node, idempotent: bool, request: impl Fn() -> RequestBuilder) -> Result<Response> {
+        let mut attempt = 1;
+
+        loop {
+            match self.send_once(node, request()).await {
+                Err(e)
+                    if attempt < self.retry_policy.max_attempts && self.retry_policy.is_retryable(&e, idempotent) =>
+                {
+                    let backoff = self.retry_policy.backoff(attempt);
+
+                    log::debug!("retrying in {} ms after: {e}", backoff.as_millis());
+                    sleep(backoff).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send_once(&self, node: &Node, request_builder: RequestBuilder) -> Result<Response> {
+        if let (Some(scoreboard), Some(circuit_breaker)) = (&self.scoreboard, &self.circuit_breaker) {
+            if scoreboard.is_circuit_open(&node.url, circuit_breaker) {
+                return Err(Error::NodeCircuitOpen(node.url.to_string()));
+            }
+        }
+
+        let _permit = self.limiter.acquire(&node.url).await;
         let start_time = instant::Instant::now();
-        let resp = request_builder.send().await?;
+        let resp = request_builder.send().await;
+
+        if let Some(scoreboard) = &self.scoreboard {
+            match &resp {
+                // Client errors like a missing output are valid responses of a healthy node.
+                Ok(resp)
+                    if !resp.status().is_server_error() && resp.status() != reqwest::StatusCode::TOO_MANY_REQUESTS =>
+                {
+                    scoreboard.record_response(&node.url, start_time.elapsed())
+                }
+                _ => scoreboard.record_error(&node.url),
+            }
+        }
+
+        let resp = resp?;
+
+        if let Some(retry_after) = retry_after(&resp) {
+            self.limiter
+                .retry_after(&node.url, retry_after.min(self.retry_policy.max_retry_after));
+        }
         log::debug!(
-            "GET: {:?} ms for {} {}",
+            "{:?} ms for {} {}",
             start_time.elapsed().as_millis(),
             resp.status(),
             node.url
@@ -89,25 +176,57 @@ impl HttpClient {
         Self::parse_response(resp, &node.url).await
     }
 
+    pub(crate) async fn get(&self, node: Node, timeout: Duration) -> Result<Response> {
+        self.send(&node, true, || {
+            self.build_request(self.client.get(node.url.clone()), &node, timeout)
+        })
+        .await
+    }
+
     // Get with header: "accept", "application/vnd.iota.serializer-v1"
     pub(crate) async fn get_bytes(&self, node: Node, timeout: Duration) -> Result<Response> {
-        let mut request_builder = self.client.get(node.url.clone());
-        request_builder = self.build_request(request_builder, &node, timeout);
-        request_builder = request_builder.header("accept", "application/vnd.iota.serializer-v1");
-        let resp = request_builder.send().await?;
-        Self::parse_response(resp, &node.url).await
+        self.send(&node, true, || {
+            self.build_request(self.client.get(node.url.clone()), &node, timeout)
+                .header("accept", "application/vnd.iota.serializer-v1")
+        })
+        .await
     }
 
     pub(crate) async fn post_json(&self, node: Node, timeout: Duration, json: Value) -> Result<Response> {
-        let mut request_builder = self.client.post(node.url.clone());
-        request_builder = self.build_request(request_builder, &node, timeout);
-        Self::parse_response(request_builder.json(&json).send().await?, &node.url).await
+        self.send(&node, false, || {
+            self.build_request(self.client.post(node.url.clone()), &node, timeout)
+                .json(&json)
+        })
+        .await
     }
 
     pub(crate) async fn post_bytes(&self, node: Node, timeout: Duration, body: &[u8]) -> Result<Response> {
-        let mut request_builder = self.client.post(node.url.clone());
-        request_builder = self.build_request(request_builder, &node, timeout);
-        request_builder = request_builder.header("Content-Type", "application/vnd.iota.serializer-v1");
-        Self::parse_response(request_builder.body(body.to_vec()).send().await?, &node.url).await
+        self.send(&node, false, || {
+            self.build_request(self.client.post(node.url.clone()), &node, timeout)
+                .header("Content-Type", "application/vnd.iota.serializer-v1")
+                .body(body.to_vec())
+        })
+        .await
     }
 }
+
+// Returns the delay requested by a node that is overloaded or throttling the client. Only delays in seconds are
+// supported, not HTTP dates.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    if !matches!(
+        response.status(),
+        reqwest::StatusCode::TOO_MANY_REQUESTS | reqwest::StatusCode::SERVICE_UNAVAILABLE
+    ) {
+        return None;
+    }
+
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
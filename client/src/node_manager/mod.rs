@@ -7,6 +7,14 @@ pub mod builder;
 pub(crate) mod http_client;
 /// Structs for nodes
 pub mod node;
+/// Comparison of the responses of a quorum and reports of the nodes that disagreed
+pub mod quorum;
+/// Limits of the concurrent requests and of the request rate of the nodes
+pub mod rate_limit;
+/// Retries of the requests and circuit breaker of the nodes
+pub mod retry;
+/// Scores of the nodes and policies ordering them
+pub mod scoreboard;
 pub(crate) mod syncing;
 
 use std::{
@@ -17,8 +25,15 @@ use std::{
 
 use iota_types::api::response::InfoResponse;
 use serde_json::Value;
+use url::Url;
 
-use self::{http_client::HttpClient, node::Node};
+use self::{
+    http_client::HttpClient,
+    node::Node,
+    quorum::{QuorumComparators, QuorumReport},
+    retry::is_unprocessed,
+    scoreboard::{node_url, NodeScoreboard, NodeSelectionPolicy},
+};
 use crate::{
     error::{Error, Result},
     node_manager::builder::NodeManagerBuilder,
@@ -35,9 +50,13 @@ pub(crate) struct NodeManager {
     pub(crate) ignore_node_health: bool,
     node_sync_interval: Duration,
     pub(crate) healthy_nodes: Arc<RwLock<HashMap<Node, InfoResponse>>>,
+    node_selection_policy: NodeSelectionPolicy,
+    pub(crate) scoreboard: NodeScoreboard,
     quorum: bool,
     min_quorum_size: usize,
     quorum_threshold: usize,
+    pub(crate) quorum_comparators: QuorumComparators,
+    pub(crate) last_quorum_report: Arc<RwLock<Option<QuorumReport>>>,
     pub(crate) http_client: HttpClient,
 }
 
@@ -51,9 +70,11 @@ impl std::fmt::Debug for NodeManager {
         d.field("ignore_node_health", &self.ignore_node_health);
         d.field("node_sync_interval", &self.node_sync_interval);
         d.field("healthy_nodes", &self.healthy_nodes);
+        d.field("node_selection_policy", &self.node_selection_policy);
         d.field("quorum", &self.quorum);
         d.field("min_quorum_size", &self.min_quorum_size);
-        d.field("quorum_threshold", &self.quorum_threshold).finish()
+        d.field("quorum_threshold", &self.quorum_threshold);
+        d.field("quorum_comparators", &self.quorum_comparators).finish()
     }
 }
 
@@ -69,13 +90,43 @@ impl NodeManager {
         use_pow_nodes: bool,
         prefer_permanode: bool,
     ) -> Result<Vec<Node>> {
-        let mut nodes_with_modified_url: Vec<Node> = Vec::new();
+        let milestone_index = milestone_index(path);
+        // Nodes that pruned the requested milestone are skipped when there are permanodes.
+        let skip_pruned_nodes = self.permanodes.is_some()
+            && milestone_index.map_or(false, |index| {
+                self.nodes
+                    .iter()
+                    .chain(&self.primary_node)
+                    .any(|node| self.pruned(&node.url, Some(index)) == Some(true))
+            });
+        let mut nodes_with_modified_url = self.select_nodes(
+            prefer_permanode || skip_pruned_nodes || (path == "api/core/v2/blocks" && query.is_some()),
+            use_pow_nodes,
+        )?;
+
+        if skip_pruned_nodes {
+            nodes_with_modified_url
+                .retain(|node| self.is_permanode(&node.url) || self.pruned(&node.url, milestone_index) != Some(true));
+        }
+
+        // Set path and query parameters
+        nodes_with_modified_url.iter_mut().for_each(|node| {
+            node.url.set_path(path);
+            node.url.set_query(query);
+        });
+
+        Ok(nodes_with_modified_url)
+    }
+
+    // Returns the nodes to send a request to, in the order they should be tried.
+    pub(crate) fn select_nodes(&self, use_permanodes: bool, use_pow_nodes: bool) -> Result<Vec<Node>> {
+        let mut nodes: Vec<Node> = Vec::new();
 
-        if prefer_permanode || (path == "api/core/v2/blocks" && query.is_some()) {
+        if use_permanodes {
             if let Some(permanodes) = self.permanodes.clone() {
                 for permanode in permanodes {
-                    if !nodes_with_modified_url.iter().any(|n| n.url == permanode.url) {
-                        nodes_with_modified_url.push(permanode);
+                    if !nodes.iter().any(|n| n.url == permanode.url) {
+                        nodes.push(permanode);
                     }
                 }
             }
@@ -83,20 +134,20 @@ impl NodeManager {
 
         if use_pow_nodes {
             if let Some(pow_node) = self.primary_pow_node.clone() {
-                if !nodes_with_modified_url.iter().any(|n| n.url == pow_node.url) {
-                    nodes_with_modified_url.push(pow_node);
+                if !nodes.iter().any(|n| n.url == pow_node.url) {
+                    nodes.push(pow_node);
                 }
             }
         }
 
         if let Some(primary_node) = self.primary_node.clone() {
-            if !nodes_with_modified_url.iter().any(|n| n.url == primary_node.url) {
-                nodes_with_modified_url.push(primary_node);
+            if !nodes.iter().any(|n| n.url == primary_node.url) {
+                nodes.push(primary_node);
             }
         }
 
-        // Add other nodes in random order, so they are not always used in the same order
-        let nodes_random_order = if !self.ignore_node_health {
+        // Add other nodes in the order of the selection policy
+        let mut pool: Vec<Node> = if !self.ignore_node_health {
             #[cfg(not(target_family = "wasm"))]
             {
                 self.healthy_nodes
@@ -121,33 +172,89 @@ impl NodeManager {
             }
             #[cfg(target_family = "wasm")]
             {
-                self.nodes.clone()
+                self.nodes.iter().cloned().collect()
             }
         } else {
-            self.nodes.clone()
+            self.nodes.iter().cloned().collect()
         };
 
-        // Add remaining nodes in random order
-        for node in nodes_random_order {
-            if !nodes_with_modified_url.iter().any(|n| n.url == node.url) {
-                nodes_with_modified_url.push(node);
+        self.scoreboard.order(&mut pool, self.node_selection_policy);
+
+        for node in pool {
+            if !nodes.iter().any(|n| n.url == node.url) {
+                nodes.push(node);
             }
         }
 
         // remove disabled nodes
-        nodes_with_modified_url.retain(|n| !n.disabled);
+        nodes.retain(|n| !n.disabled);
 
-        if nodes_with_modified_url.is_empty() {
+        if nodes.is_empty() {
             return Err(crate::Error::HealthyNodePoolEmpty);
         }
 
-        // Set path and query parameters
-        nodes_with_modified_url.iter_mut().for_each(|node| {
-            node.url.set_path(path);
-            node.url.set_query(query);
-        });
+        Ok(nodes)
+    }
 
-        Ok(nodes_with_modified_url)
+    // Returns the permanodes that aren't part of the nodes a request was sent to, to send it to them too when the
+    // requested data was not found because it may have been pruned. Only historical data of the core API is pruned.
+    fn fallback_permanodes(&self, path: &str, query: Option<&str>, nodes: &[Node]) -> Vec<Node> {
+        if !is_prunable(path) {
+            return Vec::new();
+        }
+
+        self.permanodes
+            .iter()
+            .flatten()
+            .filter(|permanode| {
+                !permanode.disabled && !nodes.iter().any(|node| node_url(&node.url) == node_url(&permanode.url))
+            })
+            .map(|permanode| {
+                let mut permanode = permanode.clone();
+                permanode.url.set_path(path);
+                permanode.url.set_query(query);
+                permanode
+            })
+            .collect()
+    }
+
+    fn is_permanode(&self, url: &Url) -> bool {
+        let url = node_url(url);
+
+        self.permanodes
+            .iter()
+            .flatten()
+            .any(|permanode| node_url(&permanode.url) == url)
+    }
+
+    // Returns whether a node pruned the milestone with the given index, or any milestone if no index is given,
+    // according to the pruning index of its last info. Returns `None` if the info of the node is unknown.
+    fn pruned(&self, url: &Url, milestone_index: Option<u32>) -> Option<bool> {
+        let url = node_url(url);
+        let healthy_nodes = self.healthy_nodes.read().ok()?;
+        let (_, info) = healthy_nodes.iter().find(|(node, _)| node_url(&node.url) == url)?;
+
+        Some(pruned(info, milestone_index))
+    }
+
+    // Returns whether a node that didn't find the requested data may have pruned it. The info of the node is requested
+    // if it's unknown, like when the node health is ignored or on wasm, where the nodes aren't synced.
+    async fn may_have_pruned(&self, node: &Node, milestone_index: Option<u32>, timeout: Duration) -> bool {
+        if let Some(pruned) = self.pruned(&node.url, milestone_index) {
+            return pruned;
+        }
+
+        let mut node = node.clone();
+        node.url.set_path("api/core/v2/info");
+        node.url.set_query(None);
+
+        match self.http_client.get(node, timeout).await {
+            Ok(res) => res
+                .into_json::<InfoResponse>()
+                .await
+                .map_or(false, |info| pruned(&info, milestone_index)),
+            Err(_) => false,
+        }
     }
 
     pub(crate) async fn get_request<T: serde::de::DeserializeOwned + std::fmt::Debug + serde::Serialize>(
@@ -158,48 +265,63 @@ impl NodeManager {
         need_quorum: bool,
         prefer_permanode: bool,
     ) -> Result<T> {
-        let mut result: HashMap<String, usize> = HashMap::new();
+        // The responses of the nodes, along with the URL of the node
+        let mut responses: Vec<(Url, Value)> = Vec::new();
+        // with query we ignore quorum because the nodes can store a different amount of history
+        let quorum = self.quorum && need_quorum && query.is_none();
         // primary_pow_node should only be used for post request with remote PoW
         // Get node urls and set path
         let nodes = self.get_nodes(path, query, false, prefer_permanode)?;
-        if self.quorum && need_quorum && nodes.len() < self.min_quorum_size {
+        let fallback_permanodes = self.fallback_permanodes(path, query, &nodes);
+        let milestone_index = milestone_index(path);
+        // Whether a node that didn't find the data may have pruned it
+        let mut pruned = false;
+        if quorum && nodes.len() < self.min_quorum_size {
             return Err(Error::QuorumPoolSizeError {
                 available_nodes: nodes.len(),
                 minimum_threshold: self.min_quorum_size,
             });
         }
 
-        // Track amount of results for quorum
-        let mut result_counter = 0;
         let mut error = None;
         // Send requests parallel for quorum
         #[cfg(target_family = "wasm")]
         let wasm = true;
         #[cfg(not(target_family = "wasm"))]
         let wasm = false;
-        if !wasm && self.quorum && need_quorum && query.is_none() {
+        if !wasm && quorum {
             #[cfg(not(target_family = "wasm"))]
             {
                 let mut tasks = Vec::new();
                 for (index, node) in nodes.into_iter().enumerate() {
                     if index < self.min_quorum_size {
                         let client_ = self.http_client.clone();
-                        tasks.push(async move { tokio::spawn(async move { client_.get(node, timeout).await }).await });
+                        tasks.push(async move {
+                            let request_node = node.clone();
+                            tokio::spawn(async move { client_.get(request_node, timeout).await })
+                                .await
+                                .map(|res| (node, res))
+                        });
                     }
                 }
-                for res in futures::future::try_join_all(tasks).await? {
+                for (node, res) in futures::future::try_join_all(tasks).await? {
                     match res {
                         Ok(res) => {
-                            if let Ok(res_text) = res.into_text().await {
-                                let counters = result.entry(res_text.to_string()).or_insert(0);
-                                *counters += 1;
-                                result_counter += 1;
+                            if let Ok(res_json) = res.into_json::<Value>().await {
+                                responses.push((node_url(&node.url), res_json));
                             } else {
-                                log::warn!("couldn't convert node response to text");
+                                log::warn!("couldn't convert node response to JSON");
                             }
                         }
-                        Err(Error::ResponseError { code: 404, url, .. }) => {
-                            error.replace(crate::Error::NotFound(url));
+                        Err(Error::ResponseError {
+                            code: 404,
+                            url: request_url,
+                            ..
+                        }) => {
+                            pruned = pruned
+                                || (!fallback_permanodes.is_empty()
+                                    && self.may_have_pruned(&node, milestone_index, timeout).await);
+                            error.replace(crate::Error::NotFound(request_url));
                         }
                         Err(err) => {
                             error.replace(err);
@@ -227,16 +349,9 @@ impl NodeManager {
 
                                 match res.into_json::<T>().await {
                                     Ok(result_data) => {
-                                        let counters = result.entry(serde_json::to_string(&result_data)?).or_insert(0);
-                                        *counters += 1;
-                                        result_counter += 1;
+                                        responses.push((node_url(&node.url), serde_json::to_value(&result_data)?));
                                         // Without quorum it's enough if we got one response
-                                        if !self.quorum
-                                            || result_counter >= self.min_quorum_size
-                                            || !need_quorum
-                                            // with query we ignore quorum because the nodes can store a different amount of history
-                                            || query.is_some()
-                                        {
+                                        if !quorum || responses.len() >= self.min_quorum_size {
                                             break;
                                         }
                                     }
@@ -255,6 +370,30 @@ impl NodeManager {
                             }
                         }
                     }
+                    Err(Error::ResponseError { code: 404, url, .. }) => {
+                        pruned = pruned
+                            || (!fallback_permanodes.is_empty()
+                                && self.may_have_pruned(&node, milestone_index, timeout).await);
+                        error.replace(crate::Error::NotFound(url));
+                    }
+                    Err(err) => {
+                        error.replace(err);
+                    }
+                }
+            }
+        }
+
+        // The permanodes are asked without quorum for the data pruned by the nodes
+        if responses.is_empty() && pruned && matches!(error, Some(Error::NotFound(_))) {
+            for node in fallback_permanodes {
+                log::debug!("falling back to permanode {} for pruned {path}", node_url(&node.url));
+                match self.http_client.get(node, timeout).await {
+                    Ok(res) => match res.into_json::<T>().await {
+                        Ok(result_data) => return Ok(result_data),
+                        Err(e) => {
+                            error.replace(e);
+                        }
+                    },
                     Err(Error::ResponseError { code: 404, url, .. }) => {
                         error.replace(crate::Error::NotFound(url));
                     }
@@ -265,23 +404,34 @@ impl NodeManager {
             }
         }
 
-        let res = result
-            .into_iter()
-            .max_by_key(|v| v.1)
-            .ok_or_else(|| error.unwrap_or_else(|| Error::NodeError("couldn't get a result from any node".into())))?;
-
-        // Return if quorum is false or check if quorum was reached
-        if !self.quorum
-            || res.1 as f64 >= self.min_quorum_size as f64 * (self.quorum_threshold as f64 / 100.0)
-            || !need_quorum
-            // with query we ignore quorum because the nodes can store a different amount of history
-            || query.is_some()
-        {
-            Ok(serde_json::from_str(&res.0)?)
+        if responses.is_empty() {
+            return Err(error.unwrap_or_else(|| Error::NodeError("couldn't get a result from any node".into())));
+        }
+
+        // Return the only response if quorum is false
+        if !quorum {
+            return Ok(serde_json::from_value(responses.swap_remove(0).1)?);
+        }
+
+        let (report, accepted) = QuorumReport::new(path, &responses, &self.quorum_comparators);
+        let accepted = responses.swap_remove(accepted).1;
+
+        if !report.differing.is_empty() {
+            log::warn!("nodes disagree on {path}: {:?}", report.differing);
+        }
+        self.last_quorum_report
+            .write()
+            .map_err(|_| crate::Error::PoisonError)?
+            .replace(report.clone());
+
+        // Check if quorum was reached
+        if report.agreeing.len() as f64 >= self.min_quorum_size as f64 * (self.quorum_threshold as f64 / 100.0) {
+            Ok(serde_json::from_value(accepted)?)
         } else {
             Err(Error::QuorumThresholdError {
-                quorum_size: res.1,
+                quorum_size: report.agreeing.len(),
                 minimum_threshold: self.min_quorum_size,
+                report: Box::new(report),
             })
         }
     }
@@ -296,10 +446,13 @@ impl NodeManager {
         // primary_pow_node should only be used for post request with remote Pow
         // Get node urls and set path
         let nodes = self.get_nodes(path, query, false, false)?;
+        let fallback_permanodes = self.fallback_permanodes(path, query, &nodes);
+        let milestone_index = milestone_index(path);
+        let mut pruned = false;
         let mut error = None;
         // Send requests
         for node in nodes {
-            match self.http_client.get_bytes(node, timeout).await {
+            match self.http_client.get_bytes(node.clone(), timeout).await {
                 Ok(res) => {
                     let status = res.status();
                     if let Ok(res_text) = res.into_bytes().await {
@@ -313,14 +466,38 @@ impl NodeManager {
                         };
                     }
                 }
-                Err(Error::ResponseError { code: 404, url, .. }) => {
-                    error.replace(crate::Error::NotFound(url));
+                Err(Error::ResponseError {
+                    code: 404,
+                    url: request_url,
+                    ..
+                }) => {
+                    pruned = pruned
+                        || (!fallback_permanodes.is_empty()
+                            && self.may_have_pruned(&node, milestone_index, timeout).await);
+                    error.replace(crate::Error::NotFound(request_url));
                 }
                 Err(err) => {
                     error.replace(err);
                 }
             }
         }
+
+        // The permanodes are asked for the data pruned by the nodes
+        if pruned && matches!(error, Some(Error::NotFound(_))) {
+            for node in fallback_permanodes {
+                log::debug!("falling back to permanode {} for pruned {path}", node_url(&node.url));
+                match self.http_client.get_bytes(node, timeout).await {
+                    Ok(res) => return res.into_bytes().await,
+                    Err(Error::ResponseError { code: 404, url, .. }) => {
+                        error.replace(crate::Error::NotFound(url));
+                    }
+                    Err(err) => {
+                        error.replace(err);
+                    }
+                }
+            }
+        }
+
         Err(error.unwrap_or_else(|| Error::NodeError("couldn't get a result from any node".into())))
     }
 
@@ -354,6 +531,10 @@ impl NodeManager {
                     };
                 }
                 Err(e) => {
+                    // The next node would process the data a second time.
+                    if !is_unprocessed(&e) {
+                        return Err(crate::Error::NodeError(e.to_string()));
+                    }
                     error.replace(crate::Error::NodeError(e.to_string()));
                 }
             }
@@ -391,6 +572,10 @@ impl NodeManager {
                     };
                 }
                 Err(e) => {
+                    // The next node would process the data a second time.
+                    if !is_unprocessed(&e) {
+                        return Err(crate::Error::NodeError(e.to_string()));
+                    }
                     error.replace(crate::Error::NodeError(e.to_string()));
                 }
             }
@@ -398,3 +583,32 @@ impl NodeManager {
         Err(error.unwrap_or_else(|| Error::NodeError("couldn't get a result from any node".into())))
     }
 }
+
+// Returns whether a path requests historical data that nodes prune: blocks, milestones, the block including a
+// transaction or outputs by ID.
+fn is_prunable(path: &str) -> bool {
+    matches!(
+        path.trim_matches('/').split('/').collect::<Vec<_>>().as_slice(),
+        ["api", "core", "v2", "blocks", _, ..]
+            | ["api", "core", "v2", "milestones", _, ..]
+            | ["api", "core", "v2", "transactions", _, "included-block", ..]
+            | ["api", "core", "v2", "outputs", _, ..]
+    )
+}
+
+// Returns whether a node pruned the milestone with the given index, or any milestone if no index is given.
+fn pruned(info: &InfoResponse, milestone_index: Option<u32>) -> bool {
+    let pruning_index = info.status.pruning_index;
+
+    milestone_index.map_or(pruning_index > 0, |index| index <= pruning_index)
+}
+
+// Returns the index of the milestone requested by a path, like `api/core/v2/milestones/by-index/{index}`.
+fn milestone_index(path: &str) -> Option<u32> {
+    path.trim_matches('/')
+        .strip_prefix("api/core/v2/milestones/by-index/")?
+        .split('/')
+        .next()?
+        .parse()
+        .ok()
+}
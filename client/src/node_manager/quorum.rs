@@ -0,0 +1,225 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Comparison of the responses of the nodes for a quorum, per endpoint, and the report of which nodes agreed.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+/// Compares the responses of the nodes to an endpoint for a quorum: nodes agree if the compared parts of their
+/// responses are equal.
+pub trait QuorumComparator: Send + Sync {
+    /// Returns the part of a response the nodes have to agree on.
+    fn compared(&self, response: &Value) -> Value;
+}
+
+impl<F: Fn(&Value) -> Value + Send + Sync> QuorumComparator for F {
+    fn compared(&self, response: &Value) -> Value {
+        self(response)
+    }
+}
+
+/// Compares the responses without some of their fields, given as JSON pointers like `/ledgerIndex`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IgnoreFields(pub Vec<String>);
+
+impl QuorumComparator for IgnoreFields {
+    fn compared(&self, response: &Value) -> Value {
+        let mut response = response.clone();
+
+        for pointer in &self.0 {
+            if let Some((parent, key)) = pointer.rsplit_once('/') {
+                if let Some(object) = response.pointer_mut(parent).and_then(Value::as_object_mut) {
+                    object.remove(&key.replace("~1", "/").replace("~0", "~"));
+                }
+            }
+        }
+
+        response
+    }
+}
+
+/// The outcome of a request sent to several nodes for a quorum.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct QuorumReport {
+    /// The path of the request.
+    pub path: String,
+    /// The nodes that returned the accepted response, the one returned by most nodes.
+    pub agreeing: Vec<Url>,
+    /// The nodes that returned another response.
+    pub differing: Vec<QuorumDifference>,
+}
+
+/// A response differing from the accepted response of a quorum.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct QuorumDifference {
+    /// The node that returned the response.
+    pub url: Url,
+    /// The JSON pointers of the compared fields whose values differ from the accepted response.
+    pub paths: Vec<String>,
+}
+
+impl QuorumReport {
+    // Groups the responses of the nodes by their compared part and returns the report along with the index of the
+    // accepted response.
+    pub(crate) fn new(path: &str, responses: &[(Url, Value)], comparators: &QuorumComparators) -> (Self, usize) {
+        let compared = responses
+            .iter()
+            .map(|(_, response)| comparators.compared(path, response))
+            .collect::<Vec<_>>();
+        // The indexes of the responses, grouped by their compared part, in the order of their first response.
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+
+        for (index, value) in compared.iter().enumerate() {
+            match groups.iter_mut().find(|group| compared[group[0]] == *value) {
+                Some(group) => group.push(index),
+                None => groups.push(vec![index]),
+            }
+        }
+
+        // The first group wins ties.
+        let accepted = groups
+            .iter()
+            .rev()
+            .max_by_key(|group| group.len())
+            .map_or(0, |group| group[0]);
+        let mut report = Self {
+            path: path.to_string(),
+            agreeing: Vec::new(),
+            differing: Vec::new(),
+        };
+
+        for (index, (url, _)) in responses.iter().enumerate() {
+            if compared[index] == compared[accepted] {
+                report.agreeing.push(url.clone());
+            } else {
+                let mut paths = Vec::new();
+                differing_paths(&compared[accepted], &compared[index], &mut String::new(), &mut paths);
+                report.differing.push(QuorumDifference {
+                    url: url.clone(),
+                    paths,
+                });
+            }
+        }
+
+        (report, accepted)
+    }
+}
+
+/// The quorum comparators of the endpoints, the responses of other endpoints being compared as a whole.
+#[derive(Clone)]
+pub(crate) struct QuorumComparators(Vec<(String, Arc<dyn QuorumComparator>)>);
+
+impl QuorumComparators {
+    /// Sets the comparator of an endpoint, replacing the previous one.
+    pub(crate) fn insert(&mut self, endpoint: &str, comparator: Arc<dyn QuorumComparator>) {
+        let endpoint = endpoint.trim_matches('/').to_string();
+
+        self.0.retain(|(e, _)| *e != endpoint);
+        self.0.push((endpoint, comparator));
+    }
+
+    /// Returns whether a comparator is set for the endpoint of a path.
+    pub(crate) fn contains(&self, path: &str) -> bool {
+        self.0.iter().any(|(endpoint, _)| matches_endpoint(endpoint, path))
+    }
+
+    fn compared(&self, path: &str, response: &Value) -> Value {
+        match self.0.iter().find(|(endpoint, _)| matches_endpoint(endpoint, path)) {
+            Some((_, comparator)) => comparator.compared(response),
+            None => response.clone(),
+        }
+    }
+}
+
+impl Default for QuorumComparators {
+    fn default() -> Self {
+        let mut comparators = Self(Vec::new());
+        // Whether a block should be promoted or reattached depends on when the node is asked.
+        for endpoint in [
+            "api/core/v2/blocks/{blockId}/metadata",
+            "api/core/v2/transactions/{transactionId}/included-block/metadata",
+        ] {
+            comparators.insert(
+                endpoint,
+                Arc::new(IgnoreFields(vec![
+                    "/shouldPromote".to_string(),
+                    "/shouldReattach".to_string(),
+                ])),
+            );
+        }
+
+        comparators
+    }
+}
+
+impl std::fmt::Debug for QuorumComparators {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|(endpoint, _)| endpoint))
+            .finish()
+    }
+}
+
+// Comparators can't be compared, so only their endpoints are.
+impl PartialEq for QuorumComparators {
+    fn eq(&self, other: &Self) -> bool {
+        self.0
+            .iter()
+            .map(|(endpoint, _)| endpoint)
+            .eq(other.0.iter().map(|(endpoint, _)| endpoint))
+    }
+}
+
+impl Eq for QuorumComparators {}
+
+// Returns whether a path matches an endpoint, whose segments in braces match any segment.
+fn matches_endpoint(endpoint: &str, path: &str) -> bool {
+    let endpoint = endpoint.split('/').collect::<Vec<_>>();
+    let path = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+    endpoint.len() == path.len()
+        && endpoint.iter().zip(path).all(|(endpoint_segment, path_segment)| {
+            *endpoint_segment == path_segment || (endpoint_segment.starts_with('{') && endpoint_segment.ends_with('}'))
+        })
+}
+
+// Collects the JSON pointers of the values that differ between two JSON values.
+fn differing_paths(a: &Value, b: &Value, pointer: &mut String, paths: &mut Vec<String>) {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys = a
+                .keys()
+                .chain(b.keys().filter(|key| !a.contains_key(*key)))
+                .collect::<Vec<_>>();
+            keys.sort();
+
+            for key in keys {
+                let length = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                match (a.get(key), b.get(key)) {
+                    (Some(a), Some(b)) => differing_paths(a, b, pointer, paths),
+                    _ => paths.push(pointer.clone()),
+                }
+                pointer.truncate(length);
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for index in 0..a.len().max(b.len()) {
+                let length = pointer.len();
+                pointer.push_str(&format!("/{index}"));
+                match (a.get(index), b.get(index)) {
+                    (Some(a), Some(b)) => differing_paths(a, b, pointer, paths),
+                    _ => paths.push(pointer.clone()),
+                }
+                pointer.truncate(length);
+            }
+        }
+        (a, b) if a != b => paths.push(pointer.clone()),
+        _ => {}
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Limits of the requests sent to each node: a cap on the concurrent requests, a token bucket rate limit and the
+//! `Retry-After` periods requested by the nodes.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use instant::Instant;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{retry::sleep, scoreboard::node_url};
+
+/// A token bucket rate limit of the requests to a node: up to `max_requests` requests can be sent at once, then
+/// `max_requests` more every `interval`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// The size of the bucket, the number of requests that can be sent at once.
+    #[serde(rename = "maxRequests")]
+    pub max_requests: u32,
+    /// The time it takes to refill the bucket.
+    pub interval: Duration,
+}
+
+#[derive(Debug)]
+struct NodeLimit {
+    // Tokens left in the bucket, each request taking one.
+    tokens: f64,
+    last_refill: Instant,
+    // No request is sent before this instant, set from a `Retry-After` header.
+    retry_after: Option<Instant>,
+    #[cfg(not(target_family = "wasm"))]
+    semaphore: Arc<tokio::sync::Semaphore>,
+}
+
+/// The limits of the requests to the nodes, shared by the clones of a client.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct NodeLimiter {
+    max_concurrent_requests: Option<usize>,
+    rate_limit: Option<RateLimit>,
+    nodes: Arc<Mutex<HashMap<Url, NodeLimit>>>,
+}
+
+/// Allows a request to a node until it is dropped.
+pub(crate) struct RequestPermit {
+    #[cfg(not(target_family = "wasm"))]
+    _permit: Option<tokio::sync::OwnedSemaphorePermit>,
+}
+
+impl NodeLimiter {
+    pub(crate) fn new(max_concurrent_requests: Option<usize>, rate_limit: Option<RateLimit>) -> Self {
+        Self {
+            max_concurrent_requests,
+            rate_limit,
+            nodes: Default::default(),
+        }
+    }
+
+    /// Waits until a request can be sent to a node.
+    pub(crate) async fn acquire(&self, url: &Url) -> RequestPermit {
+        let url = node_url(url);
+
+        // The concurrent requests are capped first, so that waiting requests don't take tokens.
+        #[cfg(not(target_family = "wasm"))]
+        let permit = match self.max_concurrent_requests {
+            Some(_) => {
+                let semaphore = self.update(&url, |limit| limit.semaphore.clone());
+                semaphore.acquire_owned().await.ok()
+            }
+            None => None,
+        };
+
+        loop {
+            let wait = self.update(&url, |limit| {
+                let now = Instant::now();
+
+                if let Some(retry_after) = limit.retry_after.filter(|retry_after| *retry_after > now) {
+                    return retry_after - now;
+                }
+
+                match self.rate_limit {
+                    Some(rate_limit) if rate_limit.max_requests > 0 => {
+                        let max_requests = f64::from(rate_limit.max_requests);
+                        let refill = (now - limit.last_refill).as_secs_f64() / rate_limit.interval.as_secs_f64();
+
+                        limit.tokens = (limit.tokens + refill * max_requests).min(max_requests);
+                        limit.last_refill = now;
+
+                        if limit.tokens >= 1.0 {
+                            limit.tokens -= 1.0;
+                            Duration::ZERO
+                        } else {
+                            rate_limit.interval.mul_f64((1.0 - limit.tokens) / max_requests)
+                        }
+                    }
+                    _ => Duration::ZERO,
+                }
+            });
+
+            if wait.is_zero() {
+                break;
+            }
+            log::debug!("waiting {} ms for the rate limit of {url}", wait.as_millis());
+            sleep(wait).await;
+        }
+
+        RequestPermit {
+            #[cfg(not(target_family = "wasm"))]
+            _permit: permit,
+        }
+    }
+
+    /// Delays the next requests to a node.
+    pub(crate) fn retry_after(&self, url: &Url, retry_after: Duration) {
+        let retry_after = Instant::now() + retry_after;
+
+        self.update(&node_url(url), |limit| {
+            if limit.retry_after.map_or(true, |previous| previous < retry_after) {
+                limit.retry_after.replace(retry_after);
+            }
+        });
+    }
+
+    /// Returns how many requests can be sent at once to a node.
+    pub(crate) fn max_concurrent_requests(&self) -> Option<usize> {
+        self.max_concurrent_requests
+    }
+
+    fn update<T>(&self, url: &Url, update: impl FnOnce(&mut NodeLimit) -> T) -> T {
+        // The state of the limits stays consistent even if a thread panicked while holding the lock.
+        let mut nodes = self.nodes.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let limit = nodes.entry(url.clone()).or_insert_with(|| NodeLimit {
+            tokens: self
+                .rate_limit
+                .map_or(0.0, |rate_limit| f64::from(rate_limit.max_requests)),
+            last_refill: Instant::now(),
+            retry_after: None,
+            #[cfg(not(target_family = "wasm"))]
+            semaphore: Arc::new(tokio::sync::Semaphore::new(
+                self.max_concurrent_requests.unwrap_or(1).max(1),
+            )),
+        });
+
+        update(limit)
+    }
+}
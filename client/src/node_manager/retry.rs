@@ -0,0 +1,122 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Retries of the requests to a node with an exponential backoff, and the circuit breaker skipping failing nodes.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::scoreboard::random;
+use crate::Error;
+
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(10);
+const DEFAULT_MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+const DEFAULT_COOL_DOWN: Duration = Duration::from_secs(30);
+
+/// How a request is retried on the same node before the next node is tried.
+///
+/// Requests posting data, like blocks, are only retried and sent to the next node when the node certainly didn't
+/// process them: it couldn't be connected to, or it answered with a client error or `503 Service Unavailable`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts of a request to a node, 1 disables retries.
+    #[serde(rename = "maxAttempts")]
+    pub max_attempts: u32,
+    /// The backoff before the first retry, doubled for each further retry.
+    #[serde(rename = "initialBackoff")]
+    pub initial_backoff: Duration,
+    /// The maximum backoff before a retry.
+    #[serde(rename = "maxBackoff")]
+    pub max_backoff: Duration,
+    /// The longest `Retry-After` period of a node that is honoured, so that a misbehaving node can't stall the
+    /// requests for hours. Unlike backoffs, the period requested by a node isn't capped by `max_backoff`.
+    #[serde(rename = "maxRetryAfter")]
+    pub max_retry_after: Duration,
+    /// Whether a backoff is a random duration up to the exponential one, so that clients don't retry in sync.
+    pub jitter: bool,
+    /// The HTTP status codes a request is retried on, besides connection errors and timeouts.
+    #[serde(rename = "retryOnStatusCodes")]
+    pub retry_on_status_codes: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            max_retry_after: DEFAULT_MAX_RETRY_AFTER,
+            jitter: true,
+            retry_on_status_codes: vec![429, 502, 503, 504],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the backoff before a retry, `retry` starting at 1.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_backoff);
+
+        if self.jitter {
+            backoff.mul_f64(random())
+        } else {
+            backoff
+        }
+    }
+
+    // Returns whether a failed request should be retried.
+    pub(crate) fn is_retryable(&self, error: &Error, idempotent: bool) -> bool {
+        match error {
+            Error::ResponseError { code, .. } => {
+                self.retry_on_status_codes.contains(code) && (idempotent || is_unprocessed(error))
+            }
+            Error::ReqwestError(e) => (idempotent && e.is_timeout()) || e.is_connect(),
+            _ => false,
+        }
+    }
+}
+
+/// Stops sending requests to a node after consecutive failures, until a cool-down period passed. A request is then
+/// sent again, and a further failure restarts the cool-down period.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CircuitBreaker {
+    /// The number of consecutive failed requests opening the circuit.
+    #[serde(rename = "failureThreshold")]
+    pub failure_threshold: u32,
+    /// How long no request is sent to a node after its last failure, once the circuit is open.
+    #[serde(rename = "coolDown")]
+    pub cool_down: Duration,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            cool_down: DEFAULT_COOL_DOWN,
+        }
+    }
+}
+
+// Returns whether a failed request certainly wasn't processed by the node, so that it can be sent again, even if it
+// isn't idempotent.
+pub(crate) fn is_unprocessed(error: &Error) -> bool {
+    match error {
+        Error::ResponseError { code, .. } => (400..500).contains(code) || *code == 503,
+        Error::ReqwestError(e) => e.is_connect(),
+        Error::NodeCircuitOpen(_) => true,
+        _ => false,
+    }
+}
+
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(target_family = "wasm")]
+    gloo_timers::future::TimeoutFuture::new(duration.as_millis().try_into().unwrap_or(u32::MAX)).await;
+    #[cfg(not(target_family = "wasm"))]
+    tokio::time::sleep(duration).await;
+}
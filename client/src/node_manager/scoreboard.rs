@@ -0,0 +1,268 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scoring of the nodes from the latency and the errors of their responses and from their confirmed milestone lag,
+//! and the policies ordering the nodes for a request.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{node::Node, retry::CircuitBreaker};
+use crate::Result;
+
+// Weight of the latest request in the moving averages of the latency and the error rate.
+const SMOOTHING_FACTOR: f64 = 0.2;
+// Latency halving the score of a node.
+const REFERENCE_LATENCY: Duration = Duration::from_millis(100);
+// Weight of a node with a zero score in a weighted random order, so that it still gets a chance to recover.
+const MIN_WEIGHT: f64 = 0.01;
+
+/// The policy ordering the nodes a request is sent to, after the primary node.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NodeSelectionPolicy {
+    /// Orders the nodes by decreasing [score](NodeScore::score).
+    #[default]
+    BestScore,
+    /// Orders the nodes by increasing average latency.
+    LowestLatency,
+    /// Rotates the order of the nodes with each request.
+    RoundRobin,
+    /// Orders the nodes randomly, nodes with a higher score being more likely to come first.
+    WeightedRandom,
+}
+
+/// The live score of a node, from the requests sent to it and from the latest sync of the nodes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeScore {
+    /// The URL of the node, without credentials and path.
+    pub url: Url,
+    /// The moving average of the latency of the responses, `None` until the node responded.
+    pub latency: Option<Duration>,
+    /// The moving average of the share of failed requests, between 0 and 1.
+    #[serde(rename = "errorRate")]
+    pub error_rate: f64,
+    /// How many milestones the confirmed milestone of the node is behind the most recent one of the other nodes,
+    /// `None` until the node was synced.
+    #[serde(rename = "milestoneLag")]
+    pub milestone_lag: Option<u32>,
+    /// The number of requests sent to the node.
+    pub requests: u64,
+    /// The number of requests that failed because of a connection error or of a server error.
+    pub errors: u64,
+    /// The number of failed requests since the last response.
+    #[serde(rename = "consecutiveErrors")]
+    pub consecutive_errors: u32,
+    #[serde(skip)]
+    last_error: Option<instant::Instant>,
+}
+
+impl NodeScore {
+    fn new(url: Url) -> Self {
+        Self {
+            url,
+            latency: None,
+            error_rate: 0.0,
+            milestone_lag: None,
+            requests: 0,
+            errors: 0,
+            consecutive_errors: 0,
+            last_error: None,
+        }
+    }
+
+    /// Returns the score of the node, between 0 and 1 and higher for better nodes. It is halved by a latency of
+    /// 100ms, by an error rate of 0.5 or by a milestone lag of 1. A node without any request yet gets the highest
+    /// score, so that it gets tried.
+    pub fn score(&self) -> f64 {
+        let latency = self.latency.unwrap_or_default().as_secs_f64() / REFERENCE_LATENCY.as_secs_f64();
+        let milestone_lag = f64::from(self.milestone_lag.unwrap_or_default());
+
+        (1.0 - self.error_rate) / (1.0 + latency) / (1.0 + milestone_lag)
+    }
+}
+
+/// The scores of the nodes, shared by the clones of a client.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct NodeScoreboard {
+    scores: Arc<RwLock<HashMap<Url, NodeScore>>>,
+    round_robin_index: Arc<AtomicUsize>,
+}
+
+impl NodeScoreboard {
+    /// Records a response of a node.
+    pub(crate) fn record_response(&self, url: &Url, latency: Duration) {
+        self.update(url, |score| {
+            let latency = match score.latency {
+                Some(average) => average.mul_f64(1.0 - SMOOTHING_FACTOR) + latency.mul_f64(SMOOTHING_FACTOR),
+                None => latency,
+            };
+
+            score.latency.replace(latency);
+            score.error_rate *= 1.0 - SMOOTHING_FACTOR;
+            score.consecutive_errors = 0;
+        });
+    }
+
+    /// Records a request to a node that failed because of a connection error or of a server error.
+    pub(crate) fn record_error(&self, url: &Url) {
+        self.update(url, |score| {
+            score.errors += 1;
+            score.error_rate = score.error_rate * (1.0 - SMOOTHING_FACTOR) + SMOOTHING_FACTOR;
+            score.consecutive_errors += 1;
+            score.last_error.replace(instant::Instant::now());
+        });
+    }
+
+    /// Returns whether requests to a node are skipped by a circuit breaker.
+    pub(crate) fn is_circuit_open(&self, url: &Url, circuit_breaker: &CircuitBreaker) -> bool {
+        self.scores.read().map_or(false, |scores| {
+            scores.get(&node_url(url)).map_or(false, |score| {
+                score.consecutive_errors >= circuit_breaker.failure_threshold.max(1)
+                    && score
+                        .last_error
+                        .map_or(false, |last_error| last_error.elapsed() < circuit_breaker.cool_down)
+            })
+        })
+    }
+
+    /// Sets the milestone lags of the synced nodes, from their confirmed milestone indexes.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn set_confirmed_milestones<'a>(&self, confirmed_milestones: impl IntoIterator<Item = (&'a Url, u32)>) {
+        let confirmed_milestones = confirmed_milestones.into_iter().collect::<Vec<_>>();
+        let latest_confirmed_milestone = confirmed_milestones.iter().map(|(_, index)| *index).max();
+
+        if let (Some(latest_confirmed_milestone), Ok(mut scores)) = (latest_confirmed_milestone, self.scores.write()) {
+            for (url, index) in confirmed_milestones {
+                scores
+                    .entry(node_url(url))
+                    .or_insert_with_key(|url| NodeScore::new(url.clone()))
+                    .milestone_lag
+                    .replace(latest_confirmed_milestone - index);
+            }
+        }
+    }
+
+    /// Returns the scores of the nodes, best first.
+    pub(crate) fn scores(&self) -> Result<Vec<NodeScore>> {
+        let mut scores = self
+            .scores
+            .read()
+            .map_err(|_| crate::Error::PoisonError)?
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        scores.sort_by(|a, b| b.score().total_cmp(&a.score()).then_with(|| a.url.cmp(&b.url)));
+
+        Ok(scores)
+    }
+
+    /// Orders nodes according to a policy.
+    pub(crate) fn order(&self, nodes: &mut [Node], policy: NodeSelectionPolicy) {
+        // Nodes come from hash sets, so their order has to be made deterministic first.
+        nodes.sort_by(|a, b| a.url.cmp(&b.url));
+
+        if nodes.len() < 2 {
+            return;
+        }
+
+        let scores = self
+            .scores
+            .read()
+            .map(|scores| {
+                nodes
+                    .iter()
+                    .map(|node| {
+                        scores
+                            .get(&node_url(&node.url))
+                            .cloned()
+                            .unwrap_or_else(|| NodeScore::new(node_url(&node.url)))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_else(|_| nodes.iter().map(|node| NodeScore::new(node_url(&node.url))).collect());
+        let mut indexes = (0..nodes.len()).collect::<Vec<_>>();
+
+        match policy {
+            NodeSelectionPolicy::BestScore => {
+                indexes.sort_by(|a, b| scores[*b].score().total_cmp(&scores[*a].score()));
+            }
+            NodeSelectionPolicy::LowestLatency => {
+                indexes.sort_by_key(|index| scores[*index].latency.unwrap_or_default());
+            }
+            NodeSelectionPolicy::RoundRobin => {
+                indexes.rotate_left(self.round_robin_index.fetch_add(1, Ordering::Relaxed) % nodes.len());
+            }
+            NodeSelectionPolicy::WeightedRandom => {
+                let mut weights = scores
+                    .iter()
+                    .map(|score| score.score().max(MIN_WEIGHT))
+                    .collect::<Vec<_>>();
+
+                // Draws the nodes one after the other, each one with a probability proportional to its weight.
+                for position in 0..indexes.len() - 1 {
+                    let mut draw = random() * weights[position..].iter().sum::<f64>();
+                    let mut drawn = indexes.len() - 1;
+
+                    for (offset, weight) in weights[position..].iter().enumerate() {
+                        if draw < *weight {
+                            drawn = position + offset;
+                            break;
+                        }
+                        draw -= weight;
+                    }
+
+                    indexes.swap(position, drawn);
+                    weights.swap(position, drawn);
+                }
+            }
+        }
+
+        let ordered = indexes.iter().map(|index| nodes[*index].clone()).collect::<Vec<_>>();
+
+        nodes.clone_from_slice(&ordered);
+    }
+
+    fn update(&self, url: &Url, update: impl FnOnce(&mut NodeScore)) {
+        if let Ok(mut scores) = self.scores.write() {
+            let score = scores
+                .entry(node_url(url))
+                .or_insert_with_key(|url| NodeScore::new(url.clone()));
+
+            score.requests += 1;
+            update(score);
+        }
+    }
+}
+
+// Returns the URL of a node without the credentials, the path and the query of a request.
+pub(crate) fn node_url(url: &Url) -> Url {
+    let mut url = url.clone();
+
+    url.set_username("").ok();
+    url.set_password(None).ok();
+    url.set_path("");
+    url.set_query(None);
+    url
+}
+
+// Returns a random number in [0, 1).
+pub(crate) fn random() -> f64 {
+    let mut bytes = [0; 8];
+
+    match crypto::utils::rand::fill(&mut bytes) {
+        // The 53 most significant bits fill the mantissa of the number.
+        Ok(()) => (u64::from_le_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64,
+        Err(_) => 0.0,
+    }
+}
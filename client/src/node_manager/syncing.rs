@@ -3,6 +3,7 @@
 
 #[cfg(not(target_family = "wasm"))]
 use {
+    super::scoreboard::NodeScoreboard,
     crate::NetworkInfo,
     iota_types::{api::response::InfoResponse, block::protocol::ProtocolParameters},
     std::collections::HashMap,
@@ -14,19 +15,33 @@ use {
     tokio::{runtime::Runtime, time::sleep},
 };
 
-use super::Node;
+use super::{quorum::QuorumReport, scoreboard::NodeScore, Node};
 use crate::{Client, Error, Result};
 
 impl Client {
-    /// Get a node candidate from the healthy node pool.
+    /// Get a node candidate from the healthy node pool, the primary node if any or else the first node of the
+    /// selection policy.
     pub fn get_node(&self) -> Result<Node> {
-        if let Some(primary_node) = &self.node_manager.primary_node {
-            return Ok(primary_node.clone());
-        }
+        self.node_manager
+            .select_nodes(false, false)?
+            .into_iter()
+            .next()
+            .ok_or(Error::HealthyNodePoolEmpty)
+    }
 
-        let pool = self.node_manager.nodes.clone();
+    /// Returns the live scores of the nodes that were synced or sent requests, best first.
+    pub fn get_node_scores(&self) -> Result<Vec<NodeScore>> {
+        self.node_manager.scoreboard.scores()
+    }
 
-        pool.into_iter().next().ok_or(Error::HealthyNodePoolEmpty)
+    /// Returns the report of the latest request sent to several nodes for a quorum, `None` if there wasn't any.
+    pub fn last_quorum_report(&self) -> Result<Option<QuorumReport>> {
+        Ok(self
+            .node_manager
+            .last_quorum_report
+            .read()
+            .map_err(|_| Error::PoisonError)?
+            .clone())
     }
 
     /// returns the unhealthy nodes.
@@ -52,6 +67,7 @@ impl Client {
         nodes: HashSet<Node>,
         node_sync_interval: Duration,
         network_info: Arc<RwLock<NetworkInfo>>,
+        scoreboard: NodeScoreboard,
         ignore_node_health: bool,
     ) -> tokio::task::JoinHandle<()> {
         runtime.spawn(async move {
@@ -59,7 +75,8 @@ impl Client {
                 // Delay first since the first `sync_nodes` call is made by the builder to ensure the node list is
                 // filled before the client is used.
                 sleep(node_sync_interval).await;
-                if let Err(e) = Client::sync_nodes(&sync, &nodes, &network_info, ignore_node_health).await {
+                if let Err(e) = Client::sync_nodes(&sync, &nodes, &network_info, &scoreboard, ignore_node_health).await
+                {
                     log::warn!("Syncing nodes failed: {e}");
                 }
             }
@@ -71,6 +88,7 @@ impl Client {
         sync: &Arc<RwLock<HashMap<Node, InfoResponse>>>,
         nodes: &HashSet<Node>,
         network_info: &Arc<RwLock<NetworkInfo>>,
+        scoreboard: &NodeScoreboard,
         ignore_node_health: bool,
     ) -> Result<()> {
         log::debug!("sync_nodes");
@@ -117,6 +135,12 @@ impl Client {
             for (info, node_url) in nodes {
                 healthy_nodes.insert(node_url.clone(), info.clone());
             }
+
+            scoreboard.set_confirmed_milestones(
+                nodes
+                    .iter()
+                    .map(|(info, node)| (&node.url, info.status.confirmed_milestone.index)),
+            );
         }
 
         // Update the sync list.
@@ -22,11 +22,11 @@ use iota_types::block::{
 use packable::{unpacker::SliceUnpacker, Packable, PackableExt};
 use tokio::sync::Mutex;
 
-use super::{types::InputSigningData, GenerateAddressOptions, SecretManage, SecretManageExt};
+use super::{signature_unlock_chains, types::InputSigningData, GenerateAddressOptions, SecretManage, SecretManageExt};
 use crate::{
     secret::{
         types::{LedgerApp, LedgerDeviceType},
-        LedgerNanoStatus, PreparedTransactionData, RemainderData,
+        LedgerNanoStatus, PartiallySignedTransaction, PreparedTransactionData, RemainderData,
     },
     Error, Result,
 };
@@ -311,6 +311,60 @@ impl SecretManageExt for LedgerSecretManager {
 
         Ok(Unlocks::new(unlocks)?)
     }
+
+    async fn sign_transaction_essence_partially(
+        &self,
+        prepared_transaction: &PreparedTransactionData,
+        addresses: &[Address],
+    ) -> crate::Result<PartiallySignedTransaction> {
+        let mut partially_signed_transaction = PartiallySignedTransaction::new(prepared_transaction.clone());
+        // Only the inputs of this ledger are signed, the other inputs may not even have a chain.
+        let chains = signature_unlock_chains(&prepared_transaction.inputs_data, addresses)?;
+
+        if chains.is_empty() {
+            return Ok(partially_signed_transaction);
+        }
+
+        // lock the mutex to prevent multiple simultaneous requests to a ledger
+        let _lock = self.mutex.lock().await;
+
+        let coin_type = chains[0].1[1] & !HARDENED;
+        let bip32_account = chains[0].1[2] | HARDENED;
+        let input_bip32_indices = chains
+            .iter()
+            .map(|(_, bip32_indices)| LedgerBIP32Index {
+                bip32_change: bip32_indices[3] | HARDENED,
+                bip32_index: bip32_indices[4] | HARDENED,
+            })
+            .collect::<Vec<_>>();
+        let essence_hash = prepared_transaction.essence.hash().to_vec();
+
+        // The ledger can only sign a subset of the inputs by blind signing the essence hash
+        let ledger = get_ledger(coin_type, bip32_account, self.is_simulator)?;
+        log::debug!("[LEDGER] prepare_blind_signing");
+        log::debug!("[LEDGER] {:?} {:?}", input_bip32_indices, essence_hash);
+        ledger.prepare_blind_signing(input_bip32_indices, essence_hash)?;
+
+        // show essence to user
+        // if denied by user, it returns with `DeniedByUser` Error
+        log::debug!("[LEDGER] await user confirmation");
+        ledger.user_confirm()?;
+
+        // sign
+        let signature_bytes = ledger.sign(chains.len() as u16)?;
+        let mut unpacker = SliceUnpacker::new(&signature_bytes);
+
+        for (index, _) in chains {
+            match Unlock::unpack::<_, true>(&mut unpacker, &())? {
+                unlock @ Unlock::Signature(_) => {
+                    partially_signed_transaction.signature_unlocks[index] = Some(unlock);
+                }
+                _ => return Err(Error::MissingSignatureUnlock(index)),
+            }
+        }
+
+        Ok(partially_signed_transaction)
+    }
 }
 
 impl LedgerSecretManager {
@@ -36,7 +36,7 @@ use self::{mnemonic::MnemonicSecretManager, placeholder::PlaceholderSecretManage
 #[cfg(feature = "stronghold")]
 use crate::secret::types::StrongholdDto;
 use crate::{
-    api::{PreparedTransactionData, RemainderData},
+    api::{PartiallySignedTransaction, PreparedTransactionData, RemainderData},
     secret::types::InputSigningData,
 };
 
@@ -80,6 +80,21 @@ pub trait SecretManageExt {
         &self,
         prepared_transaction_data: &PreparedTransactionData,
     ) -> crate::Result<Unlocks>;
+
+    /// Signs the inputs of a transaction essence that are unlocked by the given ed25519 addresses, for transactions
+    /// whose inputs are controlled by multiple secret managers.
+    ///
+    /// The returned [PartiallySignedTransaction] only contains the signature unlocks of these inputs and is meant to
+    /// be merged with the ones of the other secret managers before building the final [Unlocks].
+    ///
+    /// Returns [`Error::UnsupportedOperation`](crate::Error::UnsupportedOperation) by default.
+    async fn sign_transaction_essence_partially(
+        &self,
+        _prepared_transaction_data: &PreparedTransactionData,
+        _addresses: &[Address],
+    ) -> crate::Result<PartiallySignedTransaction> {
+        Err(crate::Error::UnsupportedOperation("sign_transaction_essence_partially"))
+    }
 }
 
 /// Supported secret managers
@@ -287,6 +302,35 @@ impl SecretManageExt for SecretManager {
             SecretManager::Placeholder(_) => self.sign_transaction_essence(prepared_transaction_data).await,
         }
     }
+
+    async fn sign_transaction_essence_partially(
+        &self,
+        prepared_transaction_data: &PreparedTransactionData,
+        addresses: &[Address],
+    ) -> crate::Result<PartiallySignedTransaction> {
+        match self {
+            #[cfg(feature = "stronghold")]
+            SecretManager::Stronghold(_) => {
+                self.default_sign_transaction_essence_partially(prepared_transaction_data, addresses)
+                    .await
+            }
+            #[cfg(feature = "ledger_nano")]
+            SecretManager::LedgerNano(secret_manager) => {
+                secret_manager
+                    .sign_transaction_essence_partially(prepared_transaction_data, addresses)
+                    .await
+            }
+            SecretManager::Mnemonic(_) => {
+                self.default_sign_transaction_essence_partially(prepared_transaction_data, addresses)
+                    .await
+            }
+            SecretManager::Placeholder(secret_manager) => {
+                secret_manager
+                    .sign_transaction_essence_partially(prepared_transaction_data, addresses)
+                    .await
+            }
+        }
+    }
 }
 
 impl SecretManager {
@@ -298,58 +342,219 @@ impl SecretManager {
         // The hashed_essence gets signed
         let hashed_essence = prepared_transaction_data.essence.hash();
         let mut blocks = Vec::new();
-        let mut block_indexes = HashMap::<Address, usize>::new();
-
-        // Assuming inputs_data is ordered by address type
-        for (current_block_index, input) in prepared_transaction_data.inputs_data.iter().enumerate() {
-            // Get the address that is required to unlock the input
-            let (_, input_address) = Address::try_from_bech32(&input.bech32_address)?;
-
-            // Check if we already added an [Unlock] for this address
-            match block_indexes.get(&input_address) {
-                // If we already have an [Unlock] for this address, add a [Unlock] based on the address type
-                Some(block_index) => match input_address {
-                    Address::Alias(_alias) => blocks.push(Unlock::Alias(AliasUnlock::new(*block_index as u16)?)),
-                    Address::Ed25519(_ed25519) => {
-                        blocks.push(Unlock::Reference(ReferenceUnlock::new(*block_index as u16)?));
-                    }
-                    Address::Nft(_nft) => blocks.push(Unlock::Nft(NftUnlock::new(*block_index as u16)?)),
-                },
-                None => {
-                    // We can only sign ed25519 addresses and block_indexes needs to contain the alias or nft
-                    // address already at this point, because the reference index needs to be lower
-                    // than the current block index
-                    if !input_address.is_ed25519() {
-                        return Err(crate::Error::MissingInputWithEd25519Address);
-                    }
-
-                    let block = self
-                        .signature_unlock(input, &hashed_essence, &prepared_transaction_data.remainder)
-                        .await?;
-                    blocks.push(block);
-
-                    // Add the ed25519 address to the block_indexes, so it gets referenced if further inputs have
-                    // the same address in their unlock condition
-                    block_indexes.insert(input_address, current_block_index);
-                }
-            }
 
-            // When we have an alias or Nft output, we will add their alias or nft address to block_indexes,
-            // because they can be used to unlock outputs via [Unlock::Alias] or [Unlock::Nft],
-            // that have the corresponding alias or nft address in their unlock condition
-            match &input.output {
-                Output::Alias(alias_output) => block_indexes.insert(
-                    Address::Alias(alias_output.alias_address(input.output_id())),
-                    current_block_index,
+        for (input, block) in prepared_transaction_data
+            .inputs_data
+            .iter()
+            .zip(reference_unlocks(&prepared_transaction_data.inputs_data)?)
+        {
+            match block {
+                Some(block) => blocks.push(block),
+                None => blocks.push(
+                    self.signature_unlock(input, &hashed_essence, &prepared_transaction_data.remainder)
+                        .await?,
                 ),
-                Output::Nft(nft_output) => block_indexes.insert(
-                    Address::Nft(nft_output.nft_address(input.output_id())),
-                    current_block_index,
-                ),
-                _ => None,
-            };
+            }
         }
 
         Ok(Unlocks::new(blocks)?)
     }
 }
+
+impl SecretManager {
+    // Shared implementation for MnemonicSecretManager and StrongholdSecretManager
+    async fn default_sign_transaction_essence_partially(
+        &self,
+        prepared_transaction_data: &PreparedTransactionData,
+        addresses: &[Address],
+    ) -> crate::Result<PartiallySignedTransaction> {
+        let hashed_essence = prepared_transaction_data.essence.hash();
+        let mut partially_signed_transaction = PartiallySignedTransaction::new(prepared_transaction_data.clone());
+
+        for index in signature_unlock_indexes(&prepared_transaction_data.inputs_data, addresses)? {
+            partially_signed_transaction.signature_unlocks[index] = Some(
+                self.signature_unlock(
+                    &prepared_transaction_data.inputs_data[index],
+                    &hashed_essence,
+                    &prepared_transaction_data.remainder,
+                )
+                .await?,
+            );
+        }
+
+        Ok(partially_signed_transaction)
+    }
+}
+
+/// Returns the indexes of the inputs that need a signature unlock from one of the given addresses.
+pub(crate) fn signature_unlock_indexes(
+    inputs_data: &[InputSigningData],
+    addresses: &[Address],
+) -> crate::Result<Vec<usize>> {
+    let mut indexes = Vec::new();
+
+    for (index, (input, block)) in inputs_data.iter().zip(reference_unlocks(inputs_data)?).enumerate() {
+        let (_, input_address) = Address::try_from_bech32(&input.bech32_address)?;
+
+        if block.is_none() && addresses.contains(&input_address) {
+            indexes.push(index);
+        }
+    }
+
+    Ok(indexes)
+}
+
+/// Returns the indexes of the inputs that need a signature unlock from one of the given addresses, along with the BIP32
+/// indices of their chain, which have to share the same coin type and account. The inputs of the other signers don't
+/// need a chain.
+#[cfg(any(feature = "ledger_nano", test))]
+pub(crate) fn signature_unlock_chains(
+    inputs_data: &[InputSigningData],
+    addresses: &[Address],
+) -> crate::Result<Vec<(usize, Vec<u32>)>> {
+    let mut chains: Vec<(usize, Vec<u32>)> = Vec::new();
+
+    for index in signature_unlock_indexes(inputs_data, addresses)? {
+        let bip32_indices = inputs_data[index]
+            .chain
+            .as_ref()
+            .ok_or(crate::Error::InvalidBIP32ChainData)?
+            .segments()
+            .iter()
+            // XXX: "ser32(i)". RTFSC: [crypto::keys::slip10::Segment::from_u32()]
+            .map(|seg| u32::from_be_bytes(seg.bs()))
+            .collect::<Vec<_>>();
+
+        // coin_type and account_index should be the same in each input
+        if bip32_indices.len() != 5
+            || chains
+                .first()
+                .is_some_and(|(_, first)| first[1..3] != bip32_indices[1..3])
+        {
+            return Err(crate::Error::InvalidBIP32ChainData);
+        }
+
+        chains.push((index, bip32_indices));
+    }
+
+    Ok(chains)
+}
+
+/// Computes the [Unlock]s of the inputs that can be unlocked by referencing an earlier input, `None` for the inputs
+/// that need a signature unlock.
+pub(crate) fn reference_unlocks(inputs_data: &[InputSigningData]) -> crate::Result<Vec<Option<Unlock>>> {
+    let mut blocks = Vec::new();
+    let mut block_indexes = HashMap::<Address, usize>::new();
+
+    // Assuming inputs_data is ordered by address type
+    for (current_block_index, input) in inputs_data.iter().enumerate() {
+        // Get the address that is required to unlock the input
+        let (_, input_address) = Address::try_from_bech32(&input.bech32_address)?;
+
+        // Check if we already added an [Unlock] for this address
+        match block_indexes.get(&input_address) {
+            // If we already have an [Unlock] for this address, add a [Unlock] based on the address type
+            Some(block_index) => match input_address {
+                Address::Alias(_alias) => blocks.push(Some(Unlock::Alias(AliasUnlock::new(*block_index as u16)?))),
+                Address::Ed25519(_ed25519) => {
+                    blocks.push(Some(Unlock::Reference(ReferenceUnlock::new(*block_index as u16)?)));
+                }
+                Address::Nft(_nft) => blocks.push(Some(Unlock::Nft(NftUnlock::new(*block_index as u16)?))),
+            },
+            None => {
+                // We can only sign ed25519 addresses and block_indexes needs to contain the alias or nft
+                // address already at this point, because the reference index needs to be lower
+                // than the current block index
+                if !input_address.is_ed25519() {
+                    return Err(crate::Error::MissingInputWithEd25519Address);
+                }
+
+                blocks.push(None);
+
+                // Add the ed25519 address to the block_indexes, so it gets referenced if further inputs have
+                // the same address in their unlock condition
+                block_indexes.insert(input_address, current_block_index);
+            }
+        }
+
+        // When we have an alias or Nft output, we will add their alias or nft address to block_indexes,
+        // because they can be used to unlock outputs via [Unlock::Alias] or [Unlock::Nft],
+        // that have the corresponding alias or nft address in their unlock condition
+        match &input.output {
+            Output::Alias(alias_output) => block_indexes.insert(
+                Address::Alias(alias_output.alias_address(input.output_id())),
+                current_block_index,
+            ),
+            Output::Nft(nft_output) => block_indexes.insert(
+                Address::Nft(nft_output.nft_address(input.output_id())),
+                current_block_index,
+            ),
+            _ => None,
+        };
+    }
+
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto::keys::slip10::Chain;
+    use iota_types::block::{
+        address::Ed25519Address,
+        output::{
+            unlock_condition::{AddressUnlockCondition, UnlockCondition},
+            BasicOutputBuilder, OutputId,
+        },
+        rand::{block::rand_block_id, transaction::rand_transaction_id},
+    };
+
+    use super::*;
+    use crate::{
+        constants::{IOTA_COIN_TYPE, SHIMMER_COIN_TYPE},
+        secret::types::OutputMetadata,
+    };
+
+    fn input(address: Address, chain: Option<Vec<u32>>) -> InputSigningData {
+        InputSigningData {
+            output: BasicOutputBuilder::new_with_amount(1_000_000)
+                .unwrap()
+                .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)))
+                .finish_output(1_813_620_509_061_365)
+                .unwrap(),
+            output_metadata: OutputMetadata::new(
+                rand_block_id(),
+                OutputId::new(rand_transaction_id(), 0).unwrap(),
+                false,
+                None,
+                None,
+                None,
+                0,
+                0,
+                0,
+            ),
+            chain: chain.map(Chain::from_u32_hardened),
+            bech32_address: address.to_bech32("rms"),
+        }
+    }
+
+    #[test]
+    fn signature_unlock_chains_ignore_foreign_inputs() {
+        let own = Address::Ed25519(Ed25519Address::new([1; 32]));
+        let foreign = Address::Ed25519(Ed25519Address::new([2; 32]));
+        let inputs = vec![
+            // The inputs of the other signers have no chain, or one of another coin type or account.
+            input(foreign, None),
+            input(own, Some(vec![44, SHIMMER_COIN_TYPE, 0, 0, 1])),
+            input(foreign, Some(vec![44, IOTA_COIN_TYPE, 3, 0, 0])),
+            input(own, Some(vec![44, SHIMMER_COIN_TYPE, 0, 0, 1])),
+        ];
+
+        let chains = signature_unlock_chains(&inputs, &[own]).unwrap();
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].0, 1);
+        assert_eq!(chains[0].1[1], SHIMMER_COIN_TYPE | 1 << 31);
+
+        assert!(signature_unlock_chains(&inputs, &[own, foreign]).is_err());
+        assert!(signature_unlock_chains(&inputs, &[]).unwrap().is_empty());
+    }
+}
@@ -12,7 +12,7 @@ use iota_types::block::{
 };
 
 use super::{types::InputSigningData, GenerateAddressOptions, SecretManage, SecretManageExt};
-use crate::secret::{PreparedTransactionData, RemainderData};
+use crate::secret::{PartiallySignedTransaction, PreparedTransactionData, RemainderData};
 
 /// Secret manager that is only useful to prevent accidental address generation in a wallet
 /// that has an offline counterpart for address generation and signing.
@@ -49,4 +49,12 @@ impl SecretManageExt for PlaceholderSecretManager {
     ) -> crate::Result<Unlocks> {
         return Err(crate::Error::PlaceholderSecretManager);
     }
+
+    async fn sign_transaction_essence_partially(
+        &self,
+        _prepared_transaction_data: &PreparedTransactionData,
+        _addresses: &[Address],
+    ) -> crate::Result<PartiallySignedTransaction> {
+        Err(crate::Error::PlaceholderSecretManager)
+    }
 }
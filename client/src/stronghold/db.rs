@@ -9,7 +9,10 @@ use async_trait::async_trait;
 use crypto::ciphers::chacha;
 
 use super::{common::PRIVATE_DATA_CLIENT_PATH, StrongholdAdapter};
-use crate::{db::DatabaseProvider, Error, Result};
+use crate::{
+    db::{DatabaseProvider, WriteBatch, WriteOperation},
+    Error, Result,
+};
 
 #[async_trait]
 impl DatabaseProvider for StrongholdAdapter {
@@ -70,6 +73,82 @@ impl DatabaseProvider for StrongholdAdapter {
             .store()
             .delete(k)?)
     }
+
+    async fn scan_prefix(&mut self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let mut keys = self
+            .stronghold
+            .lock()
+            .await
+            .get_client(PRIVATE_DATA_CLIENT_PATH)?
+            .store()
+            .keys()?
+            .into_iter()
+            .filter(|k| k.starts_with(prefix))
+            .collect::<Vec<_>>();
+        keys.sort();
+
+        let mut records = Vec::with_capacity(keys.len());
+
+        for k in keys {
+            // A record may have expired in the meantime.
+            if let Some(v) = self.get(&k).await? {
+                records.push((k, v));
+            }
+        }
+
+        Ok(records)
+    }
+
+    async fn write_batch(&mut self, batch: WriteBatch) -> Result<()> {
+        // Encrypt everything upfront so that nothing can fail while the store is being written.
+        let mut operations = Vec::with_capacity(batch.len());
+        {
+            let locked_key_provider = self.key_provider.lock().await;
+            let key_provider = if let Some(key_provider) = &*locked_key_provider {
+                key_provider
+            } else {
+                return Err(Error::StrongholdKeyCleared);
+            };
+            let buffer = key_provider.try_unlock()?;
+            let buffer_ref = buffer.borrow();
+
+            for operation in batch {
+                operations.push(match operation {
+                    WriteOperation::Insert(k, v) => (k, Some(chacha::aead_encrypt(buffer_ref.deref(), &v)?)),
+                    WriteOperation::Delete(k) => (k, None),
+                });
+            }
+        }
+
+        let stronghold = self.stronghold.lock().await;
+        let store = stronghold.get_client(PRIVATE_DATA_CLIENT_PATH)?.store();
+        // The previous values of the written keys, to restore them if a write fails.
+        let mut previous = Vec::with_capacity(operations.len());
+
+        for (k, v) in operations {
+            let result = match v {
+                Some(v) => store.insert(k.clone(), v, None),
+                None => store.delete(&k),
+            };
+
+            match result {
+                Ok(v) => previous.push((k, v)),
+                Err(e) => {
+                    for (k, v) in previous.into_iter().rev() {
+                        match v {
+                            Some(v) => store.insert(k, v, None).map(|_| ()),
+                            None => store.delete(&k).map(|_| ()),
+                        }
+                        .ok();
+                    }
+
+                    return Err(e.into());
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 mod tests {
@@ -78,7 +157,7 @@ mod tests {
         use std::fs;
 
         use super::StrongholdAdapter;
-        use crate::db::DatabaseProvider;
+        use crate::db::{DatabaseProvider, WriteBatch};
 
         let snapshot_path = "test_stronghold_db.stronghold";
         let mut stronghold = StrongholdAdapter::builder()
@@ -110,6 +189,18 @@ mod tests {
         assert!(matches!(stronghold.get(b"test-1").await, Ok(None)));
         assert!(matches!(stronghold.get(b"test-2").await, Ok(None)));
 
+        let mut batch = WriteBatch::new();
+        batch
+            .insert(b"test-1", b"1")
+            .insert(b"test-0", b"0")
+            .insert(b"other", b"other");
+        stronghold.write_batch(batch).await.unwrap();
+
+        assert_eq!(
+            stronghold.scan_prefix(b"test-").await.unwrap(),
+            vec![(b"test-0".to_vec(), b"0".to_vec()), (b"test-1".to_vec(), b"1".to_vec())]
+        );
+
         fs::remove_file(snapshot_path).unwrap();
     }
 }
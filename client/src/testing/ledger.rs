@@ -0,0 +1,475 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! In-memory ledger backing the mock node.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::Duration,
+};
+
+use crypto::{
+    hashes::{blake2b::Blake2b256, Digest},
+    signatures::ed25519,
+};
+use iota_types::{
+    api::{
+        dto::LedgerInclusionStateDto,
+        response::{BlockMetadataResponse, OutputMetadataResponse, UtxoChangesResponse},
+    },
+    block::{
+        input::Input,
+        output::{Output, OutputId},
+        parent::Parents,
+        payload::{
+            milestone::{MerkleHasher, MerkleRoot, MilestoneEssence, MilestoneId, MilestoneIndex, MilestoneOptions},
+            transaction::{TransactionEssence, TransactionId},
+            MilestonePayload, Payload, TransactionPayload,
+        },
+        protocol::ProtocolParameters,
+        semantic::{semantic_validation, ConflictReason, ValidationContext},
+        signature::{Ed25519Signature, Signature},
+        Block, BlockBuilder, BlockId,
+    },
+};
+
+use crate::Result;
+
+/// A block stored by the mock node together with its metadata.
+#[derive(Clone, Debug)]
+pub(crate) struct MockBlock {
+    pub(crate) block: Block,
+    pub(crate) metadata: BlockMetadataResponse,
+}
+
+/// An output stored by the mock node together with its metadata.
+#[derive(Clone, Debug)]
+pub(crate) struct MockOutput {
+    pub(crate) output: Output,
+    pub(crate) metadata: OutputMetadataResponse,
+}
+
+/// A milestone issued by the mock node together with the UTXO changes it applied.
+#[derive(Clone, Debug)]
+pub(crate) struct MockMilestone {
+    pub(crate) payload: MilestonePayload,
+    pub(crate) utxo_changes: UtxoChangesResponse,
+}
+
+/// In-memory ledger of a mock node.
+///
+/// By default, every submitted block is immediately confirmed by its own milestone. Milestones are signed by a
+/// coordinator key that is generated when the ledger is created.
+pub(crate) struct MockLedger {
+    pub(crate) protocol_parameters: ProtocolParameters,
+    coordinator_key: ed25519::SecretKey,
+    genesis_block_id: BlockId,
+    pub(crate) blocks: HashMap<BlockId, MockBlock>,
+    pub(crate) outputs: HashMap<OutputId, MockOutput>,
+    pub(crate) included_blocks: HashMap<TransactionId, BlockId>,
+    // Milestone with index `i` is stored at position `i - 1`.
+    pub(crate) milestones: Vec<MockMilestone>,
+    pub(crate) tips: Vec<BlockId>,
+    pub(crate) auto_milestones: bool,
+    // Index of the latest milestone whose blocks and milestone data were pruned.
+    pub(crate) pruning_index: u32,
+    // Statuses of the errors returned to the next requests instead of routing them, along with their `Retry-After`.
+    pub(crate) failures: VecDeque<(u16, Option<Duration>)>,
+    // Statuses of the errors returned to the next block submissions, along with whether the blocks are still added.
+    pub(crate) block_post_failures: VecDeque<(u16, bool)>,
+    pub(crate) request_count: usize,
+    genesis_output_count: u64,
+}
+
+impl MockLedger {
+    pub(crate) fn new(protocol_parameters: ProtocolParameters) -> Result<Self> {
+        // The genesis block is the only solid entry point of the mock tangle, it is referenced by the non-existing
+        // milestone 0.
+        let genesis_block = BlockBuilder::new(Parents::new(vec![BlockId::null()])?)
+            .with_protocol_version(protocol_parameters.protocol_version())
+            .finish()?;
+        let genesis_block_id = genesis_block.id();
+        let mut blocks = HashMap::new();
+        blocks.insert(
+            genesis_block_id,
+            MockBlock {
+                metadata: block_metadata(&genesis_block, Some((0, 0)), None),
+                block: genesis_block,
+            },
+        );
+
+        Ok(Self {
+            protocol_parameters,
+            coordinator_key: ed25519::SecretKey::generate()?,
+            genesis_block_id,
+            blocks,
+            outputs: HashMap::new(),
+            included_blocks: HashMap::new(),
+            milestones: Vec::new(),
+            tips: vec![genesis_block_id],
+            auto_milestones: true,
+            pruning_index: 0,
+            failures: VecDeque::new(),
+            block_post_failures: VecDeque::new(),
+            request_count: 0,
+            genesis_output_count: 0,
+        })
+    }
+
+    /// Returns the public key of the coordinator signing the milestones.
+    pub(crate) fn coordinator_public_key(&self) -> [u8; 32] {
+        self.coordinator_key.public_key().to_bytes()
+    }
+
+    /// Returns the index of the latest milestone, 0 if no milestone was issued yet.
+    pub(crate) fn latest_milestone_index(&self) -> u32 {
+        self.milestones.len() as u32
+    }
+
+    /// Returns the timestamp of the latest milestone, the current time if no milestone was issued yet.
+    pub(crate) fn latest_milestone_timestamp(&self) -> u32 {
+        self.milestones
+            .last()
+            .map_or_else(unix_timestamp, |milestone| milestone.payload.essence().timestamp())
+    }
+
+    /// Returns the milestone with the given index.
+    pub(crate) fn milestone(&self, index: u32) -> Option<&MockMilestone> {
+        index
+            .checked_sub(1)
+            .and_then(|position| self.milestones.get(position as usize))
+    }
+
+    /// Returns the milestone with the given index, if it wasn't pruned.
+    pub(crate) fn unpruned_milestone(&self, index: u32) -> Option<&MockMilestone> {
+        self.milestone(index).filter(|_| index > self.pruning_index)
+    }
+
+    /// Prunes the milestones up to the given index, along with the blocks they referenced.
+    pub(crate) fn prune(&mut self, index: u32) {
+        self.pruning_index = self.pruning_index.max(index.min(self.latest_milestone_index()));
+
+        let pruning_index = self.pruning_index;
+        self.blocks.retain(|_, mock_block| {
+            !matches!(mock_block.metadata.referenced_by_milestone_index, Some(index) if index > 0 && index <= pruning_index)
+        });
+        let blocks = &self.blocks;
+        self.included_blocks.retain(|_, block_id| blocks.contains_key(block_id));
+    }
+
+    /// Adds an unspent output to the ledger without a transaction, as if it was part of the genesis snapshot.
+    pub(crate) fn add_output(&mut self, output: Output) -> Result<OutputId> {
+        self.genesis_output_count += 1;
+        let transaction_id = TransactionId::new(
+            Blake2b256::digest([b"mock-genesis".as_slice(), &self.genesis_output_count.to_le_bytes()].concat()).into(),
+        );
+        let output_id = OutputId::new(transaction_id, 0)?;
+        let metadata = OutputMetadataResponse {
+            block_id: self.genesis_block_id.to_string(),
+            transaction_id: transaction_id.to_string(),
+            output_index: 0,
+            is_spent: false,
+            milestone_index_spent: None,
+            milestone_timestamp_spent: None,
+            transaction_id_spent: None,
+            milestone_index_booked: self.latest_milestone_index(),
+            milestone_timestamp_booked: self.latest_milestone_timestamp(),
+            ledger_index: self.latest_milestone_index(),
+        };
+
+        self.outputs.insert(output_id, MockOutput { output, metadata });
+
+        Ok(output_id)
+    }
+
+    /// Adds a block to the tangle and, unless milestones are issued manually, confirms it with a new milestone.
+    pub(crate) fn submit_block(&mut self, block: Block) -> Result<BlockId> {
+        let block_id = block.id();
+
+        if self.blocks.contains_key(&block_id) {
+            return Ok(block_id);
+        }
+
+        self.tips
+            .retain(|tip| !block.parents().iter().any(|parent| parent == tip));
+        self.tips.push(block_id);
+        // Tips beyond the maximum number of parents are dropped and will never be referenced.
+        let excess_tips = self.tips.len().saturating_sub(*Parents::COUNT_RANGE.end() as usize);
+        self.tips.drain(..excess_tips);
+
+        self.blocks.insert(
+            block_id,
+            MockBlock {
+                metadata: block_metadata(&block, None, None),
+                block,
+            },
+        );
+
+        if self.auto_milestones {
+            self.issue_milestone()?;
+        }
+
+        Ok(block_id)
+    }
+
+    /// Drops the tips that aren't referenced yet, so that no milestone will reference them and their metadata asks for
+    /// a reattachment, like blocks below the max depth of a node.
+    pub(crate) fn orphan_tips(&mut self) {
+        let blocks = &mut self.blocks;
+
+        self.tips.retain(|tip| match blocks.get_mut(tip) {
+            Some(mock_block) if mock_block.metadata.referenced_by_milestone_index.is_none() => {
+                mock_block.metadata.should_reattach = Some(true);
+                false
+            }
+            _ => true,
+        });
+
+        if self.tips.is_empty() {
+            self.tips.push(self.genesis_block_id);
+        }
+    }
+
+    /// Issues a new milestone referencing the current tips, confirming all the blocks that weren't referenced yet and
+    /// applying their transactions to the ledger in white-flag order. Returns the index of the milestone.
+    pub(crate) fn issue_milestone(&mut self) -> Result<u32> {
+        let index = self.latest_milestone_index() + 1;
+        let timestamp = unix_timestamp().max(self.latest_milestone_timestamp());
+        let parents = Parents::new(self.tips.clone())?;
+        let mut created_outputs = Vec::new();
+        let mut consumed_outputs = Vec::new();
+        let included_block_ids = self.white_flag_order(&parents);
+        let mut applied_block_ids = Vec::new();
+
+        for (white_flag_index, block_id) in included_block_ids.iter().copied().enumerate() {
+            let block = self.blocks[&block_id].block.clone();
+            let inclusion = match block.payload() {
+                Some(Payload::Transaction(transaction)) => Some(self.apply_transaction(
+                    block_id,
+                    transaction,
+                    index,
+                    timestamp,
+                    &mut created_outputs,
+                    &mut consumed_outputs,
+                )?),
+                _ => None,
+            };
+
+            if inclusion == Some(ConflictReason::None) {
+                applied_block_ids.push(block_id);
+            }
+            if let Some(mock_block) = self.blocks.get_mut(&block_id) {
+                mock_block.metadata = block_metadata(&block, Some((index, white_flag_index as u32)), inclusion);
+            }
+        }
+
+        self.sign_milestone(
+            index,
+            timestamp,
+            parents,
+            MerkleHasher::digest(&included_block_ids),
+            MerkleHasher::digest(&applied_block_ids),
+            UtxoChangesResponse {
+                index,
+                created_outputs,
+                consumed_outputs,
+            },
+        )?;
+
+        Ok(index)
+    }
+
+    /// Returns the blocks that are not referenced yet in the past cone of `parents`, in white-flag order: a depth-first
+    /// post-order traversal visiting the parents of every block in order.
+    fn white_flag_order(&self, parents: &Parents) -> Vec<BlockId> {
+        let mut visited = HashSet::new();
+        let mut order = Vec::new();
+        // The flag tells whether the parents of the block were already traversed.
+        let mut stack = Vec::new();
+        push_parents(&mut stack, parents);
+
+        while let Some((block_id, traversed)) = stack.pop() {
+            if traversed {
+                order.push(block_id);
+                continue;
+            }
+            if !visited.insert(block_id) {
+                continue;
+            }
+            if let Some(mock_block) = self.blocks.get(&block_id) {
+                if mock_block.metadata.referenced_by_milestone_index.is_none() {
+                    stack.push((block_id, true));
+                    push_parents(&mut stack, mock_block.block.parents());
+                }
+            }
+        }
+
+        order
+    }
+
+    /// Applies a transaction to the ledger if it is valid and returns its conflict reason.
+    fn apply_transaction(
+        &mut self,
+        block_id: BlockId,
+        transaction: &TransactionPayload,
+        index: u32,
+        timestamp: u32,
+        created_outputs: &mut Vec<String>,
+        consumed_outputs: &mut Vec<String>,
+    ) -> Result<ConflictReason> {
+        let transaction_id = transaction.id();
+        let conflict = self.validate_transaction(transaction, timestamp)?;
+
+        if conflict != ConflictReason::None {
+            return Ok(conflict);
+        }
+
+        let TransactionEssence::Regular(essence) = transaction.essence();
+
+        for input in essence.inputs() {
+            if let Input::Utxo(input) = input {
+                if let Some(consumed) = self.outputs.get_mut(input.output_id()) {
+                    consumed.metadata.is_spent = true;
+                    consumed.metadata.milestone_index_spent = Some(index);
+                    consumed.metadata.milestone_timestamp_spent = Some(timestamp);
+                    consumed.metadata.transaction_id_spent = Some(transaction_id.to_string());
+                    consumed_outputs.push(input.output_id().to_string());
+                }
+            }
+        }
+
+        for (output_index, output) in essence.outputs().iter().enumerate() {
+            let output_id = OutputId::new(transaction_id, output_index as u16)?;
+            self.outputs.insert(
+                output_id,
+                MockOutput {
+                    output: output.clone(),
+                    metadata: OutputMetadataResponse {
+                        block_id: block_id.to_string(),
+                        transaction_id: transaction_id.to_string(),
+                        output_index: output_index as u16,
+                        is_spent: false,
+                        milestone_index_spent: None,
+                        milestone_timestamp_spent: None,
+                        transaction_id_spent: None,
+                        milestone_index_booked: index,
+                        milestone_timestamp_booked: timestamp,
+                        ledger_index: index,
+                    },
+                },
+            );
+            created_outputs.push(output_id.to_string());
+        }
+
+        self.included_blocks.insert(transaction_id, block_id);
+
+        Ok(conflict)
+    }
+
+    fn validate_transaction(&self, transaction: &TransactionPayload, timestamp: u32) -> Result<ConflictReason> {
+        let TransactionEssence::Regular(essence) = transaction.essence();
+        let mut inputs = Vec::new();
+
+        for input in essence.inputs() {
+            let Input::Utxo(input) = input else {
+                return Ok(ConflictReason::SemanticValidationFailed);
+            };
+            match self.outputs.get(input.output_id()) {
+                Some(consumed) if consumed.metadata.is_spent => return Ok(ConflictReason::InputUtxoAlreadySpent),
+                Some(consumed) => inputs.push((*input.output_id(), &consumed.output)),
+                None => return Ok(ConflictReason::InputUtxoNotFound),
+            }
+        }
+
+        let transaction_id = transaction.id();
+        let context = ValidationContext::new(
+            &transaction_id,
+            essence,
+            inputs.iter().map(|(output_id, output)| (output_id, *output)),
+            transaction.unlocks(),
+            timestamp,
+        );
+
+        Ok(semantic_validation(context, &inputs, transaction.unlocks())?)
+    }
+
+    fn sign_milestone(
+        &mut self,
+        index: u32,
+        timestamp: u32,
+        parents: Parents,
+        inclusion_merkle_root: MerkleRoot,
+        applied_merkle_root: MerkleRoot,
+        utxo_changes: UtxoChangesResponse,
+    ) -> Result<()> {
+        let previous_milestone_id = self
+            .milestones
+            .last()
+            .map_or_else(MilestoneId::null, |milestone| milestone.payload.id());
+        let essence = MilestoneEssence::new(
+            MilestoneIndex::new(index),
+            timestamp,
+            self.protocol_parameters.protocol_version(),
+            previous_milestone_id,
+            parents,
+            inclusion_merkle_root,
+            applied_merkle_root,
+            Vec::new(),
+            MilestoneOptions::new(Vec::new())?,
+        )?;
+        let signature = self.coordinator_key.sign(&essence.hash());
+        let payload = MilestonePayload::new(
+            essence,
+            vec![Signature::from(Ed25519Signature::new(
+                self.coordinator_public_key(),
+                signature.to_bytes(),
+            ))],
+        )?;
+
+        self.milestones.push(MockMilestone { payload, utxo_changes });
+
+        Ok(())
+    }
+}
+
+/// Pushes the parents in reverse order so that they are popped in order.
+fn push_parents(stack: &mut Vec<(BlockId, bool)>, parents: &Parents) {
+    let first = stack.len();
+    stack.extend(parents.iter().map(|parent| (*parent, false)));
+    stack[first..].reverse();
+}
+
+/// Builds the metadata of a block, `referenced` being the index of the milestone referencing it and its white-flag
+/// index.
+fn block_metadata(
+    block: &Block,
+    referenced: Option<(u32, u32)>,
+    inclusion: Option<ConflictReason>,
+) -> BlockMetadataResponse {
+    let (ledger_inclusion_state, conflict_reason) = match (referenced, inclusion) {
+        (None, _) => (None, None),
+        (Some(_), None) => (Some(LedgerInclusionStateDto::NoTransaction), None),
+        (Some(_), Some(ConflictReason::None)) => (Some(LedgerInclusionStateDto::Included), None),
+        (Some(_), Some(conflict)) => (Some(LedgerInclusionStateDto::Conflicting), Some(conflict as u8)),
+    };
+
+    BlockMetadataResponse {
+        block_id: block.id().to_string(),
+        parents: block.parents().iter().map(BlockId::to_string).collect(),
+        is_solid: true,
+        referenced_by_milestone_index: referenced.map(|(index, _)| index),
+        milestone_index: None,
+        ledger_inclusion_state,
+        conflict_reason,
+        white_flag_index: referenced.map(|(_, white_flag_index)| white_flag_index),
+        should_promote: None,
+        should_reattach: None,
+    }
+}
+
+fn unix_timestamp() -> u32 {
+    instant::SystemTime::now()
+        .duration_since(instant::SystemTime::UNIX_EPOCH)
+        .expect("time went backwards")
+        .as_secs() as u32
+}
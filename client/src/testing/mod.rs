@@ -0,0 +1,234 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! An in-process mock node to test the client without network access.
+//!
+//! The [`MockNode`] serves the core and indexer routes used by the client from an in-memory ledger. By default, every
+//! submitted block is confirmed right away by a new milestone, signed by a coordinator key generated by the mock node.
+//!
+//! ```no_run
+//! # use iota_client::{block::output::{unlock_condition::{AddressUnlockCondition, UnlockCondition}, BasicOutputBuilder}, testing::MockNode, Client, Result};
+//! # #[tokio::main]
+//! # async fn main() -> Result<()> {
+//! # let address = iota_client::block::address::Address::Ed25519([0; 32].into());
+//! let mock = MockNode::start()?;
+//! mock.add_output(
+//!     BasicOutputBuilder::new_with_amount(1_000_000)?
+//!         .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)))
+//!         .finish_output(mock.protocol_parameters().token_supply())?,
+//! )?;
+//!
+//! let client = Client::builder().with_node(mock.url())?.finish()?;
+//! # Ok(())}
+//! ```
+
+mod ledger;
+mod routes;
+
+use std::{
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use hyper::{
+    server::Server,
+    service::{make_service_fn, service_fn},
+};
+use iota_types::block::{
+    output::{Output, OutputId, RentStructure},
+    protocol::ProtocolParameters,
+};
+use tokio::sync::oneshot;
+
+use self::ledger::MockLedger;
+use crate::{Error, Result};
+
+/// A local HTTP server mimicking a node, backed by an in-memory ledger.
+///
+/// The server runs on its own thread and is shut down when the [`MockNode`] is dropped.
+pub struct MockNode {
+    url: String,
+    ledger: Arc<Mutex<MockLedger>>,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockNode {
+    /// Starts a mock node with the default testing protocol parameters.
+    ///
+    /// The minimum PoW score is 0, so blocks can be built locally without doing any proof of work.
+    pub fn start() -> Result<Self> {
+        Self::start_with_protocol_parameters(ProtocolParameters::new(
+            ProtocolParameters::default().protocol_version(),
+            String::from("testnet"),
+            String::from("rms"),
+            0,
+            15,
+            RentStructure::default(),
+            1_813_620_509_061_365,
+        )?)
+    }
+
+    /// Starts a mock node with the given protocol parameters.
+    pub fn start_with_protocol_parameters(protocol_parameters: ProtocolParameters) -> Result<Self> {
+        let ledger = Arc::new(Mutex::new(MockLedger::new(protocol_parameters)?));
+        let listener = TcpListener::bind("127.0.0.1:0").map_err(|e| Error::MockNode(e.to_string()))?;
+        let url = format!(
+            "http://{}",
+            listener.local_addr().map_err(|e| Error::MockNode(e.to_string()))?
+        );
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| Error::MockNode(e.to_string()))?;
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+
+        let ledger_ = ledger.clone();
+        let handle = std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("failed to create Tokio runtime");
+
+            runtime.block_on(async move {
+                // The listener can only be registered from within the runtime.
+                let server = match Server::from_tcp(listener) {
+                    Ok(server) => {
+                        started_tx.send(Ok(())).ok();
+                        server
+                    }
+                    Err(e) => {
+                        started_tx.send(Err(Error::MockNode(e.to_string()))).ok();
+                        return;
+                    }
+                };
+                let service = make_service_fn(move |_| {
+                    let ledger = ledger_.clone();
+                    async move {
+                        Ok::<_, std::convert::Infallible>(service_fn(move |request| {
+                            routes::handle(ledger.clone(), request)
+                        }))
+                    }
+                });
+
+                // Connections are not closed gracefully since clients may keep them alive, they are dropped with the
+                // runtime instead.
+                tokio::select! {
+                    res = server.serve(service) => {
+                        if let Err(e) = res {
+                            log::error!("mock node stopped: {e}");
+                        }
+                    }
+                    _ = shutdown_rx => {}
+                }
+            });
+        });
+
+        started_rx
+            .recv()
+            .map_err(|_| Error::MockNode("mock node thread stopped".to_string()))??;
+
+        Ok(Self {
+            url,
+            ledger,
+            shutdown: Some(shutdown_tx),
+            handle: Some(handle),
+        })
+    }
+
+    /// Returns the URL of the mock node, to be used with [`ClientBuilder::with_node`](crate::ClientBuilder::with_node).
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the protocol parameters of the mock node.
+    pub fn protocol_parameters(&self) -> ProtocolParameters {
+        self.lock().protocol_parameters.clone()
+    }
+
+    /// Returns the public key of the coordinator signing the milestones of the mock node.
+    pub fn coordinator_public_key(&self) -> [u8; 32] {
+        self.lock().coordinator_public_key()
+    }
+
+    /// Returns the index of the latest milestone, 0 if no block was submitted yet.
+    pub fn latest_milestone_index(&self) -> u32 {
+        self.lock().latest_milestone_index()
+    }
+
+    /// Sets whether every submitted block is confirmed right away by a new milestone, which is the default. When
+    /// disabled, milestones are only issued with [`issue_milestone()`](MockNode::issue_milestone).
+    pub fn set_auto_milestones(&self, auto_milestones: bool) {
+        self.lock().auto_milestones = auto_milestones;
+    }
+
+    /// Issues a new milestone referencing the current tips, which confirms all the blocks submitted since the previous
+    /// milestone, and returns its index.
+    pub fn issue_milestone(&self) -> Result<u32> {
+        self.lock().issue_milestone()
+    }
+
+    /// Prunes the milestones up to `index` and the blocks they referenced, which are then not found anymore. The
+    /// pruning index is returned in the node info.
+    pub fn prune(&self, index: u32) {
+        self.lock().prune(index);
+    }
+
+    /// Adds an unspent output to the ledger, as if it was part of the genesis snapshot, and returns its id.
+    pub fn add_output(&self, output: Output) -> Result<OutputId> {
+        self.lock().add_output(output)
+    }
+
+    /// Fails the next `count` requests with the HTTP status `status`, without processing them.
+    pub fn fail_requests(&self, status: u16, count: usize) {
+        self.lock()
+            .failures
+            .extend(std::iter::repeat((status, None)).take(count));
+    }
+
+    /// Fails the next `count` requests with the HTTP status `status` and a `Retry-After` header of `retry_after`,
+    /// rounded up to seconds.
+    pub fn fail_requests_with_retry_after(&self, status: u16, count: usize, retry_after: Duration) {
+        self.lock()
+            .failures
+            .extend(std::iter::repeat((status, Some(retry_after))).take(count));
+    }
+
+    /// Fails the next `count` block submissions with the HTTP status `status`. The blocks are still added to the
+    /// tangle if `processed`, like when a node fails after processing a request.
+    pub fn fail_block_posts(&self, status: u16, count: usize, processed: bool) {
+        self.lock()
+            .block_post_failures
+            .extend(std::iter::repeat((status, processed)).take(count));
+    }
+
+    /// Drops the tips that aren't referenced by a milestone yet: they will never be and their metadata asks for a
+    /// reattachment.
+    pub fn orphan_tips(&self) {
+        self.lock().orphan_tips()
+    }
+
+    /// Returns the number of requests received by the mock node, including the failed ones.
+    pub fn request_count(&self) -> usize {
+        self.lock().request_count
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockLedger> {
+        // A poisoned ledger means a route panicked, which is a bug of the mock node.
+        self.ledger.lock().expect("mock ledger poisoned")
+    }
+}
+
+impl Drop for MockNode {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            // The server may have stopped already.
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
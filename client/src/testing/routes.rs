@@ -0,0 +1,471 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Core and indexer routes served by the mock node.
+
+use std::{
+    convert::Infallible,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use hyper::{body::Bytes, header, Body, Method, Request, Response, StatusCode};
+use iota_types::{
+    api::response::{
+        BaseTokenResponse, ConfirmedMilestoneResponse, InfoResponse, LatestMilestoneResponse, MetricsResponse,
+        OutputWithMetadataResponse, RoutesResponse, StatusResponse, SubmitBlockResponse, TipsResponse,
+    },
+    block::{
+        address::Address,
+        output::{
+            dto::{OutputDto, RentStructureDto},
+            AliasId, FoundryId, NftId, Output, OutputId,
+        },
+        payload::{dto::MilestonePayloadDto, milestone::MilestoneId, transaction::TransactionId},
+        protocol::dto::ProtocolParametersDto,
+        Block, BlockDto, BlockId,
+    },
+};
+use packable::PackableExt;
+use serde::Serialize;
+
+use super::ledger::{MockLedger, MockOutput};
+use crate::node_api::indexer::OutputIdsResponse;
+
+const RAW_CONTENT_TYPE: &str = "application/vnd.iota.serializer-v1";
+
+/// The result of a route handler, errors are turned into HTTP error responses.
+type RouteResult = std::result::Result<Response<Body>, (StatusCode, String)>;
+
+pub(crate) async fn handle(
+    ledger: Arc<Mutex<MockLedger>>,
+    request: Request<Body>,
+) -> std::result::Result<Response<Body>, Infallible> {
+    let method = request.method().clone();
+    let path = request.uri().path().trim_matches('/').to_string();
+    let query = request.uri().query().map(ToString::to_string);
+    let raw = request
+        .headers()
+        .get(header::ACCEPT)
+        .is_some_and(|accept| accept == RAW_CONTENT_TYPE);
+    let raw_body = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|content_type| content_type == RAW_CONTENT_TYPE);
+    let body = match hyper::body::to_bytes(request.into_body()).await {
+        Ok(body) => body,
+        Err(e) => return Ok(error_response(StatusCode::BAD_REQUEST, e.to_string())),
+    };
+
+    let mut ledger = match ledger.lock() {
+        Ok(ledger) => ledger,
+        Err(_) => {
+            return Ok(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "poisoned ledger".into(),
+            ));
+        }
+    };
+    ledger.request_count += 1;
+
+    if let Some((status, retry_after)) = ledger.failures.pop_front() {
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = error_response(status, "injected failure".into());
+
+        if let Some(retry_after) = retry_after {
+            let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            response.headers_mut().insert(header::RETRY_AFTER, seconds.into());
+        }
+        return Ok(response);
+    }
+
+    let segments = path.split('/').collect::<Vec<_>>();
+
+    Ok(
+        route(&mut ledger, &method, &segments, query.as_deref(), &body, raw, raw_body)
+            .unwrap_or_else(|(status, message)| error_response(status, message)),
+    )
+}
+
+fn route(
+    ledger: &mut MockLedger,
+    method: &Method,
+    segments: &[&str],
+    query: Option<&str>,
+    body: &Bytes,
+    raw: bool,
+    raw_body: bool,
+) -> RouteResult {
+    match (method, segments) {
+        (&Method::GET, ["health"]) => Ok(Response::new(Body::empty())),
+        (&Method::GET, ["api", "routes"]) => json(&RoutesResponse {
+            routes: vec!["core/v2".to_string(), "indexer/v1".to_string()],
+        }),
+        (&Method::GET, ["api", "core", "v2", "info"]) => json(&info(ledger)),
+        (&Method::GET, ["api", "core", "v2", "tips"]) => json(&TipsResponse {
+            tips: ledger.tips.iter().map(BlockId::to_string).collect(),
+        }),
+        (&Method::POST, ["api", "core", "v2", "blocks"]) => post_block(ledger, body, raw_body),
+        (&Method::GET, ["api", "core", "v2", "blocks", block_id]) => block(ledger, Some(parse(block_id)?), raw),
+        (&Method::GET, ["api", "core", "v2", "blocks", block_id, "metadata"]) => {
+            block_metadata(ledger, Some(parse(block_id)?))
+        }
+        (&Method::GET, ["api", "core", "v2", "outputs", output_id]) => output(ledger, parse(output_id)?, raw),
+        (&Method::GET, ["api", "core", "v2", "outputs", output_id, "metadata"]) => {
+            json(&found(ledger.outputs.get(&parse(output_id)?))?.metadata)
+        }
+        (&Method::GET, ["api", "core", "v2", "transactions", transaction_id, "included-block"]) => {
+            let transaction_id: TransactionId = parse(transaction_id)?;
+            block(ledger, ledger.included_blocks.get(&transaction_id).copied(), raw)
+        }
+        (&Method::GET, ["api", "core", "v2", "transactions", transaction_id, "included-block", "metadata"]) => {
+            let transaction_id: TransactionId = parse(transaction_id)?;
+            block_metadata(ledger, ledger.included_blocks.get(&transaction_id).copied())
+        }
+        (&Method::GET, ["api", "core", "v2", "milestones", "by-index", index]) => milestone(ledger, parse(index)?, raw),
+        (&Method::GET, ["api", "core", "v2", "milestones", "by-index", index, "utxo-changes"]) => {
+            json(&found(ledger.unpruned_milestone(parse(index)?))?.utxo_changes)
+        }
+        (&Method::GET, ["api", "core", "v2", "milestones", milestone_id]) => {
+            let index = milestone_index(ledger, parse(milestone_id)?)?;
+            milestone(ledger, index, raw)
+        }
+        (&Method::GET, ["api", "core", "v2", "milestones", milestone_id, "utxo-changes"]) => {
+            let index = milestone_index(ledger, parse(milestone_id)?)?;
+            json(&found(ledger.unpruned_milestone(index))?.utxo_changes)
+        }
+        (&Method::GET, ["api", "indexer", "v1", "outputs", kind]) => output_ids(ledger, kind, query, |_, _| true),
+        (&Method::GET, ["api", "indexer", "v1", "outputs", "alias", alias_id]) => {
+            let alias_id: AliasId = parse(alias_id)?;
+            chain_output_id(
+                ledger,
+                "alias",
+                |output_id, output| matches!(output, Output::Alias(alias) if alias.alias_id_non_null(output_id) == alias_id),
+            )
+        }
+        (&Method::GET, ["api", "indexer", "v1", "outputs", "foundry", foundry_id]) => {
+            let foundry_id: FoundryId = parse(foundry_id)?;
+            chain_output_id(
+                ledger,
+                "foundry",
+                |_, output| matches!(output, Output::Foundry(foundry) if foundry.id() == foundry_id),
+            )
+        }
+        (&Method::GET, ["api", "indexer", "v1", "outputs", "nft", nft_id]) => {
+            let nft_id: NftId = parse(nft_id)?;
+            chain_output_id(
+                ledger,
+                "nft",
+                |output_id, output| matches!(output, Output::Nft(nft) if nft.nft_id_non_null(output_id) == nft_id),
+            )
+        }
+        _ => Err((
+            StatusCode::NOT_FOUND,
+            format!("no route for {method} /{}", segments.join("/")),
+        )),
+    }
+}
+
+fn info(ledger: &MockLedger) -> InfoResponse {
+    let protocol_parameters = &ledger.protocol_parameters;
+    let latest_milestone_id = ledger
+        .milestone(ledger.latest_milestone_index())
+        .map(|milestone| milestone.payload.id().to_string());
+
+    InfoResponse {
+        name: "mock".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        status: StatusResponse {
+            is_healthy: true,
+            latest_milestone: LatestMilestoneResponse {
+                index: ledger.latest_milestone_index(),
+                timestamp: Some(ledger.latest_milestone_timestamp()),
+                milestone_id: latest_milestone_id.clone(),
+            },
+            confirmed_milestone: ConfirmedMilestoneResponse {
+                index: ledger.latest_milestone_index(),
+                timestamp: Some(ledger.latest_milestone_timestamp()),
+                milestone_id: latest_milestone_id,
+            },
+            pruning_index: ledger.pruning_index,
+        },
+        supported_protocol_versions: vec![protocol_parameters.protocol_version()],
+        protocol: ProtocolParametersDto {
+            protocol_version: protocol_parameters.protocol_version(),
+            network_name: protocol_parameters.network_name().to_string(),
+            bech32_hrp: protocol_parameters.bech32_hrp().to_string(),
+            min_pow_score: protocol_parameters.min_pow_score(),
+            below_max_depth: protocol_parameters.below_max_depth(),
+            rent_structure: RentStructureDto {
+                v_byte_cost: protocol_parameters.rent_structure().byte_cost(),
+                v_byte_factor_key: protocol_parameters.rent_structure().byte_factor_key(),
+                v_byte_factor_data: protocol_parameters.rent_structure().byte_factor_data(),
+            },
+            token_supply: protocol_parameters.token_supply().to_string(),
+        },
+        pending_protocol_parameters: Vec::new(),
+        base_token: BaseTokenResponse {
+            name: "Shimmer".to_string(),
+            ticker_symbol: "SMR".to_string(),
+            unit: "SMR".to_string(),
+            subunit: Some("glow".to_string()),
+            decimals: 6,
+            use_metric_prefix: false,
+        },
+        metrics: MetricsResponse {
+            blocks_per_second: 0.0,
+            referenced_blocks_per_second: 0.0,
+            referenced_rate: 0.0,
+        },
+        features: Vec::new(),
+    }
+}
+
+fn post_block(ledger: &mut MockLedger, body: &Bytes, raw: bool) -> RouteResult {
+    let block = if raw {
+        Block::unpack_strict(body.as_ref(), &ledger.protocol_parameters).map_err(bad_request)?
+    } else {
+        let dto: BlockDto = serde_json::from_slice(body).map_err(bad_request)?;
+        Block::try_from_dto(&dto, &ledger.protocol_parameters).map_err(bad_request)?
+    };
+    if let Some((status, processed)) = ledger.block_post_failures.pop_front() {
+        if processed {
+            ledger.submit_block(block).map_err(bad_request)?;
+        }
+        return Err((
+            StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            "injected failure".into(),
+        ));
+    }
+
+    let block_id = ledger.submit_block(block).map_err(bad_request)?;
+
+    let mut response = json(&SubmitBlockResponse {
+        block_id: block_id.to_string(),
+    })?;
+    *response.status_mut() = StatusCode::CREATED;
+
+    Ok(response)
+}
+
+fn block(ledger: &MockLedger, block_id: Option<BlockId>, raw: bool) -> RouteResult {
+    let block = &found(block_id.and_then(|block_id| ledger.blocks.get(&block_id)))?.block;
+
+    if raw {
+        Ok(Response::new(Body::from(block.pack_to_vec())))
+    } else {
+        json(&BlockDto::from(block))
+    }
+}
+
+fn block_metadata(ledger: &MockLedger, block_id: Option<BlockId>) -> RouteResult {
+    json(&found(block_id.and_then(|block_id| ledger.blocks.get(&block_id)))?.metadata)
+}
+
+fn output(ledger: &MockLedger, output_id: OutputId, raw: bool) -> RouteResult {
+    let MockOutput { output, metadata } = found(ledger.outputs.get(&output_id))?;
+
+    if raw {
+        Ok(Response::new(Body::from(output.pack_to_vec())))
+    } else {
+        json(&OutputWithMetadataResponse {
+            metadata: metadata.clone(),
+            output: OutputDto::from(output),
+        })
+    }
+}
+
+fn milestone(ledger: &MockLedger, index: u32, raw: bool) -> RouteResult {
+    let payload = &found(ledger.unpruned_milestone(index))?.payload;
+
+    if raw {
+        Ok(Response::new(Body::from(payload.pack_to_vec())))
+    } else {
+        json(&MilestonePayloadDto::from(payload))
+    }
+}
+
+fn milestone_index(ledger: &MockLedger, milestone_id: MilestoneId) -> Result<u32, (StatusCode, String)> {
+    ledger
+        .milestones
+        .iter()
+        .position(|milestone| milestone.payload.id() == milestone_id)
+        .map(|position| position as u32 + 1)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("milestone {milestone_id} not found")))
+}
+
+// Like the indexer, lookups by chain ID fail if there is no unspent output of the chain.
+fn chain_output_id(ledger: &MockLedger, kind: &str, filter: impl Fn(&OutputId, &Output) -> bool) -> RouteResult {
+    found(
+        ledger
+            .outputs
+            .iter()
+            .find(|(output_id, MockOutput { output, metadata })| !metadata.is_spent && filter(output_id, output)),
+    )?;
+
+    output_ids(ledger, kind, None, filter)
+}
+
+fn output_ids(
+    ledger: &MockLedger,
+    kind: &str,
+    query: Option<&str>,
+    filter: impl Fn(&OutputId, &Output) -> bool,
+) -> RouteResult {
+    let kind = match kind {
+        "basic" => iota_types::block::output::BasicOutput::KIND,
+        "alias" => iota_types::block::output::AliasOutput::KIND,
+        "foundry" => iota_types::block::output::FoundryOutput::KIND,
+        "nft" => iota_types::block::output::NftOutput::KIND,
+        _ => return Err((StatusCode::NOT_FOUND, format!("unknown output type {kind}"))),
+    };
+    let parameters = url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect::<Vec<_>>();
+
+    let mut items = Vec::new();
+
+    for (output_id, MockOutput { output, metadata }) in &ledger.outputs {
+        if metadata.is_spent || output.kind() != kind || !filter(output_id, output) {
+            continue;
+        }
+        if parameters
+            .iter()
+            .all(|(key, value)| matches_query_parameter(output, metadata.milestone_timestamp_booked, key, value))
+        {
+            items.push((metadata.milestone_index_booked, output_id.to_string()));
+        }
+    }
+
+    // Sort by booking milestone and output id, like the indexer does.
+    items.sort();
+
+    json(&OutputIdsResponse {
+        ledger_index: ledger.latest_milestone_index(),
+        cursor: None,
+        items: items.into_iter().map(|(_, output_id)| output_id).collect(),
+    })
+}
+
+// Unsupported parameters, like pagination, are ignored and all results are returned in a single page.
+fn matches_query_parameter(output: &Output, booked_timestamp: u32, key: &str, value: &str) -> bool {
+    let address = || Address::try_from_bech32(value).map(|(_, address)| address).ok();
+    let flag = || value == "true";
+    let number = || value.parse::<u32>().unwrap_or_default();
+    let unlock_conditions = output.unlock_conditions();
+    let features = output.features();
+
+    match key {
+        "address" => unlock_conditions
+            .and_then(|unlock_conditions| unlock_conditions.address())
+            .map(|unlock_condition| Some(*unlock_condition.address()) == address())
+            .unwrap_or(false),
+        "aliasAddress" => unlock_conditions
+            .and_then(|unlock_conditions| unlock_conditions.immutable_alias_address())
+            .map(|unlock_condition| Some(*unlock_condition.address()) == address())
+            .unwrap_or(false),
+        "stateController" => unlock_conditions
+            .and_then(|unlock_conditions| unlock_conditions.state_controller_address())
+            .map(|unlock_condition| Some(*unlock_condition.address()) == address())
+            .unwrap_or(false),
+        "governor" => unlock_conditions
+            .and_then(|unlock_conditions| unlock_conditions.governor_address())
+            .map(|unlock_condition| Some(*unlock_condition.address()) == address())
+            .unwrap_or(false),
+        "hasStorageDepositReturn" => {
+            unlock_conditions.is_some_and(|unlock_conditions| unlock_conditions.storage_deposit_return().is_some())
+                == flag()
+        }
+        "storageDepositReturnAddress" => unlock_conditions
+            .and_then(|unlock_conditions| unlock_conditions.storage_deposit_return())
+            .map(|unlock_condition| Some(*unlock_condition.return_address()) == address())
+            .unwrap_or(false),
+        "hasExpiration" => {
+            unlock_conditions.is_some_and(|unlock_conditions| unlock_conditions.expiration().is_some()) == flag()
+        }
+        "expirationReturnAddress" => unlock_conditions
+            .and_then(|unlock_conditions| unlock_conditions.expiration())
+            .map(|unlock_condition| Some(*unlock_condition.return_address()) == address())
+            .unwrap_or(false),
+        "expiresBefore" => unlock_conditions
+            .and_then(|unlock_conditions| unlock_conditions.expiration())
+            .is_some_and(|unlock_condition| unlock_condition.timestamp() < number()),
+        "expiresAfter" => unlock_conditions
+            .and_then(|unlock_conditions| unlock_conditions.expiration())
+            .is_some_and(|unlock_condition| unlock_condition.timestamp() > number()),
+        "hasTimelock" => {
+            unlock_conditions.is_some_and(|unlock_conditions| unlock_conditions.timelock().is_some()) == flag()
+        }
+        "timelockedBefore" => unlock_conditions
+            .and_then(|unlock_conditions| unlock_conditions.timelock())
+            .is_some_and(|unlock_condition| unlock_condition.timestamp() < number()),
+        "timelockedAfter" => unlock_conditions
+            .and_then(|unlock_conditions| unlock_conditions.timelock())
+            .is_some_and(|unlock_condition| unlock_condition.timestamp() > number()),
+        "hasNativeTokens" => {
+            output
+                .native_tokens()
+                .is_some_and(|native_tokens| !native_tokens.is_empty())
+                == flag()
+        }
+        "minNativeTokenCount" => {
+            output.native_tokens().map_or(0, |native_tokens| native_tokens.len()) >= number() as usize
+        }
+        "maxNativeTokenCount" => {
+            output.native_tokens().map_or(0, |native_tokens| native_tokens.len()) <= number() as usize
+        }
+        "sender" => features
+            .and_then(|features| features.sender())
+            .map(|feature| Some(*feature.address()) == address())
+            .unwrap_or(false),
+        "issuer" => output
+            .immutable_features()
+            .and_then(|features| features.issuer())
+            .map(|feature| Some(*feature.address()) == address())
+            .unwrap_or(false),
+        "tag" => features
+            .and_then(|features| features.tag())
+            .map(|feature| prefix_hex::encode(feature.tag()) == value)
+            .unwrap_or(false),
+        "createdBefore" => booked_timestamp < number(),
+        "createdAfter" => booked_timestamp > number(),
+        _ => true,
+    }
+}
+
+fn json<T: Serialize>(value: &T) -> RouteResult {
+    let body = serde_json::to_vec(value).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+fn found<T>(value: Option<T>) -> Result<T, (StatusCode, String)> {
+    value.ok_or_else(|| (StatusCode::NOT_FOUND, "not found".to_string()))
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<T, (StatusCode, String)> {
+    value
+        .parse()
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("invalid parameter {value}")))
+}
+
+fn bad_request(error: impl std::fmt::Display) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, error.to_string())
+}
+
+// Errors are returned in the same format as HORNET does.
+fn error_response(status: StatusCode, message: String) -> Response<Body> {
+    let body = serde_json::json!({
+        "error": {
+            "code": status.as_u16().to_string(),
+            "message": message,
+        }
+    });
+    let mut response = Response::new(Body::from(body.to_string()));
+    *response.status_mut() = status;
+
+    response
+}
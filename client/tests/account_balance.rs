@@ -0,0 +1,205 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use iota_client::{
+    block::{
+        address::{Address, AliasAddress, Ed25519Address},
+        output::{
+            unlock_condition::{
+                AddressUnlockCondition, ExpirationUnlockCondition, GovernorAddressUnlockCondition,
+                ImmutableAliasAddressUnlockCondition, StateControllerAddressUnlockCondition,
+                StorageDepositReturnUnlockCondition, TimelockUnlockCondition,
+            },
+            AliasId, AliasOutputBuilder, BasicOutputBuilder, FoundryOutputBuilder, NativeToken, NftId,
+            NftOutputBuilder, Rent, SimpleTokenScheme, TokenId, TokenScheme, UnlockCondition,
+        },
+    },
+    constants::SHIMMER_COIN_TYPE,
+    secret::{mnemonic::MnemonicSecretManager, SecretManager},
+    testing::MockNode,
+    Client, Result,
+};
+use primitive_types::U256;
+
+use self::common::build_basic_output;
+
+// THIS SEED SERVES FOR TESTING PURPOSES! DON'T USE THIS SEED IN PRODUCTION!
+const DEFAULT_DEVELOPMENT_SEED: &str = "0x256a818b2aac458941f7274985a410e57fb750f3a3a67969ece5bd9ae7eef5b2";
+
+#[tokio::test]
+async fn account_balance() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let addresses = client
+        .get_addresses(&secret_manager)
+        .with_range(0..6)
+        .get_all_raw()
+        .await?;
+    let protocol_parameters = client.get_protocol_parameters().await?;
+    let rent_structure = protocol_parameters.rent_structure();
+    let token_supply = protocol_parameters.token_supply();
+    let now = client.get_time_checked().await?;
+    let other_address = Address::Ed25519(Ed25519Address::new([1; 32]));
+
+    // Index 0.
+    mock.add_output(build_basic_output(
+        1_000_000_000,
+        addresses.public[0],
+        None,
+        None,
+        token_supply,
+    )?)?;
+    mock.add_output(build_basic_output(
+        2_000_000,
+        addresses.internal[0],
+        None,
+        Some(UnlockCondition::Timelock(TimelockUnlockCondition::new(now + 3600)?)),
+        token_supply,
+    )?)?;
+    // Expired, so it belongs to its return address.
+    mock.add_output(build_basic_output(
+        3_000_000,
+        other_address,
+        None,
+        Some(UnlockCondition::Expiration(ExpirationUnlockCondition::new(
+            addresses.public[0],
+            now - 3600,
+        )?)),
+        token_supply,
+    )?)?;
+    // Not expired yet, so it doesn't belong to its return address.
+    mock.add_output(build_basic_output(
+        4_000_000,
+        other_address,
+        None,
+        Some(UnlockCondition::Expiration(ExpirationUnlockCondition::new(
+            addresses.public[0],
+            now + 3600,
+        )?)),
+        token_supply,
+    )?)?;
+    let token_id = TokenId::new([2; 38]);
+    let native_token_output = BasicOutputBuilder::new_with_amount(5_000_000)?
+        .add_native_token(NativeToken::new(token_id, U256::from(100))?)
+        .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(
+            addresses.internal[0],
+        )))
+        .finish_output(token_supply)?;
+    mock.add_output(native_token_output.clone())?;
+    let alias_id = AliasId::new([3; 32]);
+    let alias = AliasOutputBuilder::new_with_amount(6_000_000, alias_id)?
+        .with_foundry_counter(1)
+        .add_unlock_condition(UnlockCondition::StateControllerAddress(
+            StateControllerAddressUnlockCondition::new(addresses.public[0]),
+        ))
+        .add_unlock_condition(UnlockCondition::GovernorAddress(GovernorAddressUnlockCondition::new(
+            other_address,
+        )))
+        .finish_output(token_supply)?;
+    mock.add_output(alias.clone())?;
+    let foundry = FoundryOutputBuilder::new_with_amount(
+        7_000_000,
+        1,
+        TokenScheme::Simple(SimpleTokenScheme::new(
+            U256::from(1_000),
+            U256::from(0),
+            U256::from(1_000),
+        )?),
+    )?
+    .add_unlock_condition(UnlockCondition::ImmutableAliasAddress(
+        ImmutableAliasAddressUnlockCondition::new(AliasAddress::new(alias_id)),
+    ))
+    .finish_output(token_supply)?;
+    mock.add_output(foundry.clone())?;
+    // Only governed, so its amount isn't counted.
+    let governed_alias_id = AliasId::from(
+        &mock.add_output(
+            AliasOutputBuilder::new_with_amount(8_000_000, AliasId::null())?
+                .add_unlock_condition(UnlockCondition::StateControllerAddress(
+                    StateControllerAddressUnlockCondition::new(other_address),
+                ))
+                .add_unlock_condition(UnlockCondition::GovernorAddress(GovernorAddressUnlockCondition::new(
+                    addresses.internal[0],
+                )))
+                .finish_output(token_supply)?,
+        )?,
+    );
+
+    // Index 1 is unused, index 2 is still within the gap limit.
+    mock.add_output(build_basic_output(
+        9_000_000,
+        addresses.public[2],
+        None,
+        Some(UnlockCondition::Expiration(ExpirationUnlockCondition::new(
+            other_address,
+            now + 3600,
+        )?)),
+        token_supply,
+    )?)?;
+    mock.add_output(build_basic_output(
+        10_000_000,
+        addresses.internal[2],
+        None,
+        Some(UnlockCondition::StorageDepositReturn(
+            StorageDepositReturnUnlockCondition::new(other_address, 1_000_000, token_supply)?,
+        )),
+        token_supply,
+    )?)?;
+    let nft = NftOutputBuilder::new_with_amount(11_000_000, NftId::null())?
+        .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(
+            addresses.public[2],
+        )))
+        .finish_output(token_supply)?;
+    let nft_id = NftId::from(&mock.add_output(nft.clone())?);
+
+    // Indexes 3 and 4 are unused, index 5 is beyond the gap limit.
+    mock.add_output(build_basic_output(
+        12_000_000,
+        addresses.public[5],
+        None,
+        None,
+        token_supply,
+    )?)?;
+
+    let balance = client.account_balance(&secret_manager, SHIMMER_COIN_TYPE, 0, 2).await?;
+
+    assert_eq!(
+        balance.addresses,
+        vec![
+            addresses.public[0],
+            addresses.internal[0],
+            addresses.public[2],
+            addresses.internal[2]
+        ]
+    );
+
+    let required_storage_deposit = native_token_output.rent_cost(rent_structure)
+        + alias.rent_cost(rent_structure)
+        + foundry.rent_cost(rent_structure)
+        + 1_000_000
+        + nft.rent_cost(rent_structure);
+    assert_eq!(balance.base_coin.total, 1_053_000_000);
+    assert_eq!(balance.base_coin.timelocked, 2_000_000);
+    assert_eq!(balance.base_coin.pending_expiration, 9_000_000);
+    assert_eq!(balance.base_coin.required_storage_deposit, required_storage_deposit);
+    assert_eq!(balance.base_coin.available, 1_042_000_000 - required_storage_deposit);
+
+    let mut alias_ids = vec![alias_id, governed_alias_id];
+    alias_ids.sort();
+    assert_eq!(balance.alias_ids, alias_ids);
+    assert_eq!(balance.nft_ids, vec![nft_id]);
+    assert_eq!(balance.foundry_ids, vec![foundry.as_foundry().id()]);
+    assert_eq!(balance.native_tokens.len(), 1);
+    assert_eq!(balance.native_tokens[0].token_id, token_id);
+    assert_eq!(balance.native_tokens[0].total(), U256::from(100));
+
+    // A gap limit of one stops at the unused index 1.
+    let balance = client.account_balance(&secret_manager, SHIMMER_COIN_TYPE, 0, 1).await?;
+    assert_eq!(balance.addresses, vec![addresses.public[0], addresses.internal[0]]);
+    assert_eq!(balance.base_coin.total, 1_023_000_000);
+
+    Ok(())
+}
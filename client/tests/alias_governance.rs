@@ -0,0 +1,111 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use iota_client::{
+    api::verify_state_transitions,
+    block::output::{
+        unlock_condition::{
+            AddressUnlockCondition, GovernorAddressUnlockCondition, StateControllerAddressUnlockCondition,
+        },
+        AliasId, AliasOutputBuilder, BasicOutputBuilder, FoundryId, StateTransitionError, UnlockCondition,
+    },
+    secret::{mnemonic::MnemonicSecretManager, SecretManager},
+    testing::MockNode,
+    Client, Error, Result,
+};
+use primitive_types::U256;
+
+use self::common::alias_output;
+
+// THIS SEED SERVES FOR TESTING PURPOSES! DON'T USE THIS SEED IN PRODUCTION!
+const DEFAULT_DEVELOPMENT_SEED: &str = "0x256a818b2aac458941f7274985a410e57fb750f3a3a67969ece5bd9ae7eef5b2";
+
+#[tokio::test]
+async fn alias_governance() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let addresses = client.get_addresses(&secret_manager).with_range(0..2).get_raw().await?;
+    let token_supply = client.get_token_supply().await?;
+
+    mock.add_output(
+        BasicOutputBuilder::new_with_amount(1_000_000_000)?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(addresses[0])))
+            .finish_output(token_supply)?,
+    )?;
+    let alias_id = AliasId::from(
+        &mock.add_output(
+            AliasOutputBuilder::new_with_amount(1_000_000, AliasId::null())?
+                .add_unlock_condition(UnlockCondition::StateControllerAddress(
+                    StateControllerAddressUnlockCondition::new(addresses[0]),
+                ))
+                .add_unlock_condition(UnlockCondition::GovernorAddress(GovernorAddressUnlockCondition::new(
+                    addresses[1],
+                )))
+                .finish_output(token_supply)?,
+        )?,
+    );
+
+    client
+        .set_alias_state_metadata(&secret_manager, alias_id, b"first state".to_vec())
+        .await?;
+    let alias = alias_output(&client, alias_id).await?;
+    assert_eq!(alias.state_metadata(), b"first state");
+    assert_eq!(alias.state_index(), 1);
+
+    // Governance transitions, unlocked by a different address than the state controller.
+    client
+        .set_alias_state_controller(&secret_manager, alias_id, addresses[1])
+        .await?;
+    client
+        .set_alias_governor(&secret_manager, alias_id, addresses[0])
+        .await?;
+    let alias = alias_output(&client, alias_id).await?;
+    assert_eq!(alias.state_controller_address(), &addresses[1]);
+    assert_eq!(alias.governor_address(), &addresses[0]);
+    assert_eq!(alias.state_index(), 1);
+
+    client
+        .set_alias_state_metadata(&secret_manager, alias_id, b"second state".to_vec())
+        .await?;
+    assert_eq!(alias_output(&client, alias_id).await?.state_metadata(), b"second state");
+
+    // A governance transition can't change the state metadata.
+    let invalid = AliasOutputBuilder::from(&alias_output(&client, alias_id).await?)
+        .with_state_metadata(b"third state".to_vec())
+        .finish_output(token_supply)?;
+    let prepared_transaction_data = client
+        .block()
+        .with_secret_manager(&secret_manager)
+        .with_outputs(vec![invalid])?
+        .prepare_transaction()
+        .await?;
+    assert!(matches!(
+        verify_state_transitions(&prepared_transaction_data, client.get_time_checked().await?),
+        Err(Error::InvalidStateTransition {
+            reason: StateTransitionError::MutatedFieldWithoutRights,
+            ..
+        })
+    ));
+
+    let token_id = client
+        .create_native_token(&secret_manager, alias_id, U256::from(100), U256::from(100), None)
+        .await?
+        .token_id;
+
+    let blocks = client.destroy_alias(&secret_manager, alias_id).await?;
+    assert_eq!(blocks.len(), 2);
+    assert!(matches!(
+        client.alias_output_id(alias_id).await,
+        Err(Error::NotFound(_))
+    ));
+    assert!(matches!(
+        client.foundry_output_id(FoundryId::new(*token_id)).await,
+        Err(Error::NotFound(_))
+    ));
+    assert!(client.native_token_balances(&addresses).await?.is_empty());
+
+    Ok(())
+}
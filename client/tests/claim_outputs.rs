@@ -0,0 +1,205 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use iota_client::{
+    api::GetAddressesBuilderOptions,
+    block::{
+        address::{Address, Ed25519Address},
+        output::{
+            feature::{Feature, SenderFeature},
+            unlock_condition::{
+                AddressUnlockCondition, ExpirationUnlockCondition, StorageDepositReturnUnlockCondition,
+                TimelockUnlockCondition,
+            },
+            NftId, NftOutputBuilder, Output, OutputId, UnlockCondition,
+        },
+    },
+    node_api::indexer::query_parameters::QueryParameter,
+    secret::{mnemonic::MnemonicSecretManager, SecretManager},
+    testing::MockNode,
+    Client, Error, Result,
+};
+
+use self::common::build_basic_output;
+
+// THIS SEED SERVES FOR TESTING PURPOSES! DON'T USE THIS SEED IN PRODUCTION!
+const DEFAULT_DEVELOPMENT_SEED: &str = "0x256a818b2aac458941f7274985a410e57fb750f3a3a67969ece5bd9ae7eef5b2";
+
+fn sorted(mut output_ids: Vec<OutputId>) -> Vec<OutputId> {
+    output_ids.sort();
+    output_ids
+}
+
+#[tokio::test]
+async fn claim_outputs() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let bech32_hrp = client.get_bech32_hrp().await?;
+    let token_supply = client.get_token_supply().await?;
+    let address = client.get_addresses(&secret_manager).with_range(0..1).get_raw().await?[0];
+    let sender = Address::Ed25519(Ed25519Address::new([1; 32]));
+    let now = client.get_time_checked().await?;
+
+    let storage_deposit_return = mock.add_output(build_basic_output(
+        2_000_000,
+        address,
+        None,
+        Some(UnlockCondition::StorageDepositReturn(
+            StorageDepositReturnUnlockCondition::new(sender, 1_000_000, token_supply)?,
+        )),
+        token_supply,
+    )?)?;
+    let not_expired = mock.add_output(build_basic_output(
+        1_000_000,
+        address,
+        None,
+        Some(UnlockCondition::Expiration(ExpirationUnlockCondition::new(
+            sender,
+            now + 3600,
+        )?)),
+        token_supply,
+    )?)?;
+    let expired = mock.add_output(build_basic_output(
+        1_000_000,
+        address,
+        None,
+        Some(UnlockCondition::Expiration(ExpirationUnlockCondition::new(
+            sender,
+            now - 3600,
+        )?)),
+        token_supply,
+    )?)?;
+    let timelocked = mock.add_output(build_basic_output(
+        1_000_000,
+        address,
+        None,
+        Some(UnlockCondition::Timelock(TimelockUnlockCondition::new(now + 3600)?)),
+        token_supply,
+    )?)?;
+    let sent = mock.add_output(build_basic_output(
+        1_000_000,
+        sender,
+        None,
+        Some(UnlockCondition::Expiration(ExpirationUnlockCondition::new(
+            address,
+            now + 3600,
+        )?)),
+        token_supply,
+    )?)?;
+    let nft = mock.add_output(
+        NftOutputBuilder::new_with_amount(1_000_000, NftId::null())?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)))
+            .add_unlock_condition(UnlockCondition::StorageDepositReturn(
+                StorageDepositReturnUnlockCondition::new(sender, 500_000, token_supply)?,
+            ))
+            .add_feature(Feature::Sender(SenderFeature::new(sender)))
+            .finish_output(token_supply)?,
+    )?;
+    // Outputs without additional unlock conditions are not concerned.
+    mock.add_output(build_basic_output(1_000_000, address, None, None, token_supply)?)?;
+
+    let claimable = client.claimable_outputs(&[address]).await?;
+
+    assert_eq!(
+        sorted(claimable.now.iter().map(|output| output.output_id).collect()),
+        sorted(vec![storage_deposit_return, not_expired, nft])
+    );
+    assert_eq!(
+        sorted(claimable.later.iter().map(|output| output.output_id).collect()),
+        sorted(vec![timelocked, sent])
+    );
+    assert!(claimable
+        .later
+        .iter()
+        .all(|output| output.claimable_from == Some(now + 3600)));
+    assert_eq!(claimable.returning_to_sender.len(), 1);
+    assert_eq!(claimable.returning_to_sender[0].output_id, expired);
+    assert_eq!(claimable.returning_to_sender[0].address, address);
+
+    let blocks = client
+        .claim_outputs(
+            &secret_manager,
+            claimable.now.iter().map(|output| output.output_id).collect(),
+            GetAddressesBuilderOptions {
+                range: Some(0..1),
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert_eq!(blocks.len(), 1);
+
+    let claimable = client.claimable_outputs(&[address]).await?;
+    assert!(claimable.now.is_empty());
+    assert_eq!(claimable.later.len(), 2);
+
+    // The storage deposits are returned to the sender.
+    let returned = client
+        .get_outputs(
+            client
+                .basic_output_ids(vec![QueryParameter::Address(sender.to_bech32(&bech32_hrp))])
+                .await?,
+        )
+        .await?
+        .iter()
+        .map(|output| Ok(Output::try_from_dto(&output.output, token_supply)?.amount()))
+        .collect::<Result<Vec<_>>>()?;
+    assert!(returned.contains(&1_500_000));
+
+    // The NFT was transferred without its sender feature and the rest swept to the address.
+    let nft_output_ids = client
+        .nft_output_ids(vec![QueryParameter::Address(address.to_bech32(&bech32_hrp))])
+        .await?;
+    assert_eq!(nft_output_ids.len(), 1);
+    let Output::Nft(nft_output) =
+        Output::try_from_dto(&client.get_output(&nft_output_ids[0]).await?.output, token_supply)?
+    else {
+        panic!("expected an NFT output");
+    };
+    assert_eq!(nft_output.nft_id(), &NftId::from(&nft));
+    assert!(nft_output.features().is_empty());
+    assert_eq!(nft_output.unlock_conditions().len(), 1);
+
+    let swept = client
+        .basic_output_ids(vec![
+            QueryParameter::Address(address.to_bech32(&bech32_hrp)),
+            QueryParameter::HasExpiration(false),
+            QueryParameter::HasTimelock(false),
+            QueryParameter::HasStorageDepositReturn(false),
+        ])
+        .await?;
+    let swept_amount = client
+        .get_outputs(swept)
+        .await?
+        .iter()
+        .map(|output| Ok(Output::try_from_dto(&output.output, token_supply)?.amount()))
+        .sum::<Result<u64>>()?;
+    assert_eq!(swept_amount + nft_output.amount(), 1_000_000 + 2_500_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn claim_outputs_without_addresses() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+
+    assert!(matches!(
+        client
+            .claim_outputs(
+                &secret_manager,
+                Vec::new(),
+                GetAddressesBuilderOptions {
+                    range: Some(0..0),
+                    ..Default::default()
+                },
+            )
+            .await,
+        Err(Error::MissingParameter("address"))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+// Every test binary only uses some of these helpers.
+#![allow(dead_code)]
+
+use iota_client::{
+    block::{
+        address::Address,
+        output::{
+            unlock_condition::AddressUnlockCondition, AliasId, AliasOutput, BasicOutputBuilder, FoundryId,
+            FoundryOutput, NativeToken, NftId, NftOutput, Output, OutputId, TokenId, UnlockCondition,
+        },
+    },
+    Client, Result,
+};
+
+pub fn build_basic_output(
+    amount: u64,
+    address: Address,
+    native_tokens: Option<Vec<NativeToken>>,
+    unlock_condition: Option<UnlockCondition>,
+    token_supply: u64,
+) -> Result<Output> {
+    let mut builder = BasicOutputBuilder::new_with_amount(amount)?
+        .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)));
+
+    if let Some(native_tokens) = native_tokens {
+        builder = builder.with_native_tokens(native_tokens);
+    }
+
+    if let Some(unlock_condition) = unlock_condition {
+        builder = builder.add_unlock_condition(unlock_condition);
+    }
+
+    Ok(builder.finish_output(token_supply)?)
+}
+
+pub async fn alias_output(client: &Client, alias_id: AliasId) -> Result<AliasOutput> {
+    match chain_output(client, client.alias_output_id(alias_id).await?).await? {
+        Output::Alias(alias) => Ok(alias),
+        _ => panic!("expected an alias output"),
+    }
+}
+
+pub async fn foundry_output(client: &Client, token_id: TokenId) -> Result<FoundryOutput> {
+    match chain_output(client, client.foundry_output_id(FoundryId::new(*token_id)).await?).await? {
+        Output::Foundry(foundry) => Ok(foundry),
+        _ => panic!("expected a foundry output"),
+    }
+}
+
+pub async fn nft_output(client: &Client, nft_id: NftId) -> Result<NftOutput> {
+    match chain_output(client, client.nft_output_id(nft_id).await?).await? {
+        Output::Nft(nft) => Ok(nft),
+        _ => panic!("expected an NFT output"),
+    }
+}
+
+async fn chain_output(client: &Client, output_id: OutputId) -> Result<Output> {
+    let token_supply = client.get_token_supply().await?;
+
+    Ok(Output::try_from_dto(
+        &client.get_output(&output_id).await?.output,
+        token_supply,
+    )?)
+}
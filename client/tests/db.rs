@@ -0,0 +1,227 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs::{self, OpenOptions};
+
+use async_trait::async_trait;
+use iota_client::{
+    db::{DatabaseProvider, EncryptedDatabaseProvider, FileDatabaseProvider, MemoryDatabaseProvider, WriteBatch},
+    Error, Result,
+};
+
+async fn check_database_provider(db: &mut (impl DatabaseProvider + Send)) -> Result<()> {
+    assert_eq!(db.get(b"a-0").await?, None);
+    assert_eq!(db.insert(b"a-0", b"0").await?, None);
+    assert_eq!(db.insert(b"a-0", b"1").await?, Some(b"0".to_vec()));
+    assert_eq!(db.get(b"a-0").await?, Some(b"1".to_vec()));
+    assert_eq!(db.delete(b"a-0").await?, Some(b"1".to_vec()));
+    assert_eq!(db.delete(b"a-0").await?, None);
+
+    let mut batch = WriteBatch::new();
+    batch
+        .insert(b"a-1", b"1")
+        .insert(b"b-0", b"0")
+        .insert(b"a-0", b"0")
+        .insert(b"a-2", b"2")
+        .delete(b"a-2");
+    db.write_batch(batch).await?;
+
+    assert_eq!(
+        db.scan_prefix(b"a-").await?,
+        vec![(b"a-0".to_vec(), b"0".to_vec()), (b"a-1".to_vec(), b"1".to_vec())]
+    );
+    assert_eq!(db.scan_prefix(b"b-").await?.len(), 1);
+    assert_eq!(db.scan_prefix(b"").await?.len(), 3);
+    assert!(db.scan_prefix(b"c-").await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn memory_database_provider() -> Result<()> {
+    check_database_provider(&mut MemoryDatabaseProvider::new()).await
+}
+
+#[tokio::test]
+async fn file_database_provider() -> Result<()> {
+    let path = "test_file_database_provider.db";
+    let _ = fs::remove_file(path);
+
+    check_database_provider(&mut FileDatabaseProvider::open(path)?).await?;
+
+    // The records are still there after reopening the file.
+    let mut db = FileDatabaseProvider::open(path)?;
+    assert_eq!(db.scan_prefix(b"").await?.len(), 3);
+    assert_eq!(db.get(b"a-1").await?, Some(b"1".to_vec()));
+    assert_eq!(db.get(b"a-2").await?, None);
+
+    fs::remove_file(path)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn file_database_provider_discards_partial_write() -> Result<()> {
+    let path = "test_file_database_provider_discards_partial_write.db";
+    let _ = fs::remove_file(path);
+
+    let mut db = FileDatabaseProvider::open(path)?;
+    db.insert(b"key", b"value").await?;
+    drop(db);
+
+    // Simulate a crash in the middle of a write.
+    let length = fs::metadata(path)?.len();
+    std::io::Write::write_all(
+        &mut OpenOptions::new().append(true).open(path)?,
+        &[42, 0, 0, 0, 1, 2, 3],
+    )?;
+
+    let mut db = FileDatabaseProvider::open(path)?;
+    assert_eq!(fs::metadata(path)?.len(), length);
+    assert_eq!(db.get(b"key").await?, Some(b"value".to_vec()));
+    db.insert(b"other", b"value").await?;
+    drop(db);
+
+    let mut db = FileDatabaseProvider::open(path)?;
+    assert_eq!(db.scan_prefix(b"").await?.len(), 2);
+
+    fs::remove_file(path)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn file_database_provider_rejects_corrupted_frame_length() -> Result<()> {
+    let path = "test_file_database_provider_rejects_corrupted_frame_length.db";
+    let _ = fs::remove_file(path);
+
+    let mut db = FileDatabaseProvider::open(path)?;
+    db.insert(b"first", b"value").await?;
+    let offset = fs::metadata(path)?.len() as usize;
+    db.insert(b"second", b"value").await?;
+    db.insert(b"third", b"value").await?;
+    drop(db);
+
+    // The length of the middle frame now exceeds the file, which isn't a partial write of the last frame.
+    let mut bytes = fs::read(path)?;
+    bytes[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    fs::write(path, &bytes)?;
+
+    assert!(matches!(
+        FileDatabaseProvider::open(path),
+        Err(Error::CorruptedDatabase(_))
+    ));
+    // The following records are kept.
+    assert_eq!(fs::metadata(path)?.len(), bytes.len() as u64);
+
+    fs::remove_file(path)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn file_database_provider_compaction() -> Result<()> {
+    let path = "test_file_database_provider_compaction.db";
+    let _ = fs::remove_file(path);
+
+    let mut db = FileDatabaseProvider::open(path)?;
+    db.insert(b"key", b"first").await?;
+    let length = fs::metadata(path)?.len();
+
+    for i in 0..5_000u32 {
+        db.insert(b"key", &i.to_le_bytes()).await?;
+    }
+    // Overwritten records are removed automatically.
+    assert!(fs::metadata(path)?.len() < 1_000 * length);
+
+    db.compact()?;
+    assert_eq!(fs::metadata(path)?.len(), length - 1);
+    drop(db);
+
+    let mut db = FileDatabaseProvider::open(path)?;
+    assert_eq!(db.get(b"key").await?, Some(4_999u32.to_le_bytes().to_vec()));
+
+    fs::remove_file(path)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn encrypted_database_provider() -> Result<()> {
+    let mut db = EncryptedDatabaseProvider::new(MemoryDatabaseProvider::new(), [1; 32]);
+
+    check_database_provider(&mut db).await?;
+
+    let mut inner = db.into_inner();
+    let encrypted = inner.get(b"a-0").await?.unwrap();
+    assert_ne!(encrypted, b"0");
+
+    // A value can't be read with another key, nor moved under another record key.
+    inner.insert(b"a-1", &encrypted).await?;
+    let mut db = EncryptedDatabaseProvider::new(inner, [2; 32]);
+    assert!(db.get(b"a-0").await.is_err());
+    let mut db = EncryptedDatabaseProvider::new(db.into_inner(), [1; 32]);
+    assert_eq!(db.get(b"a-0").await?, Some(b"0".to_vec()));
+    assert!(db.get(b"a-1").await.is_err());
+
+    Ok(())
+}
+
+// A provider implementing only the required methods, failing to write a key.
+struct FailingDatabaseProvider {
+    inner: MemoryDatabaseProvider,
+    failing_key: &'static [u8],
+}
+
+#[async_trait]
+impl DatabaseProvider for FailingDatabaseProvider {
+    async fn get(&mut self, k: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.inner.get(k).await
+    }
+
+    async fn insert(&mut self, k: &[u8], v: &[u8]) -> Result<Option<Vec<u8>>> {
+        if k == self.failing_key {
+            return Err(Error::CorruptedDatabase("failing key".to_string()));
+        }
+        self.inner.insert(k, v).await
+    }
+
+    async fn delete(&mut self, k: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.inner.delete(k).await
+    }
+}
+
+#[tokio::test]
+async fn default_write_batch_rolls_back() -> Result<()> {
+    let mut db = FailingDatabaseProvider {
+        inner: MemoryDatabaseProvider::new(),
+        failing_key: b"fail",
+    };
+    db.insert(b"a", b"0").await?;
+    db.insert(b"b", b"0").await?;
+
+    let mut batch = WriteBatch::new();
+    batch
+        .insert(b"a", b"1")
+        .delete(b"b")
+        .insert(b"c", b"1")
+        .insert(b"fail", b"1");
+    assert!(db.write_batch(batch).await.is_err());
+
+    assert_eq!(db.get(b"a").await?, Some(b"0".to_vec()));
+    assert_eq!(db.get(b"b").await?, Some(b"0".to_vec()));
+    assert_eq!(db.get(b"c").await?, None);
+
+    let mut batch = WriteBatch::new();
+    batch.insert(b"a", b"1").delete(b"b");
+    db.write_batch(batch).await?;
+    assert_eq!(db.get(b"a").await?, Some(b"1".to_vec()));
+    assert_eq!(db.get(b"b").await?, None);
+
+    assert!(matches!(
+        db.scan_prefix(b"").await,
+        Err(Error::UnsupportedOperation("scan_prefix"))
+    ));
+
+    Ok(())
+}
@@ -843,3 +843,25 @@ fn transitioned_zero_alias_id_no_longer_is_zero() {
         }
     });
 }
+
+#[test]
+fn alias_owned_input_sorted_after_alias() {
+    let protocol_parameters = protocol_parameters();
+    let alias_id_1 = AliasId::from_str(ALIAS_ID_1).unwrap();
+
+    // The input owned by the alias comes before the alias.
+    let inputs = build_inputs(vec![
+        Basic(2_000_000, BECH32_ADDRESS_ALIAS_1, None, None, None),
+        Alias(1_000_000, alias_id_1, BECH32_ADDRESS_ED25519_0, None, None, None),
+    ]);
+    let outputs = build_outputs(vec![Basic(2_000_000, BECH32_ADDRESS_ED25519_0, None, None, None)]);
+
+    let selected = InputSelection::new(inputs, outputs, protocol_parameters)
+        .select()
+        .unwrap();
+
+    // The alias has to be unlocked before the input it owns can reference it.
+    assert_eq!(selected.inputs.len(), 2);
+    assert!(selected.inputs[0].output.is_alias());
+    assert!(selected.inputs[1].output.is_basic());
+}
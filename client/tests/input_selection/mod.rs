@@ -11,6 +11,8 @@ mod native_tokens;
 mod nft_outputs;
 mod outputs;
 mod storage_deposit_return;
+mod strategy;
+mod transaction_chain;
 
 use std::{collections::HashMap, hash::Hash, str::FromStr};
 
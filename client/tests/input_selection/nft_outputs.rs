@@ -770,3 +770,22 @@ fn transitioned_zero_nft_id_no_longer_is_zero() {
         }
     });
 }
+
+#[test]
+fn nft_input_before_inputs_it_unlocks() {
+    let protocol_parameters = protocol_parameters();
+    let nft_id_1 = NftId::from_str(NFT_ID_1).unwrap();
+
+    let inputs = build_inputs(vec![
+        Basic(1_000_000, BECH32_ADDRESS_NFT_1, None, None, None),
+        Nft(1_000_000, nft_id_1, BECH32_ADDRESS_ED25519_0, None, None, None, None),
+    ]);
+    let outputs = build_outputs(vec![Basic(1_000_000, BECH32_ADDRESS_ED25519_0, None, None, None)]);
+
+    let selected = InputSelection::new(inputs.clone(), outputs, protocol_parameters)
+        .select()
+        .unwrap();
+
+    // The basic input is unlocked by a reference to the NFT input, which has to come first.
+    assert_eq!(selected.inputs, vec![inputs[1].clone(), inputs[0].clone()]);
+}
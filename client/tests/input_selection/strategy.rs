@@ -0,0 +1,156 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use iota_client::{
+    api::input_selection::{BuiltinStrategy, InputSelection, InputSelectionStrategy, SelectionCandidate},
+    block::protocol::protocol_parameters,
+    secret::types::OutputMetadata,
+};
+use primitive_types::U256;
+
+use crate::input_selection::{
+    build_inputs, build_outputs, unsorted_eq, Build::Basic, BECH32_ADDRESS_ED25519_0, BECH32_ADDRESS_ED25519_1,
+};
+
+fn select(amounts: &[u64], output_amount: u64, strategy: Arc<dyn InputSelectionStrategy>) -> (Vec<u64>, Option<u64>) {
+    let inputs = build_inputs(
+        amounts
+            .iter()
+            .map(|amount| Basic(*amount, BECH32_ADDRESS_ED25519_0, None, None, None))
+            .collect(),
+    );
+    let outputs = build_outputs(vec![Basic(output_amount, BECH32_ADDRESS_ED25519_1, None, None, None)]);
+
+    let selected = InputSelection::new(inputs, outputs, protocol_parameters())
+        .strategy(strategy)
+        .select()
+        .unwrap();
+
+    (
+        selected.inputs.iter().map(|input| input.output.amount()).collect(),
+        selected.remainder.map(|remainder| remainder.output.amount()),
+    )
+}
+
+#[test]
+fn smallest_first() {
+    let (inputs, remainder) = select(
+        &[5_000_000, 1_000_000, 2_000_000],
+        3_000_000,
+        Arc::new(BuiltinStrategy::SmallestFirst),
+    );
+
+    assert!(unsorted_eq(&inputs, &[1_000_000, 2_000_000]));
+    assert_eq!(remainder, None);
+}
+
+#[test]
+fn largest_first() {
+    let (inputs, remainder) = select(
+        &[1_000_000, 5_000_000, 2_000_000],
+        3_000_000,
+        Arc::new(BuiltinStrategy::LargestFirst),
+    );
+
+    assert_eq!(inputs, vec![5_000_000]);
+    assert_eq!(remainder, Some(2_000_000));
+}
+
+#[test]
+fn minimize_remainder() {
+    let (inputs, remainder) = select(
+        &[1_000_000, 10_000_000, 4_000_000],
+        3_500_000,
+        Arc::new(BuiltinStrategy::MinimizeRemainder),
+    );
+
+    assert_eq!(inputs, vec![4_000_000]);
+    assert_eq!(remainder, Some(500_000));
+
+    // No input covers the amount on its own.
+    let (inputs, remainder) = select(
+        &[1_000_000, 2_000_000, 4_000_000],
+        5_500_000,
+        Arc::new(BuiltinStrategy::MinimizeRemainder),
+    );
+
+    assert!(unsorted_eq(&inputs, &[4_000_000, 2_000_000]));
+    assert_eq!(remainder, Some(500_000));
+}
+
+#[test]
+fn branch_and_bound() {
+    let (inputs, remainder) = select(
+        &[1_000_000, 2_000_000, 4_000_000, 6_000_000],
+        7_000_000,
+        Arc::new(BuiltinStrategy::BranchAndBound),
+    );
+
+    assert!(unsorted_eq(&inputs, &[6_000_000, 1_000_000]));
+    assert_eq!(remainder, None);
+
+    // Without an exact match, it falls back to minimizing the remainder.
+    let (inputs, remainder) = select(
+        &[2_000_000, 4_000_000, 6_000_000],
+        3_000_000,
+        Arc::new(BuiltinStrategy::BranchAndBound),
+    );
+
+    assert_eq!(inputs, vec![4_000_000]);
+    assert_eq!(remainder, Some(1_000_000));
+}
+
+#[test]
+fn oldest_first() {
+    let mut inputs = build_inputs(vec![
+        Basic(1_000_000, BECH32_ADDRESS_ED25519_0, None, None, None),
+        Basic(2_000_000, BECH32_ADDRESS_ED25519_0, None, None, None),
+        Basic(3_000_000, BECH32_ADDRESS_ED25519_0, None, None, None),
+    ]);
+    for (input, milestone_index_booked) in inputs.iter_mut().zip([3, 1, 2]) {
+        input.output_metadata = OutputMetadata::new(
+            *input.output_metadata.block_id(),
+            *input.output_id(),
+            false,
+            None,
+            None,
+            None,
+            milestone_index_booked,
+            0,
+            0,
+        );
+    }
+    let outputs = build_outputs(vec![Basic(2_500_000, BECH32_ADDRESS_ED25519_1, None, None, None)]);
+
+    let selected = InputSelection::new(inputs, outputs, protocol_parameters())
+        .strategy(Arc::new(BuiltinStrategy::OldestFirst))
+        .select()
+        .unwrap();
+
+    assert!(unsorted_eq(
+        &selected
+            .inputs
+            .iter()
+            .map(|input| input.output.amount())
+            .collect::<Vec<_>>(),
+        &[2_000_000, 3_000_000]
+    ));
+}
+
+#[test]
+fn custom_strategy() {
+    // Keeps the inputs in the order in which they were provided.
+    #[derive(Debug)]
+    struct ProvidedOrder;
+
+    impl InputSelectionStrategy for ProvidedOrder {
+        fn order(&self, _candidates: &mut [SelectionCandidate<'_>], _target: U256) {}
+    }
+
+    let (inputs, remainder) = select(&[5_000_000, 1_000_000, 2_000_000], 1_000_000, Arc::new(ProvidedOrder));
+
+    assert_eq!(inputs, vec![5_000_000]);
+    assert_eq!(remainder, Some(4_000_000));
+}
@@ -0,0 +1,176 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use iota_client::{
+    api::{
+        input_selection::InputSelection,
+        transaction_chain::{plan_transaction_chain, resolve_transaction_chain_step, transaction_chain_placeholders},
+    },
+    block::{
+        address::Address,
+        input::{Input, INPUT_COUNT_MAX},
+        ledger::{LedgerState, LedgerStateError},
+        output::OutputId,
+        payload::transaction::TransactionEssence,
+        protocol::protocol_parameters,
+        rand::transaction::rand_transaction_id,
+        semantic::ConflictReason,
+    },
+    Error,
+};
+
+use crate::input_selection::{
+    build_inputs, build_outputs, Build::Basic, BECH32_ADDRESS_ED25519_0, BECH32_ADDRESS_ED25519_1,
+};
+
+fn essence_inputs(essence: &TransactionEssence) -> Vec<OutputId> {
+    let TransactionEssence::Regular(essence) = essence;
+
+    essence
+        .inputs()
+        .iter()
+        .map(|input| match input {
+            Input::Utxo(input) => *input.output_id(),
+            _ => panic!("unexpected input kind"),
+        })
+        .collect()
+}
+
+#[test]
+fn single_transaction() {
+    let protocol_parameters = protocol_parameters();
+    let inputs = build_inputs(
+        (0..3)
+            .map(|_| Basic(2_000_000, BECH32_ADDRESS_ED25519_0, None, None, None))
+            .collect(),
+    );
+    let outputs = build_outputs(vec![Basic(3_000_000, BECH32_ADDRESS_ED25519_1, None, None, None)]);
+
+    let selected = InputSelection::new(inputs, outputs, protocol_parameters.clone())
+        .select()
+        .unwrap();
+    let steps = plan_transaction_chain(selected, None, &protocol_parameters).unwrap();
+
+    assert_eq!(steps.len(), 1);
+    assert_eq!(steps[0].inputs_data.len(), 2);
+}
+
+#[test]
+fn consolidation_step() {
+    let protocol_parameters = protocol_parameters();
+    let inputs = build_inputs(
+        (0..300)
+            .map(|_| Basic(1_000_000, BECH32_ADDRESS_ED25519_0, None, None, None))
+            .collect(),
+    );
+    let outputs = build_outputs(vec![Basic(250_000_000, BECH32_ADDRESS_ED25519_1, None, None, None)]);
+
+    let selected = InputSelection::new(inputs, outputs, protocol_parameters.clone())
+        .select()
+        .unwrap();
+    assert_eq!(selected.inputs.len(), 250);
+
+    let mut steps = plan_transaction_chain(selected, None, &protocol_parameters).unwrap();
+    let placeholders = transaction_chain_placeholders(&steps);
+
+    assert_eq!(steps.len(), 2);
+    // Consolidating 123 inputs into a single one leaves exactly the maximum number of inputs.
+    assert_eq!(steps[0].inputs_data.len(), 123);
+    assert_eq!(steps[1].inputs_data.len(), INPUT_COUNT_MAX as usize);
+
+    let TransactionEssence::Regular(consolidation) = &steps[0].essence;
+    assert_eq!(consolidation.outputs().len(), 1);
+    assert_eq!(consolidation.outputs()[0].amount(), 123_000_000);
+
+    // The payment spends the consolidated output through a placeholder transaction ID.
+    let consolidated = OutputId::new(placeholders[0], 0).unwrap();
+    assert!(essence_inputs(&steps[1].essence).contains(&consolidated));
+
+    let transaction_id = rand_transaction_id();
+    let (first, last) = steps.split_at_mut(1);
+    resolve_transaction_chain_step(&mut last[0], &placeholders[0], &transaction_id).unwrap();
+    let inputs = essence_inputs(&last[0].essence);
+
+    assert!(!inputs.contains(&consolidated));
+    assert!(inputs.contains(&OutputId::new(transaction_id, 0).unwrap()));
+    assert_eq!(
+        last[0]
+            .inputs_data
+            .iter()
+            .map(|input| *input.output_id())
+            .collect::<Vec<_>>(),
+        inputs
+    );
+    // Steps not spending outputs of the resolved one are left untouched.
+    let essence = first[0].essence.clone();
+    resolve_transaction_chain_step(&mut first[0], &placeholders[0], &transaction_id).unwrap();
+    assert_eq!(first[0].essence, essence);
+}
+
+#[test]
+fn dry_run_unsigned_chain() {
+    let protocol_parameters = protocol_parameters();
+    let inputs = build_inputs(
+        (0..300)
+            .map(|_| Basic(1_000_000, BECH32_ADDRESS_ED25519_0, None, None, None))
+            .collect(),
+    );
+    let outputs = build_outputs(vec![Basic(250_000_000, BECH32_ADDRESS_ED25519_1, None, None, None)]);
+    let state = LedgerState::new(inputs.iter().map(|input| (*input.output_id(), input.output.clone())), 0);
+    let signer = Address::try_from_bech32(BECH32_ADDRESS_ED25519_0).unwrap().1;
+
+    let selected = InputSelection::new(inputs, outputs, protocol_parameters.clone())
+        .select()
+        .unwrap();
+    let steps = plan_transaction_chain(selected, None, &protocol_parameters).unwrap();
+    let placeholders = transaction_chain_placeholders(&steps);
+    assert_eq!(steps.len(), 2);
+
+    // The payment spends the consolidated output under its placeholder ID, before anything is signed.
+    let (state, created) = state.apply_unsigned_transaction(&steps[0].essence, [signer]).unwrap();
+    assert_eq!(created, vec![OutputId::new(placeholders[0], 0).unwrap()]);
+    let (state, created) = state.apply_unsigned_transaction(&steps[1].essence, [signer]).unwrap();
+    assert!(state.is_unspent(&created[0]));
+    assert_eq!(state.len(), 300 - 250 + created.len());
+
+    // The inputs need a signature of their address.
+    assert!(matches!(
+        LedgerState::new(
+            steps[0]
+                .inputs_data
+                .iter()
+                .map(|input| (*input.output_id(), input.output.clone())),
+            0,
+        )
+        .apply_unsigned_transaction(&steps[0].essence, []),
+        Err(LedgerStateError::Conflict(ConflictReason::InvalidSignature))
+    ));
+}
+
+#[test]
+fn too_many_inputs_to_consolidate() {
+    let protocol_parameters = protocol_parameters();
+    let inputs = build_inputs(
+        (0..200)
+            .map(|_| {
+                Basic(
+                    1_000_000,
+                    BECH32_ADDRESS_ED25519_0,
+                    None,
+                    None,
+                    Some((BECH32_ADDRESS_ED25519_1, 100_000)),
+                )
+            })
+            .collect(),
+    );
+    let outputs = build_outputs(vec![Basic(150_000_000, BECH32_ADDRESS_ED25519_1, None, None, None)]);
+
+    let selected = InputSelection::new(inputs, outputs, protocol_parameters.clone())
+        .select()
+        .unwrap();
+
+    assert!(matches!(
+        plan_transaction_chain(selected, None, &protocol_parameters),
+        Err(Error::ConsolidationRequired(_))
+    ));
+}
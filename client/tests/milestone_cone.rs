@@ -0,0 +1,130 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use iota_client::{
+    block::{
+        output::{unlock_condition::AddressUnlockCondition, BasicOutputBuilder, UnlockCondition},
+        payload::milestone::MerkleHasher,
+    },
+    secret::{mnemonic::MnemonicSecretManager, SecretManager},
+    testing::MockNode,
+    Client, Result,
+};
+use iota_types::api::dto::LedgerInclusionStateDto;
+
+// THIS SEED SERVES FOR TESTING PURPOSES! DON'T USE THIS SEED IN PRODUCTION!
+const DEFAULT_DEVELOPMENT_SEED: &str = "0x256a818b2aac458941f7274985a410e57fb750f3a3a67969ece5bd9ae7eef5b2";
+
+#[tokio::test]
+async fn milestone_cone_in_white_flag_order() -> Result<()> {
+    let mock = MockNode::start()?;
+    mock.set_auto_milestones(false);
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+
+    let genesis_block_id = client.get_tips().await?[0];
+    let a = client.block().with_tag(b"a".to_vec()).finish().await?.id();
+    let b = client.block().with_tag(b"b".to_vec()).finish().await?.id();
+    let c = client
+        .block()
+        .with_parents(vec![genesis_block_id])?
+        .with_tag(b"c".to_vec())
+        .finish()
+        .await?
+        .id();
+    assert_eq!(mock.issue_milestone()?, 1);
+
+    // `b` approves `a`, so `a` is applied right before `b`, wherever `b` is.
+    let milestone = client.get_milestone_by_index(1).await?;
+    let mut expected = Vec::new();
+    for parent in milestone.essence().parents().iter() {
+        if *parent == b {
+            expected.extend([a, b]);
+        } else {
+            expected.push(c);
+        }
+    }
+
+    let cone = client.milestone_cone(1).await?;
+    assert_eq!(
+        cone.iter().map(|cone_block| cone_block.block_id).collect::<Vec<_>>(),
+        expected
+    );
+    for (white_flag_index, cone_block) in cone.iter().enumerate() {
+        assert_eq!(cone_block.metadata.referenced_by_milestone_index, Some(1));
+        assert_eq!(cone_block.metadata.white_flag_index, Some(white_flag_index as u32));
+    }
+
+    // The blocks confirmed by milestone 1 are not part of the cone of milestone 2.
+    let d = client.block().with_tag(b"d".to_vec()).finish().await?.id();
+    mock.issue_milestone()?;
+    let cone = client.milestone_cone(2).await?;
+    assert_eq!(cone.len(), 1);
+    assert_eq!(cone[0].block_id, d);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn milestone_cone_merkle_roots() -> Result<()> {
+    let mock = MockNode::start()?;
+    mock.set_auto_milestones(false);
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let addresses = client.get_addresses(&secret_manager).with_range(0..2).get_raw().await?;
+
+    mock.add_output(
+        BasicOutputBuilder::new_with_amount(10_000_000)?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(addresses[0])))
+            .finish_output(client.get_token_supply().await?)?,
+    )?;
+
+    client.block().with_tag(b"tag".to_vec()).finish().await?;
+    let transaction_block_id = client
+        .block()
+        .with_secret_manager(&secret_manager)
+        .with_output(&addresses[1].to_bech32(&client.get_bech32_hrp().await?), 1_000_000)
+        .await?
+        .finish()
+        .await?
+        .id();
+    mock.issue_milestone()?;
+
+    let milestone = client.get_milestone_by_index(1).await?;
+    let cone = client.milestone_cone(1).await?;
+    let included_block_ids = cone.iter().map(|cone_block| cone_block.block_id).collect::<Vec<_>>();
+    let applied_block_ids = cone
+        .iter()
+        .filter(|cone_block| cone_block.metadata.ledger_inclusion_state == Some(LedgerInclusionStateDto::Included))
+        .map(|cone_block| cone_block.block_id)
+        .collect::<Vec<_>>();
+
+    assert_eq!(included_block_ids.len(), 2);
+    assert_eq!(applied_block_ids, vec![transaction_block_id]);
+    assert_eq!(
+        &MerkleHasher::digest(&included_block_ids),
+        milestone.essence().inclusion_merkle_root()
+    );
+    assert_eq!(
+        &MerkleHasher::digest(&applied_block_ids),
+        milestone.essence().applied_merkle_root()
+    );
+
+    // The inclusion of the transaction can be proven with the milestone alone.
+    let index = included_block_ids
+        .iter()
+        .position(|block_id| *block_id == transaction_block_id)
+        .unwrap();
+    let inclusion_proof = MerkleHasher::proof(&included_block_ids, index).unwrap();
+    let applied_proof = MerkleHasher::proof(&applied_block_ids, 0).unwrap();
+    assert!(milestone
+        .essence()
+        .verify_inclusion_proof(&transaction_block_id, &inclusion_proof));
+    assert!(milestone
+        .essence()
+        .verify_applied_proof(&transaction_block_id, &applied_proof));
+    assert!(!milestone
+        .essence()
+        .verify_applied_proof(&included_block_ids[1 - index], &applied_proof));
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{fs, time::Duration};
+
+use futures::{pin_mut, StreamExt};
+use iota_client::{
+    block::{
+        output::{unlock_condition::AddressUnlockCondition, BasicOutputBuilder, UnlockCondition},
+        payload::milestone::MilestoneIndex,
+    },
+    db::FileDatabaseProvider,
+    secret::{mnemonic::MnemonicSecretManager, SecretManager},
+    testing::MockNode,
+    Client, Result,
+};
+
+// THIS SEED SERVES FOR TESTING PURPOSES! DON'T USE THIS SEED IN PRODUCTION!
+const DEFAULT_DEVELOPMENT_SEED: &str = "0x256a818b2aac458941f7274985a410e57fb750f3a3a67969ece5bd9ae7eef5b2";
+
+#[tokio::test]
+async fn milestone_follower_yields_milestones_in_order() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let bech32_hrp = client.get_bech32_hrp().await?;
+    let addresses = client.get_addresses(&secret_manager).with_range(0..2).get_raw().await?;
+
+    let genesis_output_id = mock.add_output(
+        BasicOutputBuilder::new_with_amount(10_000_000)?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(addresses[0])))
+            .finish_output(client.get_token_supply().await?)?,
+    )?;
+
+    // Milestone 1 is confirmed before the follower starts and has to be backfilled.
+    client.block().with_tag(b"first".to_vec()).finish().await?;
+
+    let stream = client
+        .milestone_follower(1)
+        .with_poll_interval(Duration::from_millis(50))
+        .into_stream()
+        .await?;
+    pin_mut!(stream);
+
+    let update = stream.next().await.unwrap()?;
+    assert_eq!(update.milestone.essence().index(), MilestoneIndex::new(1));
+    assert!(update.created_outputs.is_empty());
+
+    // Milestone 2 is confirmed while the follower is waiting.
+    let client_ = client.clone();
+    let bech32_address = addresses[1].to_bech32(&bech32_hrp);
+    let sender = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        client_
+            .block()
+            .with_secret_manager(&secret_manager)
+            .with_output(&bech32_address, 1_000_000)
+            .await?
+            .finish()
+            .await
+    });
+
+    let update = stream.next().await.unwrap()?;
+    sender.await.unwrap()?;
+    assert_eq!(update.milestone.essence().index(), MilestoneIndex::new(2));
+    assert_eq!(update.consumed_outputs.len(), 1);
+    assert_eq!(update.consumed_outputs[0].0, genesis_output_id);
+    assert_eq!(update.created_outputs.len(), 2);
+    assert_eq!(
+        update
+            .created_outputs
+            .iter()
+            .map(|(_, output)| output.amount())
+            .sum::<u64>(),
+        10_000_000
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn milestone_follower_resumes() -> Result<()> {
+    let path = "test_milestone_follower_resumes.db";
+    let _ = fs::remove_file(path);
+
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+
+    for _ in 0..3 {
+        client.block().with_tag(b"tag".to_vec()).finish().await?;
+    }
+
+    {
+        let stream = client
+            .milestone_follower(1)
+            .with_resume_state(FileDatabaseProvider::open(path)?)
+            .into_stream()
+            .await?;
+        pin_mut!(stream);
+
+        assert_eq!(
+            stream.next().await.unwrap()?.milestone.essence().index(),
+            MilestoneIndex::new(1)
+        );
+        assert_eq!(
+            stream.next().await.unwrap()?.milestone.essence().index(),
+            MilestoneIndex::new(2)
+        );
+    }
+
+    // Milestone 2 was yielded but not acknowledged by requesting the next one, so it is yielded again.
+    let stream = client
+        .milestone_follower(1)
+        .with_resume_state(FileDatabaseProvider::open(path)?)
+        .into_stream()
+        .await?;
+    pin_mut!(stream);
+
+    assert_eq!(
+        stream.next().await.unwrap()?.milestone.essence().index(),
+        MilestoneIndex::new(2)
+    );
+    assert_eq!(
+        stream.next().await.unwrap()?.milestone.essence().index(),
+        MilestoneIndex::new(3)
+    );
+
+    fs::remove_file(path)?;
+
+    Ok(())
+}
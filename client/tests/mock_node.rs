@@ -0,0 +1,174 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use iota_client::{
+    api_types::dto::LedgerInclusionStateDto,
+    block::{
+        output::{
+            unlock_condition::{
+                AddressUnlockCondition, GovernorAddressUnlockCondition, StateControllerAddressUnlockCondition,
+            },
+            AliasId, AliasOutputBuilder, BasicOutputBuilder, Output, UnlockCondition,
+        },
+        payload::Payload,
+    },
+    node_api::indexer::query_parameters::QueryParameter,
+    secret::{mnemonic::MnemonicSecretManager, SecretManager},
+    testing::MockNode,
+    Client, Result,
+};
+
+// THIS SEED SERVES FOR TESTING PURPOSES! DON'T USE THIS SEED IN PRODUCTION!
+const DEFAULT_DEVELOPMENT_SEED: &str = "0x256a818b2aac458941f7274985a410e57fb750f3a3a67969ece5bd9ae7eef5b2";
+
+#[tokio::test]
+async fn mock_node_info() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+
+    let info = client.get_info().await?.node_info;
+
+    assert!(info.status.is_healthy);
+    assert_eq!(info.status.latest_milestone.index, 0);
+    assert_eq!(client.get_protocol_parameters().await?, mock.protocol_parameters());
+    assert_eq!(client.get_tips().await?.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mock_node_send_and_confirm() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let bech32_hrp = client.get_bech32_hrp().await?;
+    let addresses = client.get_addresses(&secret_manager).with_range(0..2).get_raw().await?;
+
+    let genesis_output_id = mock.add_output(
+        BasicOutputBuilder::new_with_amount(10_000_000)?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(addresses[0])))
+            .finish_output(client.get_token_supply().await?)?,
+    )?;
+
+    let block = client
+        .block()
+        .with_secret_manager(&secret_manager)
+        .with_output(&addresses[1].to_bech32(&bech32_hrp), 1_000_000)
+        .await?
+        .finish()
+        .await?;
+
+    let included = client.retry_until_included(&block.id(), Some(0), Some(5)).await?;
+    assert_eq!(included[0].0, block.id());
+    assert_eq!(mock.latest_milestone_index(), 1);
+
+    let metadata = client.get_block_metadata(&block.id()).await?;
+    assert_eq!(metadata.ledger_inclusion_state, Some(LedgerInclusionStateDto::Included));
+
+    let transaction_id = match block.payload() {
+        Some(Payload::Transaction(transaction)) => transaction.id(),
+        _ => panic!("expected a transaction payload"),
+    };
+    assert_eq!(client.get_included_block(&transaction_id).await?, block);
+
+    // The genesis output is spent and the funds moved to the second address and the remainder.
+    assert!(client.get_output_metadata(&genesis_output_id).await?.is_spent);
+    let output_ids = client
+        .basic_output_ids(vec![QueryParameter::Address(addresses[1].to_bech32(&bech32_hrp))])
+        .await?;
+    assert_eq!(output_ids.len(), 1);
+    let output = Output::try_from_dto(
+        &client.get_outputs(output_ids).await?[0].output,
+        client.get_token_supply().await?,
+    )?;
+    assert_eq!(output.amount(), 1_000_000);
+
+    let utxo_changes = client.get_utxo_changes_by_index(1).await?;
+    assert_eq!(utxo_changes.consumed_outputs, vec![genesis_output_id.to_string()]);
+    assert_eq!(utxo_changes.created_outputs.len(), 2);
+
+    let milestone = client.get_milestone_by_index(1).await?;
+    assert_eq!(milestone.essence().parents().first(), Some(&block.id()));
+    assert!(milestone
+        .validate(&[prefix_hex::encode(mock.coordinator_public_key())[2..].to_string()], 1)
+        .is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mock_node_rejects_double_spend() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let bech32_hrp = client.get_bech32_hrp().await?;
+    let address = client.get_addresses(&secret_manager).with_range(0..1).get_raw().await?[0];
+
+    mock.add_output(
+        BasicOutputBuilder::new_with_amount(1_000_000)?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)))
+            .finish_output(client.get_token_supply().await?)?,
+    )?;
+
+    let builder = client
+        .block()
+        .with_secret_manager(&secret_manager)
+        .with_output(&address.to_bech32(&bech32_hrp), 1_000_000)
+        .await?;
+    let prepared = builder.prepare_transaction().await?;
+    let payload = builder.sign_transaction(prepared).await?;
+
+    let first = client.finish_block_builder(None, Some(payload.clone())).await?;
+    client.post_block_raw(&first).await?;
+    // Different parents, same transaction.
+    let second = client.finish_block_builder(None, Some(payload)).await?;
+    client.post_block(&second).await?;
+
+    let metadata = client.get_block_metadata(&second.id()).await?;
+    assert_eq!(
+        metadata.ledger_inclusion_state,
+        Some(LedgerInclusionStateDto::Conflicting)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mock_node_alias_state_transition_unlocked_by_state_controller() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let bech32_hrp = client.get_bech32_hrp().await?;
+    let addresses = client.get_addresses(&secret_manager).with_range(0..2).get_raw().await?;
+    let token_supply = client.get_token_supply().await?;
+
+    let alias = AliasOutputBuilder::new_with_amount(1_000_000, AliasId::null())?
+        .add_unlock_condition(UnlockCondition::StateControllerAddress(
+            StateControllerAddressUnlockCondition::new(addresses[0]),
+        ))
+        .add_unlock_condition(UnlockCondition::GovernorAddress(GovernorAddressUnlockCondition::new(
+            addresses[1],
+        )))
+        .finish(token_supply)?;
+    let alias_id = AliasId::from(&mock.add_output(Output::Alias(alias.clone()))?);
+    let next_state = AliasOutputBuilder::from(&alias)
+        .with_alias_id(alias_id)
+        .with_state_index(alias.state_index() + 1)
+        .finish_output(token_supply)?;
+
+    let prepared_transaction_data = client
+        .block()
+        .with_secret_manager(&secret_manager)
+        .with_outputs(vec![next_state])?
+        .prepare_transaction()
+        .await?;
+
+    // The state transition is unlocked by the state controller, not by the governor.
+    assert_eq!(prepared_transaction_data.inputs_data.len(), 1);
+    assert_eq!(
+        prepared_transaction_data.inputs_data[0].bech32_address,
+        addresses[0].to_bech32(&bech32_hrp)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use iota_client::{
+    api::NativeTokenMetadata,
+    block::{
+        address::{Address, AliasAddress, Ed25519Address},
+        output::{
+            feature::{Feature, MetadataFeature},
+            unlock_condition::{
+                GovernorAddressUnlockCondition, ImmutableAliasAddressUnlockCondition,
+                StateControllerAddressUnlockCondition, TimelockUnlockCondition,
+            },
+            AliasId, AliasOutputBuilder, FoundryOutputBuilder, NativeToken, Output, SimpleTokenScheme, TokenId,
+            TokenScheme, UnlockCondition,
+        },
+    },
+    testing::MockNode,
+    Client, Result,
+};
+use primitive_types::U256;
+
+use self::common::build_basic_output;
+
+#[tokio::test]
+async fn native_token_balances() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let token_supply = client.get_token_supply().await?;
+    let now = client.get_time_checked().await?;
+    let address = Address::Ed25519(Ed25519Address::new([1; 32]));
+    let other_address = Address::Ed25519(Ed25519Address::new([2; 32]));
+    let alias_id = AliasId::new([3; 32]);
+
+    let foundry = FoundryOutputBuilder::new_with_amount(
+        1_000_000,
+        1,
+        TokenScheme::Simple(SimpleTokenScheme::new(
+            U256::from(1_000),
+            U256::from(0),
+            U256::from(1_000),
+        )?),
+    )?
+    .add_unlock_condition(UnlockCondition::ImmutableAliasAddress(
+        ImmutableAliasAddressUnlockCondition::new(AliasAddress::new(alias_id)),
+    ))
+    .add_immutable_feature(Feature::Metadata(MetadataFeature::new(
+        br#"{"standard":"IRC30","name":"Shimmer Token","symbol":"STK","decimals":6,"description":"A token"}"#.to_vec(),
+    )?))
+    .finish(token_supply)?;
+    let token_id = foundry.token_id();
+    let foundry_output_id = mock.add_output(Output::Foundry(foundry.clone()))?;
+    // The foundry of this token was destroyed.
+    let destroyed_token_id = TokenId::new([4; 38]);
+
+    mock.add_output(build_basic_output(
+        1_000_000,
+        address,
+        Some(vec![NativeToken::new(token_id, U256::from(100))?]),
+        None,
+        token_supply,
+    )?)?;
+    mock.add_output(build_basic_output(
+        1_000_000,
+        address,
+        Some(vec![NativeToken::new(token_id, U256::from(50))?]),
+        Some(UnlockCondition::Timelock(TimelockUnlockCondition::new(now + 3600)?)),
+        token_supply,
+    )?)?;
+    mock.add_output(build_basic_output(
+        1_000_000,
+        address,
+        Some(vec![NativeToken::new(destroyed_token_id, U256::from(10))?]),
+        None,
+        token_supply,
+    )?)?;
+    mock.add_output(build_basic_output(
+        1_000_000,
+        other_address,
+        Some(vec![NativeToken::new(token_id, U256::from(1))?]),
+        None,
+        token_supply,
+    )?)?;
+    mock.add_output(
+        AliasOutputBuilder::new_with_amount(1_000_000, alias_id)?
+            .add_native_token(NativeToken::new(token_id, U256::from(25))?)
+            .with_foundry_counter(1)
+            .add_unlock_condition(UnlockCondition::StateControllerAddress(
+                StateControllerAddressUnlockCondition::new(address),
+            ))
+            .add_unlock_condition(UnlockCondition::GovernorAddress(GovernorAddressUnlockCondition::new(
+                other_address,
+            )))
+            .finish_output(token_supply)?,
+    )?;
+
+    let balances = client.native_token_balances(&[address]).await?;
+    assert_eq!(balances.len(), 2);
+
+    let balance = balances.iter().find(|balance| balance.token_id == token_id).unwrap();
+    assert_eq!(balance.unlocked, U256::from(125));
+    assert_eq!(balance.locked, U256::from(50));
+    assert_eq!(balance.total(), U256::from(175));
+    assert_eq!(balance.foundry_output_id, Some(foundry_output_id));
+    assert_eq!(balance.foundry.as_ref(), Some(&foundry));
+    assert_eq!(
+        balance.metadata,
+        Some(NativeTokenMetadata {
+            name: "Shimmer Token".to_string(),
+            symbol: "STK".to_string(),
+            decimals: 6,
+        })
+    );
+
+    let balance = balances
+        .iter()
+        .find(|balance| balance.token_id == destroyed_token_id)
+        .unwrap();
+    assert_eq!(balance.unlocked, U256::from(10));
+    assert_eq!(balance.locked, U256::from(0));
+    assert_eq!(balance.foundry, None);
+    assert_eq!(balance.metadata, None);
+
+    Ok(())
+}
+
+#[test]
+fn irc30_metadata() {
+    assert_eq!(
+        NativeTokenMetadata::from_irc30(br#"{"standard":"IRC30","name":"Token","symbol":"TK","decimals":0}"#),
+        Some(NativeTokenMetadata {
+            name: "Token".to_string(),
+            symbol: "TK".to_string(),
+            decimals: 0,
+        })
+    );
+    assert_eq!(
+        NativeTokenMetadata::from_irc30(br#"{"standard":"IRC27","name":"Token","symbol":"TK","decimals":0}"#),
+        None
+    );
+    assert_eq!(
+        NativeTokenMetadata::from_irc30(br#"{"standard":"IRC30","name":"Token"}"#),
+        None
+    );
+    assert_eq!(NativeTokenMetadata::from_irc30(b"not json"), None);
+}
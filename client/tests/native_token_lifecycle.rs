@@ -0,0 +1,128 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use iota_client::{
+    block::{
+        address::Address,
+        output::{
+            irc::Irc30Metadata,
+            unlock_condition::{
+                AddressUnlockCondition, GovernorAddressUnlockCondition, StateControllerAddressUnlockCondition,
+            },
+            AliasId, AliasOutputBuilder, BasicOutputBuilder, TokenId, TokenScheme, UnlockCondition,
+        },
+    },
+    secret::{mnemonic::MnemonicSecretManager, SecretManager},
+    testing::MockNode,
+    Client, Error, Result,
+};
+use primitive_types::U256;
+
+use self::common::{alias_output, foundry_output};
+
+// THIS SEED SERVES FOR TESTING PURPOSES! DON'T USE THIS SEED IN PRODUCTION!
+const DEFAULT_DEVELOPMENT_SEED: &str = "0x256a818b2aac458941f7274985a410e57fb750f3a3a67969ece5bd9ae7eef5b2";
+
+async fn balance(client: &Client, address: Address, token_id: TokenId) -> Result<U256> {
+    Ok(client
+        .native_token_balances(&[address])
+        .await?
+        .iter()
+        .find(|balance| balance.token_id == token_id)
+        .map_or(U256::zero(), |balance| balance.total()))
+}
+
+#[tokio::test]
+async fn native_token_lifecycle() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let address = client.get_addresses(&secret_manager).with_range(0..1).get_raw().await?[0];
+    let token_supply = client.get_token_supply().await?;
+
+    mock.add_output(
+        BasicOutputBuilder::new_with_amount(1_000_000_000)?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)))
+            .finish_output(token_supply)?,
+    )?;
+    let alias_id = AliasId::from(
+        &mock.add_output(
+            AliasOutputBuilder::new_with_amount(1_000_000, AliasId::null())?
+                .add_unlock_condition(UnlockCondition::StateControllerAddress(
+                    StateControllerAddressUnlockCondition::new(address),
+                ))
+                .add_unlock_condition(UnlockCondition::GovernorAddress(GovernorAddressUnlockCondition::new(
+                    address,
+                )))
+                .finish_output(token_supply)?,
+        )?,
+    );
+    let metadata = Irc30Metadata::new("Shimmer Token", "STK", 6)?;
+
+    let created = client
+        .create_native_token(
+            &secret_manager,
+            alias_id,
+            U256::from(100),
+            U256::from(1_000),
+            Some(&metadata),
+        )
+        .await?;
+    let token_id = created.token_id;
+
+    let alias = alias_output(&client, alias_id).await?;
+    assert_eq!(alias.state_index(), 1);
+    assert_eq!(alias.foundry_counter(), 1);
+
+    let foundry = foundry_output(&client, token_id).await?;
+    assert_eq!(foundry.serial_number(), 1);
+    assert_eq!(
+        Irc30Metadata::try_from(foundry.immutable_features().metadata().unwrap())?,
+        metadata
+    );
+    assert_eq!(balance(&client, address, token_id).await?, U256::from(100));
+
+    // A second token gets the next serial number.
+    let other = client
+        .create_native_token(&secret_manager, alias_id, U256::zero(), U256::from(10), None)
+        .await?;
+    assert_ne!(other.token_id, token_id);
+    assert_eq!(foundry_output(&client, other.token_id).await?.serial_number(), 2);
+    assert_eq!(alias_output(&client, alias_id).await?.foundry_counter(), 2);
+
+    let minted = client
+        .mint_native_token(&secret_manager, token_id, U256::from(50))
+        .await?;
+    assert_eq!(minted.token_id, token_id);
+    assert_ne!(minted.transaction_id, created.transaction_id);
+    assert_eq!(balance(&client, address, token_id).await?, U256::from(150));
+
+    client
+        .melt_native_token(&secret_manager, token_id, U256::from(30))
+        .await?;
+    assert_eq!(balance(&client, address, token_id).await?, U256::from(120));
+
+    client
+        .burn_native_token(&secret_manager, token_id, U256::from(20))
+        .await?;
+    assert_eq!(balance(&client, address, token_id).await?, U256::from(100));
+
+    let TokenScheme::Simple(token_scheme) = foundry_output(&client, token_id).await?.token_scheme().clone();
+    assert_eq!(token_scheme.minted_tokens(), U256::from(150));
+    assert_eq!(token_scheme.melted_tokens(), U256::from(30));
+    // Burnt tokens are still part of the circulating supply.
+    assert_eq!(token_scheme.circulating_supply(), U256::from(120));
+    assert_eq!(alias_output(&client, alias_id).await?.state_index(), 4);
+
+    // Minting beyond the maximum supply is rejected before sending anything.
+    assert!(matches!(
+        client
+            .mint_native_token(&secret_manager, token_id, U256::from(1_000))
+            .await,
+        Err(Error::BlockError(_))
+    ));
+
+    Ok(())
+}
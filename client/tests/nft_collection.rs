@@ -0,0 +1,116 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use iota_client::{
+    api::NftTransfer,
+    block::{
+        address::{Address, Ed25519Address, NftAddress},
+        output::{
+            feature::{Feature, IssuerFeature},
+            irc::Irc27Metadata,
+            unlock_condition::{AddressUnlockCondition, UnlockCondition},
+            BasicOutputBuilder, NftId, NftOutputBuilder,
+        },
+    },
+    secret::{mnemonic::MnemonicSecretManager, SecretManager},
+    testing::MockNode,
+    Client, Result,
+};
+
+use self::common::nft_output;
+
+// THIS SEED SERVES FOR TESTING PURPOSES! DON'T USE THIS SEED IN PRODUCTION!
+const DEFAULT_DEVELOPMENT_SEED: &str = "0x256a818b2aac458941f7274985a410e57fb750f3a3a67969ece5bd9ae7eef5b2";
+
+#[tokio::test]
+async fn mint_and_send_nfts() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let address = client.get_addresses(&secret_manager).with_range(0..1).get_raw().await?[0];
+    let receiver = Address::Ed25519(Ed25519Address::new([1; 32]));
+    let token_supply = client.get_token_supply().await?;
+
+    mock.add_output(
+        BasicOutputBuilder::new_with_amount(1_000_000_000)?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)))
+            .finish_output(token_supply)?,
+    )?;
+    let collection_id = NftId::from(
+        &mock.add_output(
+            NftOutputBuilder::new_with_amount(1_000_000, NftId::null())?
+                .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)))
+                .finish_output(token_supply)?,
+        )?,
+    );
+
+    // Large metadata so that the NFTs don't fit in a single block.
+    let metadata = (0..10)
+        .map(|index| {
+            Ok(Irc27Metadata::new(
+                "image/png",
+                format!("https://example.com/{index}.png"),
+                format!("NFT {index}"),
+            )?
+            .with_collection_name("Collection")
+            .with_description("a".repeat(4000)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let minted = client
+        .mint_nfts(&secret_manager, collection_id, metadata.clone())
+        .await?;
+    let nft_ids = minted
+        .iter()
+        .flat_map(|minted| minted.nft_ids.clone())
+        .collect::<Vec<_>>();
+
+    assert_eq!(minted.len(), 2);
+    assert_eq!(nft_ids.len(), metadata.len());
+
+    for (nft_id, metadata) in nft_ids.iter().zip(&metadata) {
+        let nft = nft_output(&client, *nft_id).await?;
+
+        assert_eq!(nft.address(), &address);
+        assert_eq!(
+            nft.immutable_features().issuer(),
+            Some(&IssuerFeature::new(Address::Nft(NftAddress::new(collection_id))))
+        );
+        assert_eq!(
+            &Irc27Metadata::try_from(nft.immutable_features().metadata().unwrap())?,
+            metadata
+        );
+    }
+    // The collection NFT is kept by its owner.
+    assert_eq!(nft_output(&client, collection_id).await?.address(), &address);
+
+    let expiration = client.get_time_checked().await? + 3600;
+    let blocks = client
+        .send_nfts(
+            &secret_manager,
+            vec![
+                NftTransfer::new(nft_ids[0], receiver),
+                NftTransfer::new(nft_ids[1], receiver).with_expiration(expiration),
+            ],
+        )
+        .await?;
+    assert_eq!(blocks.len(), 1);
+
+    let sent = nft_output(&client, nft_ids[0]).await?;
+    assert_eq!(sent.address(), &receiver);
+    assert_eq!(sent.unlock_conditions().len(), 1);
+
+    let gifted = nft_output(&client, nft_ids[1]).await?;
+    let gift_expiration = gifted.unlock_conditions().expiration().unwrap();
+    assert_eq!(gifted.address(), &receiver);
+    assert_eq!(gift_expiration.return_address(), &address);
+    assert_eq!(gift_expiration.timestamp(), expiration);
+    assert!(gifted
+        .features()
+        .iter()
+        .all(|feature| !matches!(feature, Feature::Sender(_))));
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::TcpListener;
+
+use iota_client::{
+    node_manager::scoreboard::{NodeScore, NodeSelectionPolicy},
+    testing::MockNode,
+    Client, Result, Url,
+};
+
+fn node_score(client: &Client, url: &str) -> Result<NodeScore> {
+    let url = Url::parse(url)?;
+
+    Ok(client
+        .get_node_scores()?
+        .into_iter()
+        .find(|score| score.url == url)
+        .expect("node without score"))
+}
+
+#[tokio::test]
+async fn best_score_prefers_synced_nodes() -> Result<()> {
+    let synced = MockNode::start()?;
+    let lagging = MockNode::start()?;
+
+    for _ in 0..3 {
+        synced.issue_milestone()?;
+    }
+
+    let client = Client::builder().with_nodes(&[synced.url(), lagging.url()])?.finish()?;
+
+    let scores = client.get_node_scores()?;
+    assert_eq!(scores.len(), 2);
+    assert_eq!(scores[0].url, Url::parse(synced.url())?);
+    assert_eq!(scores[0].milestone_lag, Some(0));
+    assert_eq!(scores[1].url, Url::parse(lagging.url())?);
+    assert_eq!(scores[1].milestone_lag, Some(3));
+    assert!(scores[0].score() > scores[1].score());
+    assert_eq!(client.get_node()?.url, Url::parse(synced.url())?);
+
+    for _ in 0..3 {
+        client.get_tips().await?;
+    }
+
+    let score = node_score(&client, synced.url())?;
+    assert_eq!(score.requests, 3);
+    assert_eq!(score.errors, 0);
+    assert!(score.latency.is_some());
+    assert_eq!(node_score(&client, lagging.url())?.requests, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn round_robin_records_errors() -> Result<()> {
+    let mock = MockNode::start()?;
+    // Nothing listens on the port of a dropped listener.
+    let unreachable = format!(
+        "http://{}",
+        TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap()
+    );
+
+    let client = Client::builder()
+        .with_nodes(&[mock.url(), &unreachable])?
+        .with_ignore_node_health()
+        .with_node_selection_policy(NodeSelectionPolicy::RoundRobin)
+        .finish()?;
+
+    // Requests first sent to the unreachable node fall back to the mock node.
+    for _ in 0..4 {
+        client.get_tips().await?;
+    }
+
+    let score = node_score(&client, mock.url())?;
+    assert_eq!(score.requests, 4);
+    assert_eq!(score.errors, 0);
+    assert_eq!(score.error_rate, 0.0);
+
+    let score = node_score(&client, &unreachable)?;
+    assert_eq!(score.requests, 2);
+    assert_eq!(score.errors, 2);
+    assert!(score.error_rate > 0.0);
+    assert_eq!(score.latency, None);
+
+    // The unreachable node is now scored lower.
+    let client = Client::builder()
+        .with_nodes(&[mock.url(), &unreachable])?
+        .with_ignore_node_health()
+        .finish()?;
+
+    client.get_tips().await?;
+    client.get_tips().await?;
+    assert_eq!(client.get_node()?.url, Url::parse(mock.url())?);
+    assert_eq!(node_score(&client, &unreachable)?.requests, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn weighted_random_routes_requests() -> Result<()> {
+    let first = MockNode::start()?;
+    let second = MockNode::start()?;
+
+    let client = Client::builder()
+        .with_nodes(&[first.url(), second.url()])?
+        .with_node_selection_policy(NodeSelectionPolicy::WeightedRandom)
+        .finish()?;
+
+    for _ in 0..10 {
+        client.get_tips().await?;
+    }
+
+    let requests = node_score(&client, first.url())?.requests + node_score(&client, second.url())?.requests;
+    assert_eq!(requests, 10);
+
+    Ok(())
+}
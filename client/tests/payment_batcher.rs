@@ -0,0 +1,272 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use iota_client::{
+    api::{Payment, PAYMENTS_PER_TRANSACTION_MAX},
+    block::output::{
+        feature::{Feature, MetadataFeature},
+        unlock_condition::AddressUnlockCondition,
+        BasicOutputBuilder, UnlockCondition,
+    },
+    secret::{mnemonic::MnemonicSecretManager, SecretManager},
+    testing::MockNode,
+    Client, Result,
+};
+
+// THIS SEED SERVES FOR TESTING PURPOSES! DON'T USE THIS SEED IN PRODUCTION!
+const DEFAULT_DEVELOPMENT_SEED: &str = "0x256a818b2aac458941f7274985a410e57fb750f3a3a67969ece5bd9ae7eef5b2";
+
+#[tokio::test]
+async fn payment_batcher_triggers() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let address = client.get_addresses(&secret_manager).with_range(0..1).get_raw().await?[0];
+
+    let mut batcher = client
+        .payment_batcher(&secret_manager)
+        .with_flush_threshold(2)
+        .with_max_delay(Duration::from_secs(3600));
+
+    batcher.enqueue(Payment::new(address, 1_000_000));
+    assert!(!batcher.is_due());
+    assert!(batcher.flush_if_due().await?.is_empty());
+    batcher.enqueue(Payment::new(address, 1_000_000));
+    assert!(batcher.is_due());
+
+    let mut batcher = client.payment_batcher(&secret_manager).with_max_delay(Duration::ZERO);
+
+    batcher.enqueue(Payment::new(address, 1_000_000));
+    assert!(batcher.is_due());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn payment_batcher_sends_batches() -> Result<()> {
+    let mock = MockNode::start()?;
+    mock.set_auto_milestones(false);
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let addresses = client.get_addresses(&secret_manager).with_range(0..2).get_raw().await?;
+    let token_supply = client.get_token_supply().await?;
+
+    for _ in 0..2 {
+        mock.add_output(
+            BasicOutputBuilder::new_with_amount(200_000_000)?
+                .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(addresses[0])))
+                .finish_output(token_supply)?,
+        )?;
+    }
+
+    let mut batcher = client.payment_batcher(&secret_manager);
+    let ids = (0..PAYMENTS_PER_TRANSACTION_MAX + 3)
+        .map(|_| batcher.enqueue(Payment::new(addresses[1], 1_000_000)))
+        .collect::<Vec<_>>();
+
+    assert!(batcher.is_due());
+    let receipts = batcher.flush_if_due().await?;
+
+    // The second transaction can't spend the unconfirmed remainder of the first one nor its input.
+    assert_eq!(receipts.len(), ids.len());
+    assert_eq!(
+        receipts.iter().map(|receipt| receipt.payment_id).collect::<Vec<_>>(),
+        ids
+    );
+    assert!(receipts[..PAYMENTS_PER_TRANSACTION_MAX]
+        .iter()
+        .all(|receipt| receipt.transaction_id == receipts[0].transaction_id));
+    assert_ne!(
+        receipts[PAYMENTS_PER_TRANSACTION_MAX].transaction_id,
+        receipts[0].transaction_id
+    );
+    assert_eq!(batcher.queued(), 0);
+    assert_eq!(batcher.pending(), receipts);
+
+    // Nothing is confirmed yet.
+    assert_eq!(batcher.poll_pending().await?, Default::default());
+
+    mock.issue_milestone()?;
+    let status = batcher.poll_pending().await?;
+
+    assert_eq!(status.confirmed, receipts);
+    assert!(status.requeued.is_empty());
+    assert!(batcher.pending().is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn payment_batcher_splits_large_payments() -> Result<()> {
+    let mock = MockNode::start()?;
+    mock.set_auto_milestones(false);
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let addresses = client.get_addresses(&secret_manager).with_range(0..2).get_raw().await?;
+    let token_supply = client.get_token_supply().await?;
+
+    for _ in 0..2 {
+        mock.add_output(
+            BasicOutputBuilder::new_with_amount(1_000_000_000)?
+                .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(addresses[0])))
+                .finish_output(token_supply)?,
+        )?;
+    }
+
+    // Far fewer than PAYMENTS_PER_TRANSACTION_MAX of these payments fit in a block.
+    let mut batcher = client.payment_batcher(&secret_manager);
+    let ids = (0..8)
+        .map(|_| {
+            batcher.enqueue(
+                Payment::new(addresses[1], 100_000_000)
+                    .with_features([Feature::Metadata(MetadataFeature::new(vec![0; 6000]).unwrap())]),
+            )
+        })
+        .collect::<Vec<_>>();
+    let receipts = batcher.flush().await?;
+
+    assert_eq!(
+        receipts.iter().map(|receipt| receipt.payment_id).collect::<Vec<_>>(),
+        ids
+    );
+    assert_ne!(receipts[0].transaction_id, receipts[7].transaction_id);
+    assert_eq!(batcher.queued(), 0);
+
+    mock.issue_milestone()?;
+    assert_eq!(batcher.poll_pending().await?.confirmed, receipts);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn payment_batcher_requeues_conflicting_payments() -> Result<()> {
+    let mock = MockNode::start()?;
+    mock.set_auto_milestones(false);
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let bech32_hrp = client.get_bech32_hrp().await?;
+    let addresses = client.get_addresses(&secret_manager).with_range(0..3).get_raw().await?;
+
+    mock.add_output(
+        BasicOutputBuilder::new_with_amount(10_000_000)?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(addresses[0])))
+            .finish_output(client.get_token_supply().await?)?,
+    )?;
+
+    // Another transaction spends the same output first.
+    client
+        .block()
+        .with_secret_manager(&secret_manager)
+        .with_output(&addresses[1].to_bech32(&bech32_hrp), 10_000_000)
+        .await?
+        .finish()
+        .await?;
+
+    let mut batcher = client.payment_batcher(&secret_manager);
+    let id = batcher.enqueue(Payment::new(addresses[2], 1_000_000));
+    let receipts = batcher.flush().await?;
+
+    mock.issue_milestone()?;
+    let status = batcher.poll_pending().await?;
+
+    assert!(status.confirmed.is_empty());
+    assert_eq!(status.requeued, vec![id]);
+    assert_eq!(batcher.queued(), 1);
+    assert!(batcher.pending().is_empty());
+
+    // The payment is sent again with the funds moved by the other transaction.
+    let retried = batcher.flush().await?;
+
+    assert_eq!(retried[0].payment_id, id);
+    assert_ne!(retried[0].transaction_id, receipts[0].transaction_id);
+
+    mock.issue_milestone()?;
+    assert_eq!(batcher.poll_pending().await?.confirmed, retried);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn payment_batcher_keeps_failed_posts_pending() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let addresses = client.get_addresses(&secret_manager).with_range(0..2).get_raw().await?;
+
+    mock.add_output(
+        BasicOutputBuilder::new_with_amount(100_000_000)?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(addresses[0])))
+            .finish_output(client.get_token_supply().await?)?,
+    )?;
+
+    let mut batcher = client.payment_batcher(&secret_manager);
+
+    // Nothing is posted, the payment stays queued.
+    let id = batcher.enqueue(Payment::new(addresses[1], 1_000_000));
+    mock.fail_requests(503, 1);
+    assert!(batcher.flush().await.is_err());
+    assert_eq!(batcher.queued(), 1);
+    assert!(batcher.pending().is_empty());
+
+    // The node processed the block before failing, the payment must not be sent again.
+    mock.fail_block_posts(500, 1, true);
+    assert!(batcher.flush().await.is_err());
+    assert_eq!(batcher.queued(), 0);
+    let pending = batcher.pending();
+    assert_eq!(pending[0].payment_id, id);
+
+    let status = batcher.poll_pending().await?;
+    assert_eq!(status.confirmed, pending);
+    assert!(status.requeued.is_empty() && status.reattached.is_empty());
+
+    // The node didn't process the block, the same transaction is sent again.
+    let id = batcher.enqueue(Payment::new(addresses[1], 1_000_000));
+    mock.fail_block_posts(500, 1, false);
+    assert!(batcher.flush().await.is_err());
+    let pending = batcher.pending();
+    assert_eq!(pending[0].payment_id, id);
+
+    let status = batcher.poll_pending().await?;
+    assert!(status.confirmed.is_empty() && status.requeued.is_empty());
+    assert_eq!(status.reattached[0].transaction_id, pending[0].transaction_id);
+    assert_eq!(batcher.poll_pending().await?.confirmed, status.reattached);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn payment_batcher_reattaches_orphaned_blocks() -> Result<()> {
+    let mock = MockNode::start()?;
+    mock.set_auto_milestones(false);
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let addresses = client.get_addresses(&secret_manager).with_range(0..2).get_raw().await?;
+
+    mock.add_output(
+        BasicOutputBuilder::new_with_amount(100_000_000)?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(addresses[0])))
+            .finish_output(client.get_token_supply().await?)?,
+    )?;
+
+    let mut batcher = client.payment_batcher(&secret_manager);
+    batcher.enqueue(Payment::new(addresses[1], 1_000_000));
+    let receipts = batcher.flush().await?;
+
+    // The block will never be confirmed and the node asks for a reattachment.
+    mock.orphan_tips();
+    // The reattachment gets another parent.
+    client.block().finish().await?;
+    let status = batcher.poll_pending().await?;
+
+    assert!(status.confirmed.is_empty() && status.requeued.is_empty());
+    assert_eq!(status.reattached[0].transaction_id, receipts[0].transaction_id);
+    assert_ne!(status.reattached[0].block_id, receipts[0].block_id);
+    assert_eq!(batcher.pending(), status.reattached);
+
+    mock.issue_milestone()?;
+    assert_eq!(batcher.poll_pending().await?.confirmed, status.reattached);
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use iota_client::{block::output::AliasId, testing::MockNode, Client, Error, Result};
+use packable::PackableExt;
+
+#[tokio::test]
+async fn pruned_data_from_permanodes() -> Result<()> {
+    let node = MockNode::start()?;
+    let permanode = MockNode::start()?;
+
+    // Both nodes confirm the same block with their first milestone, then the node prunes it.
+    let block = Client::builder()
+        .with_node(node.url())?
+        .finish()?
+        .block()
+        .with_tag(b"pruned".to_vec())
+        .finish()
+        .await?;
+    Client::builder()
+        .with_node(permanode.url())?
+        .finish()?
+        .post_block_raw(&block)
+        .await?;
+    node.prune(1);
+
+    let client = Client::builder()
+        .with_node(node.url())?
+        .with_permanode(permanode.url(), None)?
+        .finish()?;
+
+    // The pruned milestone is requested from the permanode right away.
+    let request_count = node.request_count();
+    let utxo_changes = client.get_utxo_changes_by_index(1).await?;
+    assert_eq!(utxo_changes.index, 1);
+    assert_eq!(node.request_count(), request_count);
+
+    // The node doesn't find the pruned block, so the permanode is asked.
+    let request_count = (node.request_count(), permanode.request_count());
+    assert_eq!(client.get_block_raw(&block.id()).await?, block.pack_to_vec());
+    assert_eq!(node.request_count(), request_count.0 + 1);
+    assert_eq!(permanode.request_count(), request_count.1 + 1);
+
+    // Indexer lookups aren't historical data, the permanode isn't asked for them.
+    let request_count = permanode.request_count();
+    assert!(client.alias_output_id(AliasId::null()).await.is_err());
+    assert_eq!(permanode.request_count(), request_count);
+
+    // Milestones that weren't pruned aren't requested from the permanode.
+    let request_count = permanode.request_count();
+    assert!(matches!(
+        client.get_utxo_changes_by_index(2).await,
+        Err(Error::NotFound(_))
+    ));
+    assert_eq!(permanode.request_count(), request_count);
+
+    // Nodes that didn't prune anything have all the data.
+    let unpruned_node = MockNode::start()?;
+    let client = Client::builder()
+        .with_node(unpruned_node.url())?
+        .with_permanode(permanode.url(), None)?
+        .finish()?;
+
+    let request_count = permanode.request_count();
+    assert!(matches!(
+        client.get_block_raw(&block.id()).await,
+        Err(Error::NotFound(_))
+    ));
+    assert_eq!(permanode.request_count(), request_count);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pruned_data_from_permanodes_with_unknown_node_info() -> Result<()> {
+    let node = MockNode::start()?;
+    let permanode = MockNode::start()?;
+
+    let block = Client::builder()
+        .with_node(node.url())?
+        .finish()?
+        .block()
+        .with_tag(b"pruned".to_vec())
+        .finish()
+        .await?;
+    Client::builder()
+        .with_node(permanode.url())?
+        .finish()?
+        .post_block_raw(&block)
+        .await?;
+    node.prune(1);
+
+    // The info of the node can't be synced, so it's only requested once the node doesn't find the data.
+    node.fail_requests(500, 1);
+    let client = Client::builder()
+        .with_node(node.url())?
+        .with_permanode(permanode.url(), None)?
+        .with_ignore_node_health()
+        .finish()?;
+
+    assert_eq!(client.get_block_raw(&block.id()).await?, block.pack_to_vec());
+    assert_eq!(client.get_utxo_changes_by_index(1).await?.index, 1);
+
+    // Milestones that weren't pruned aren't requested from the permanode.
+    let request_count = permanode.request_count();
+    assert!(matches!(
+        client.get_utxo_changes_by_index(2).await,
+        Err(Error::NotFound(_))
+    ));
+    assert_eq!(permanode.request_count(), request_count);
+
+    Ok(())
+}
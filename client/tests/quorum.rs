@@ -0,0 +1,117 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use iota_client::{
+    block::{
+        address::{Address, Ed25519Address},
+        output::{unlock_condition::AddressUnlockCondition, BasicOutputBuilder, Output, OutputId, UnlockCondition},
+    },
+    node_manager::quorum::IgnoreFields,
+    testing::MockNode,
+    Client, ClientBuilder, Error, Result, Url,
+};
+
+fn output(token_supply: u64) -> Result<Output> {
+    Ok(BasicOutputBuilder::new_with_amount(1_000_000)?
+        .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(Address::Ed25519(
+            Ed25519Address::new([1; 32]),
+        ))))
+        .finish_output(token_supply)?)
+}
+
+// Starts three nodes holding the same output, the last one booked it at a later milestone.
+fn start_nodes() -> Result<(Vec<MockNode>, OutputId)> {
+    let nodes = vec![MockNode::start()?, MockNode::start()?, MockNode::start()?];
+    let token_supply = nodes[0].protocol_parameters().token_supply();
+    let mut output_ids = Vec::new();
+
+    nodes[2].issue_milestone()?;
+    for node in &nodes {
+        output_ids.push(node.add_output(output(token_supply)?)?);
+    }
+    assert!(output_ids.iter().all(|output_id| *output_id == output_ids[0]));
+    // The ledger index of the second node differs from the first one.
+    nodes[1].issue_milestone()?;
+
+    Ok((nodes, output_ids[0]))
+}
+
+fn quorum_client(nodes: &[MockNode], quorum_threshold: usize) -> Result<ClientBuilder> {
+    Ok(Client::builder()
+        .with_nodes(&nodes.iter().map(MockNode::url).collect::<Vec<_>>())?
+        .with_quorum(true)
+        .with_min_quorum_size(3)
+        .with_quorum_threshold(quorum_threshold))
+}
+
+// Output metadata is only requested with a quorum once it has a comparator.
+fn output_metadata_quorum_client(nodes: &[MockNode], quorum_threshold: usize) -> Result<ClientBuilder> {
+    Ok(quorum_client(nodes, quorum_threshold)?.with_quorum_comparator(
+        "api/core/v2/outputs/{outputId}/metadata",
+        IgnoreFields(vec!["/ledgerIndex".to_string()]),
+    ))
+}
+
+#[tokio::test]
+async fn quorum_reports_differing_nodes() -> Result<()> {
+    let (nodes, output_id) = start_nodes()?;
+
+    // Without a comparator for output metadata, no quorum is needed.
+    let client = quorum_client(&nodes, 100)?.finish()?;
+    client.get_output_metadata(&output_id).await?;
+    assert!(client.last_quorum_report()?.is_none());
+
+    let client = output_metadata_quorum_client(&nodes, 66)?.finish()?;
+
+    // The ledger indexes aren't compared, so the first two nodes agree.
+    let metadata = client.get_output_metadata(&output_id).await?;
+    assert_eq!(metadata.milestone_index_booked, 0);
+
+    let report = client.last_quorum_report()?.expect("no quorum report");
+    assert_eq!(report.path, format!("api/core/v2/outputs/{output_id}/metadata"));
+    let mut agreeing = report.agreeing.clone();
+    agreeing.sort();
+    let mut expected = vec![Url::parse(nodes[0].url())?, Url::parse(nodes[1].url())?];
+    expected.sort();
+    assert_eq!(agreeing, expected);
+    assert_eq!(report.differing.len(), 1);
+    assert_eq!(report.differing[0].url, Url::parse(nodes[2].url())?);
+    assert!(report.differing[0].paths.contains(&"/milestoneIndexBooked".to_string()));
+    assert!(!report.differing[0].paths.contains(&"/ledgerIndex".to_string()));
+
+    // All nodes have to agree.
+    let client = output_metadata_quorum_client(&nodes, 100)?.finish()?;
+
+    match client.get_output_metadata(&output_id).await {
+        Err(Error::QuorumThresholdError {
+            quorum_size, report, ..
+        }) => {
+            assert_eq!(quorum_size, 2);
+            assert_eq!(report.agreeing.len(), 2);
+            assert_eq!(report.differing[0].url, Url::parse(nodes[2].url())?);
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn quorum_comparator_per_endpoint() -> Result<()> {
+    let (nodes, output_id) = start_nodes()?;
+    // Only compares whether the output is spent.
+    let client = quorum_client(&nodes, 100)?
+        .with_quorum_comparator(
+            "api/core/v2/outputs/{outputId}/metadata",
+            |response: &serde_json::Value| response["isSpent"].clone(),
+        )
+        .finish()?;
+
+    client.get_output_metadata(&output_id).await?;
+
+    let report = client.last_quorum_report()?.expect("no quorum report");
+    assert_eq!(report.agreeing.len(), 3);
+    assert!(report.differing.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::{Duration, Instant};
+
+use iota_client::{
+    block::{
+        address::{Address, Ed25519Address},
+        output::{unlock_condition::AddressUnlockCondition, BasicOutputBuilder, UnlockCondition},
+    },
+    node_manager::{rate_limit::RateLimit, retry::RetryPolicy},
+    testing::MockNode,
+    Client, Result,
+};
+
+#[tokio::test]
+async fn honours_retry_after() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder()
+        .with_node(mock.url())?
+        .with_retry_policy(RetryPolicy {
+            max_attempts: 2,
+            initial_backoff: Duration::from_millis(10),
+            // The period requested by the node is longer than the backoffs.
+            max_backoff: Duration::from_millis(100),
+            ..Default::default()
+        })
+        .finish()?;
+
+    mock.fail_requests_with_retry_after(429, 1, Duration::from_secs(1));
+    let request_count = mock.request_count();
+    let start = Instant::now();
+    client.get_tips().await?;
+    assert!(start.elapsed() >= Duration::from_secs(1));
+    assert_eq!(mock.request_count() - request_count, 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bulk_requests_within_limits() -> Result<()> {
+    let mock = MockNode::start()?;
+    let token_supply = mock.protocol_parameters().token_supply();
+    let mut output_ids = Vec::new();
+
+    for amount in 1..=10 {
+        output_ids.push(
+            mock.add_output(
+                BasicOutputBuilder::new_with_amount(amount * 1_000_000)?
+                    .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(Address::Ed25519(
+                        Ed25519Address::new([1; 32]),
+                    ))))
+                    .finish_output(token_supply)?,
+            )?,
+        );
+    }
+
+    let client = Client::builder()
+        .with_node(mock.url())?
+        .with_max_concurrent_requests(2)
+        .with_rate_limit(RateLimit {
+            max_requests: 5,
+            interval: Duration::from_millis(500),
+        })
+        .finish()?;
+
+    // Five requests are sent right away, the five others as the bucket refills.
+    let start = Instant::now();
+    let outputs = client.get_outputs(output_ids.clone()).await?;
+    assert!(start.elapsed() >= Duration::from_millis(400));
+    assert_eq!(
+        outputs
+            .iter()
+            .map(|output| output.metadata.output_id())
+            .collect::<std::result::Result<Vec<_>, _>>()?,
+        output_ids
+    );
+
+    let outputs = client.try_get_outputs(output_ids.clone()).await?;
+    assert_eq!(outputs.len(), 10);
+    assert_eq!(client.find_outputs(&output_ids, &[]).await?.len(), 10);
+
+    let tips = client.get_tips().await?;
+    assert_eq!(client.find_blocks(&tips).await?.len(), tips.len());
+
+    Ok(())
+}
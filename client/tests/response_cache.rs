@@ -0,0 +1,128 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use iota_client::{
+    block::{
+        output::{unlock_condition::AddressUnlockCondition, BasicOutputBuilder, UnlockCondition},
+        payload::Payload,
+    },
+    db::{DatabaseProvider, MemoryDatabaseProvider},
+    secret::{mnemonic::MnemonicSecretManager, SecretManager},
+    testing::MockNode,
+    Client, Error, Result,
+};
+
+// THIS SEED SERVES FOR TESTING PURPOSES! DON'T USE THIS SEED IN PRODUCTION!
+const DEFAULT_DEVELOPMENT_SEED: &str = "0x256a818b2aac458941f7274985a410e57fb750f3a3a67969ece5bd9ae7eef5b2";
+
+// A database that can't be read nor written.
+struct FailingDatabaseProvider;
+
+#[async_trait]
+impl DatabaseProvider for FailingDatabaseProvider {
+    async fn get(&mut self, _k: &[u8]) -> Result<Option<Vec<u8>>> {
+        Err(Error::CorruptedDatabase("unreadable".to_string()))
+    }
+
+    async fn insert(&mut self, _k: &[u8], _v: &[u8]) -> Result<Option<Vec<u8>>> {
+        Err(Error::CorruptedDatabase("unwritable".to_string()))
+    }
+
+    async fn delete(&mut self, _k: &[u8]) -> Result<Option<Vec<u8>>> {
+        Err(Error::CorruptedDatabase("unwritable".to_string()))
+    }
+}
+
+#[tokio::test]
+async fn response_cache_serves_immutable_data() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder()
+        .with_node(mock.url())?
+        .with_response_cache(MemoryDatabaseProvider::new())
+        .finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let bech32_hrp = client.get_bech32_hrp().await?;
+    let addresses = client.get_addresses(&secret_manager).with_range(0..2).get_raw().await?;
+
+    let genesis_output_id = mock.add_output(
+        BasicOutputBuilder::new_with_amount(10_000_000)?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(addresses[0])))
+            .finish_output(client.get_token_supply().await?)?,
+    )?;
+    let block = client
+        .block()
+        .with_secret_manager(&secret_manager)
+        .with_output(&addresses[1].to_bech32(&bech32_hrp), 1_000_000)
+        .await?
+        .finish()
+        .await?;
+    let transaction_id = match block.payload() {
+        Some(Payload::Transaction(transaction)) => transaction.id(),
+        _ => panic!("expected a transaction payload"),
+    };
+    let created_output_id = client.get_utxo_changes_by_index(1).await?.created_outputs[0].parse()?;
+
+    // Fill the cache.
+    client.get_block(&block.id()).await?;
+    client.get_included_block(&transaction_id).await?;
+    let milestone = client.get_milestone_by_index(1).await?;
+    let spent_output = client.get_output(&genesis_output_id).await?;
+    let unspent_output = client.get_output(&created_output_id).await?;
+    assert!(spent_output.metadata.is_spent);
+    assert!(!unspent_output.metadata.is_spent);
+
+    // Without the node, only the data that can't change anymore is still available.
+    drop(mock);
+
+    assert_eq!(client.get_block(&block.id()).await?, block);
+    assert_eq!(client.get_included_block(&transaction_id).await?, block);
+    assert_eq!(client.get_milestone_by_index(1).await?, milestone);
+    assert_eq!(client.get_milestone_by_id(&milestone.id()).await?, milestone);
+    assert_eq!(client.get_output(&genesis_output_id).await?, spent_output);
+    // The metadata of unspent outputs must be revalidated.
+    assert!(client.get_output(&created_output_id).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn response_cache_errors_fall_back_to_the_node() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder()
+        .with_node(mock.url())?
+        .with_response_cache(FailingDatabaseProvider)
+        .finish()?;
+    let secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let bech32_hrp = client.get_bech32_hrp().await?;
+    let addresses = client.get_addresses(&secret_manager).with_range(0..2).get_raw().await?;
+
+    let genesis_output_id = mock.add_output(
+        BasicOutputBuilder::new_with_amount(10_000_000)?
+            .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(addresses[0])))
+            .finish_output(client.get_token_supply().await?)?,
+    )?;
+    let block = client
+        .block()
+        .with_secret_manager(&secret_manager)
+        .with_output(&addresses[1].to_bech32(&bech32_hrp), 1_000_000)
+        .await?
+        .finish()
+        .await?;
+    let transaction_id = match block.payload() {
+        Some(Payload::Transaction(transaction)) => transaction.id(),
+        _ => panic!("expected a transaction payload"),
+    };
+
+    // Neither reading nor filling the cache fails the requests, twice to also read after the failed insertions.
+    for _ in 0..2 {
+        assert_eq!(client.get_block(&block.id()).await?, block);
+        assert_eq!(client.get_included_block(&transaction_id).await?, block);
+        let milestone = client.get_milestone_by_index(1).await?;
+        assert_eq!(client.get_milestone_by_id(&milestone.id()).await?, milestone);
+        assert!(client.get_output(&genesis_output_id).await?.metadata.is_spent);
+        assert!(client.get_output_metadata(&genesis_output_id).await?.is_spent);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{net::TcpListener, time::Duration};
+
+use iota_client::{
+    node_manager::{
+        retry::{CircuitBreaker, RetryPolicy},
+        scoreboard::NodeSelectionPolicy,
+    },
+    testing::MockNode,
+    Client, Error, Result, Url,
+};
+
+fn retry_policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        initial_backoff: Duration::from_millis(10),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn retries_failed_requests() -> Result<()> {
+    let mock = MockNode::start()?;
+    let client = Client::builder()
+        .with_node(mock.url())?
+        .with_retry_policy(retry_policy(3))
+        .finish()?;
+
+    mock.fail_requests(503, 2);
+    let request_count = mock.request_count();
+    client.get_tips().await?;
+    assert_eq!(mock.request_count() - request_count, 3);
+
+    // Status codes that aren't listed aren't retried.
+    mock.fail_requests(500, 1);
+    let request_count = mock.request_count();
+    assert!(matches!(
+        client.get_tips().await,
+        Err(Error::ResponseError { code: 500, .. })
+    ));
+    assert_eq!(mock.request_count() - request_count, 1);
+
+    // Requests aren't retried by default.
+    let client = Client::builder().with_node(mock.url())?.finish()?;
+
+    mock.fail_requests(503, 1);
+    let request_count = mock.request_count();
+    assert!(matches!(
+        client.get_tips().await,
+        Err(Error::ResponseError { code: 503, .. })
+    ));
+    assert_eq!(mock.request_count() - request_count, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn posts_are_only_resent_when_unprocessed() -> Result<()> {
+    let primary = MockNode::start()?;
+    let other = MockNode::start()?;
+    let client = Client::builder()
+        .with_primary_node(primary.url(), None)?
+        .with_node(other.url())?
+        .with_local_pow(true)
+        .with_retry_policy(retry_policy(3))
+        .finish()?;
+
+    // The primary node may have processed the block, so it's neither retried nor sent to the other node.
+    let block = client.finish_block_builder(None, None).await?;
+    primary.fail_requests(500, 1);
+    let primary_request_count = primary.request_count();
+    let other_request_count = other.request_count();
+    assert!(matches!(client.post_block(&block).await, Err(Error::NodeError(_))));
+    assert_eq!(primary.request_count() - primary_request_count, 1);
+    assert_eq!(other.request_count(), other_request_count);
+
+    // An unavailable node didn't process the block, so it's retried and then sent to the other node.
+    let block = client.finish_block_builder(None, None).await?;
+    primary.fail_requests(503, 3);
+    let primary_request_count = primary.request_count();
+    let other_request_count = other.request_count();
+    assert_eq!(client.post_block(&block).await?, block.id());
+    assert_eq!(primary.request_count() - primary_request_count, 3);
+    assert_eq!(other.request_count() - other_request_count, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn circuit_breaker_skips_failing_nodes() -> Result<()> {
+    let mock = MockNode::start()?;
+    // Nothing listens on the port of a dropped listener.
+    let unreachable = format!(
+        "http://{}",
+        TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap()
+    );
+
+    let client = Client::builder()
+        .with_nodes(&[mock.url(), &unreachable])?
+        .with_ignore_node_health()
+        .with_node_selection_policy(NodeSelectionPolicy::RoundRobin)
+        .with_circuit_breaker(CircuitBreaker {
+            failure_threshold: 1,
+            cool_down: Duration::from_secs(60),
+        })
+        .finish()?;
+
+    for _ in 0..4 {
+        client.get_tips().await?;
+    }
+
+    let unreachable = Url::parse(&unreachable)?;
+    let score = client
+        .get_node_scores()?
+        .into_iter()
+        .find(|score| score.url == unreachable)
+        .expect("node without score");
+    assert_eq!(score.requests, 1);
+    assert_eq!(score.consecutive_errors, 1);
+
+    Ok(())
+}
@@ -1,7 +1,39 @@
 // Copyright 2022 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use iota_client::{api::GetAddressesBuilder, constants::SHIMMER_TESTNET_BECH32_HRP, secret::SecretManager, Result};
+use iota_client::{
+    api::{
+        verify_semantic, GetAddressesBuilder, PartiallySignedTransaction, PartiallySignedTransactionDto,
+        PreparedTransactionData,
+    },
+    block::{
+        address::Address,
+        input::{Input, UtxoInput},
+        output::{
+            unlock_condition::{AddressUnlockCondition, UnlockCondition},
+            BasicOutputBuilder, InputsCommitment, Output, OutputId,
+        },
+        payload::{
+            transaction::{RegularTransactionEssence, TransactionEssence},
+            TransactionPayload,
+        },
+        protocol::protocol_parameters,
+        rand::{block::rand_block_id, transaction::rand_transaction_id},
+        semantic::ConflictReason,
+        unlock::{ReferenceUnlock, Unlock},
+    },
+    constants::{SHIMMER_COIN_TYPE, SHIMMER_TESTNET_BECH32_HRP},
+    crypto::keys::slip10::Chain,
+    secret::{
+        mnemonic::MnemonicSecretManager,
+        types::{InputSigningData, OutputMetadata},
+        SecretManageExt, SecretManager,
+    },
+    Error, Result,
+};
+
+const DEFAULT_DEVELOPMENT_SEED: &str = "0x256a818b2aac458941f7274985a410e57fb750f3a3a67969ece5bd9ae7eef5b2";
+const MNEMONIC: &str = "acoustic trophy damage hint search taste love bicycle foster cradle brown govern endless depend situate athlete pudding blame question genius transfer van random vast";
 
 #[tokio::test]
 async fn mnemonic_secret_manager_dto() -> Result<()> {
@@ -93,3 +125,139 @@ async fn stronghold_mnemonic_missing() -> Result<()> {
     std::fs::remove_dir_all("stronghold_mnemonic_missing").unwrap_or(());
     Ok(())
 }
+
+async fn first_address(secret_manager: &SecretManager) -> Result<String> {
+    Ok(GetAddressesBuilder::new(secret_manager)
+        .with_bech32_hrp(SHIMMER_TESTNET_BECH32_HRP)
+        .with_coin_type(SHIMMER_COIN_TYPE)
+        .with_range(0..1)
+        .finish()
+        .await?
+        .remove(0))
+}
+
+fn basic_output(amount: u64, bech32_address: &str) -> Output {
+    BasicOutputBuilder::new_with_amount(amount)
+        .unwrap()
+        .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(
+            Address::try_from_bech32(bech32_address).unwrap().1,
+        )))
+        .finish_output(protocol_parameters().token_supply())
+        .unwrap()
+}
+
+fn input_signing_data(amount: u64, bech32_address: &str) -> InputSigningData {
+    InputSigningData {
+        output: basic_output(amount, bech32_address),
+        output_metadata: OutputMetadata::new(
+            rand_block_id(),
+            OutputId::new(rand_transaction_id(), 0).unwrap(),
+            false,
+            None,
+            None,
+            None,
+            0,
+            0,
+            0,
+        ),
+        chain: Some(Chain::from_u32_hardened(vec![44, SHIMMER_COIN_TYPE, 0, 0, 0])),
+        bech32_address: bech32_address.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn partially_signed_transaction() -> Result<()> {
+    let protocol_parameters = protocol_parameters();
+    let first_secret_manager =
+        SecretManager::Mnemonic(MnemonicSecretManager::try_from_hex_seed(DEFAULT_DEVELOPMENT_SEED)?);
+    let second_secret_manager = SecretManager::Mnemonic(MnemonicSecretManager::try_from_mnemonic(MNEMONIC)?);
+    let first_bech32_address = first_address(&first_secret_manager).await?;
+    let second_bech32_address = first_address(&second_secret_manager).await?;
+    let (_, first_address) = Address::try_from_bech32(&first_bech32_address)?;
+    let (_, second_address) = Address::try_from_bech32(&second_bech32_address)?;
+
+    let inputs_data = vec![
+        input_signing_data(1_000_000, &first_bech32_address),
+        input_signing_data(2_000_000, &first_bech32_address),
+        input_signing_data(3_000_000, &second_bech32_address),
+    ];
+    let essence = RegularTransactionEssence::builder(
+        protocol_parameters.network_id(),
+        InputsCommitment::new(inputs_data.iter().map(|input| &input.output)),
+    )
+    .with_inputs(
+        inputs_data
+            .iter()
+            .map(|input| Input::Utxo(UtxoInput::from(*input.output_id())))
+            .collect(),
+    )
+    .with_outputs(vec![basic_output(6_000_000, &second_bech32_address)])
+    .finish(&protocol_parameters)?;
+    let prepared_transaction_data = PreparedTransactionData {
+        essence: TransactionEssence::Regular(essence),
+        inputs_data,
+        remainder: None,
+    };
+
+    let mut partially_signed_transaction = first_secret_manager
+        .sign_transaction_essence_partially(&prepared_transaction_data, &[first_address])
+        .await?;
+
+    assert!(matches!(
+        partially_signed_transaction.signature_unlocks.as_slice(),
+        [Some(Unlock::Signature(_)), None, None]
+    ));
+    assert_eq!(partially_signed_transaction.missing_signatures()?, vec![2]);
+    assert!(matches!(
+        partially_signed_transaction.unlocks(),
+        Err(Error::MissingSignatureUnlock(2))
+    ));
+
+    // The second party receives the transaction serialized, and only signs the input it controls.
+    let dto = serde_json::to_string(&PartiallySignedTransactionDto::from(&PartiallySignedTransaction::new(
+        prepared_transaction_data.clone(),
+    )))?;
+    let received = PartiallySignedTransaction::try_from_dto(&serde_json::from_str(&dto)?, &protocol_parameters)?;
+    let second_partially_signed_transaction = second_secret_manager
+        .sign_transaction_essence_partially(&received.prepared_transaction_data, &[second_address, first_address])
+        .await?;
+
+    assert_eq!(second_partially_signed_transaction.signature_unlocks[1], None);
+    // The signature of the first input with the key of the second party conflicts with the one of the first party.
+    assert!(matches!(
+        partially_signed_transaction
+            .clone()
+            .merge(&second_partially_signed_transaction),
+        Err(Error::ConflictingSignatureUnlocks(0))
+    ));
+
+    let second_partially_signed_transaction = second_secret_manager
+        .sign_transaction_essence_partially(&received.prepared_transaction_data, &[second_address])
+        .await?;
+    let dto = serde_json::to_string(&PartiallySignedTransactionDto::from(
+        &second_partially_signed_transaction,
+    ))?;
+    partially_signed_transaction.merge(&PartiallySignedTransaction::try_from_dto(
+        &serde_json::from_str(&dto)?,
+        &protocol_parameters,
+    )?)?;
+
+    assert!(partially_signed_transaction.missing_signatures()?.is_empty());
+
+    let unlocks = partially_signed_transaction.unlocks()?;
+
+    assert_eq!(unlocks[1], Unlock::Reference(ReferenceUnlock::new(0)?));
+    assert_eq!(
+        Some(&unlocks[2]),
+        second_partially_signed_transaction.signature_unlocks[2].as_ref()
+    );
+
+    let transaction = TransactionPayload::new(prepared_transaction_data.essence.clone(), unlocks)?;
+
+    assert_eq!(
+        verify_semantic(&prepared_transaction_data.inputs_data, &transaction, 0)?,
+        ConflictReason::None
+    );
+
+    Ok(())
+}
@@ -131,8 +131,17 @@ impl Address {
 
                 let Signature::Ed25519(signature) = unlock.signature();
 
-                if signature.is_valid(&context.essence_hash, ed25519_address).is_err() {
-                    return Err(ConflictReason::InvalidSignature);
+                match &context.assumed_signers {
+                    Some(signers) => {
+                        if !signers.contains(self) {
+                            return Err(ConflictReason::InvalidSignature);
+                        }
+                    }
+                    None => {
+                        if signature.is_valid(&context.essence_hash, ed25519_address).is_err() {
+                            return Err(ConflictReason::InvalidSignature);
+                        }
+                    }
                 }
 
                 context.unlocked_addresses.insert(*self);
@@ -0,0 +1,261 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Module describing a local ledger state to dry-run transactions against.
+
+use alloc::vec::Vec;
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::block::{
+    address::Address,
+    input::Input,
+    output::{Output, OutputId},
+    payload::transaction::{RegularTransactionEssence, TransactionEssence, TransactionId, TransactionPayload},
+    semantic::{semantic_validation, ConflictReason, ValidationContext},
+    signature::{Ed25519Signature, Signature},
+    unlock::{AliasUnlock, NftUnlock, ReferenceUnlock, SignatureUnlock, Unlock, Unlocks},
+    Error,
+};
+
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub enum LedgerStateError {
+    InputNotUnspent(OutputId),
+    Conflict(ConflictReason),
+    Invalid(Error),
+}
+
+impl From<Error> for LedgerStateError {
+    fn from(error: Error) -> Self {
+        LedgerStateError::Invalid(error)
+    }
+}
+
+/// A set of unspent outputs and a milestone timestamp that transactions are applied to, one after the other.
+///
+/// Applying a transaction doesn't modify the state but returns a new one, so that a sequence of transactions, each one
+/// possibly consuming outputs created by the previous ones, can be checked offline before being sent.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LedgerState {
+    unspent_outputs: HashMap<OutputId, Output>,
+    milestone_timestamp: u32,
+}
+
+impl LedgerState {
+    /// Creates a new [`LedgerState`] from a set of unspent outputs and the timestamp of the milestone the transactions
+    /// are validated against.
+    pub fn new(unspent_outputs: impl IntoIterator<Item = (OutputId, Output)>, milestone_timestamp: u32) -> Self {
+        Self {
+            unspent_outputs: unspent_outputs.into_iter().collect(),
+            milestone_timestamp,
+        }
+    }
+
+    /// Sets the timestamp of the milestone the transactions are validated against, e.g. to check time-locks.
+    pub fn with_milestone_timestamp(mut self, milestone_timestamp: u32) -> Self {
+        self.milestone_timestamp = milestone_timestamp;
+        self
+    }
+
+    /// Returns the timestamp of the milestone the transactions are validated against.
+    pub fn milestone_timestamp(&self) -> u32 {
+        self.milestone_timestamp
+    }
+
+    /// Returns the unspent output with the given ID, if any.
+    pub fn output(&self, output_id: &OutputId) -> Option<&Output> {
+        self.unspent_outputs.get(output_id)
+    }
+
+    /// Checks whether the output with the given ID is unspent.
+    pub fn is_unspent(&self, output_id: &OutputId) -> bool {
+        self.unspent_outputs.contains_key(output_id)
+    }
+
+    /// Returns an iterator over the unspent outputs.
+    pub fn unspent_outputs(&self) -> impl Iterator<Item = (&OutputId, &Output)> {
+        self.unspent_outputs.iter()
+    }
+
+    /// Returns the number of unspent outputs.
+    pub fn len(&self) -> usize {
+        self.unspent_outputs.len()
+    }
+
+    /// Checks whether there are no unspent outputs.
+    pub fn is_empty(&self) -> bool {
+        self.unspent_outputs.is_empty()
+    }
+
+    /// Semantically validates a transaction against the unspent outputs and, if it is valid, returns the state with
+    /// its inputs spent and its outputs created, along with the IDs of the created outputs.
+    pub fn apply_transaction(
+        &self,
+        transaction: &TransactionPayload,
+    ) -> Result<(Self, Vec<OutputId>), LedgerStateError> {
+        let TransactionEssence::Regular(essence) = transaction.essence();
+
+        self.apply(essence, &transaction.id(), transaction.unlocks(), None)
+    }
+
+    /// Semantically validates a transaction essence before it is signed, assuming that the inputs requiring a
+    /// signature from one of `signers` are signed by it, and returns the state with its inputs spent and its outputs
+    /// created, along with the IDs of the created outputs.
+    ///
+    /// The transaction ID is not known before signing, so the IDs of the created outputs use the hash of the essence
+    /// as placeholder transaction ID, like the steps of a transaction chain do.
+    pub fn apply_unsigned_transaction(
+        &self,
+        essence: &TransactionEssence,
+        signers: impl IntoIterator<Item = Address>,
+    ) -> Result<(Self, Vec<OutputId>), LedgerStateError> {
+        let TransactionEssence::Regular(regular_essence) = essence;
+        let unlocks = self.assumed_unlocks(regular_essence)?;
+
+        self.apply(
+            regular_essence,
+            &TransactionId::new(essence.hash()),
+            &unlocks,
+            Some(signers.into_iter().collect()),
+        )
+    }
+
+    fn apply(
+        &self,
+        essence: &RegularTransactionEssence,
+        transaction_id: &TransactionId,
+        unlocks: &Unlocks,
+        assumed_signers: Option<HashSet<Address>>,
+    ) -> Result<(Self, Vec<OutputId>), LedgerStateError> {
+        let inputs = self.inputs(essence)?;
+
+        let mut context = ValidationContext::new(
+            transaction_id,
+            essence,
+            inputs.iter().copied(),
+            unlocks,
+            self.milestone_timestamp,
+        );
+        context.assumed_signers = assumed_signers;
+        let conflict = semantic_validation(
+            context,
+            &inputs
+                .iter()
+                .map(|(output_id, output)| (**output_id, *output))
+                .collect::<Vec<_>>(),
+            unlocks,
+        )?;
+
+        if conflict != ConflictReason::None {
+            return Err(LedgerStateError::Conflict(conflict));
+        }
+
+        let mut unspent_outputs = self.unspent_outputs.clone();
+
+        for (output_id, _) in &inputs {
+            unspent_outputs.remove(*output_id);
+        }
+
+        let mut created = Vec::with_capacity(essence.outputs().len());
+
+        for (index, output) in essence.outputs().iter().enumerate() {
+            let output_id = OutputId::new(*transaction_id, index as u16)?;
+
+            unspent_outputs.insert(output_id, output.clone());
+            created.push(output_id);
+        }
+
+        Ok((
+            Self {
+                unspent_outputs,
+                milestone_timestamp: self.milestone_timestamp,
+            },
+            created,
+        ))
+    }
+
+    fn inputs(&self, essence: &RegularTransactionEssence) -> Result<Vec<(&OutputId, &Output)>, LedgerStateError> {
+        essence
+            .inputs()
+            .iter()
+            .map(|input| match input {
+                Input::Utxo(input) => self
+                    .unspent_outputs
+                    .get_key_value(input.output_id())
+                    .ok_or(LedgerStateError::InputNotUnspent(*input.output_id())),
+                _ => Err(Error::InvalidInputKind(input.kind()).into()),
+            })
+            .collect()
+    }
+
+    // Builds the unlocks a signed transaction would have, with empty signatures: an input is unlocked by referencing
+    // the earlier input unlocking the same address, or by a signature otherwise.
+    fn assumed_unlocks(&self, essence: &RegularTransactionEssence) -> Result<Unlocks, LedgerStateError> {
+        let mut unlocks = Vec::new();
+        let mut unlock_indexes = HashMap::<Address, u16>::new();
+
+        for (index, (output_id, output)) in self.inputs(essence)?.into_iter().enumerate() {
+            let (required_address, unlocked_address) = output.required_and_unlocked_address(
+                self.milestone_timestamp,
+                output_id,
+                is_alias_state_transition(output_id, output, essence),
+            )?;
+
+            unlocks.push(match (unlock_indexes.get(&required_address), required_address) {
+                (Some(index), Address::Ed25519(_)) => Unlock::Reference(ReferenceUnlock::new(*index)?),
+                (Some(index), Address::Alias(_)) => Unlock::Alias(AliasUnlock::new(*index)?),
+                (Some(index), Address::Nft(_)) => Unlock::Nft(NftUnlock::new(*index)?),
+                (None, Address::Ed25519(_)) => {
+                    unlock_indexes.insert(required_address, index as u16);
+                    Unlock::Signature(SignatureUnlock::new(Signature::Ed25519(Ed25519Signature::new(
+                        [0; Ed25519Signature::PUBLIC_KEY_LENGTH],
+                        [0; Ed25519Signature::SIGNATURE_LENGTH],
+                    ))))
+                }
+                // An alias or NFT address can only be unlocked by an earlier input.
+                (None, _) => return Err(LedgerStateError::Conflict(ConflictReason::InvalidUnlock)),
+            });
+
+            if let Some(unlocked_address) = unlocked_address {
+                unlock_indexes.insert(unlocked_address, index as u16);
+            }
+        }
+
+        Ok(Unlocks::new(unlocks)?)
+    }
+
+    /// Applies transactions one after the other, each one being validated against the state left by the previous ones,
+    /// and returns the final state along with the IDs of the outputs created by every transaction.
+    pub fn apply_transactions<'a>(
+        &self,
+        transactions: impl IntoIterator<Item = &'a TransactionPayload>,
+    ) -> Result<(Self, Vec<Vec<OutputId>>), LedgerStateError> {
+        let mut state = self.clone();
+        let mut created = Vec::new();
+
+        for transaction in transactions {
+            let (next_state, output_ids) = state.apply_transaction(transaction)?;
+
+            state = next_state;
+            created.push(output_ids);
+        }
+
+        Ok((state, created))
+    }
+}
+
+// An alias output is unlocked by its state controller if its state index is incremented, by its governor otherwise.
+fn is_alias_state_transition(output_id: &OutputId, output: &Output, essence: &RegularTransactionEssence) -> bool {
+    match output {
+        Output::Alias(alias) => {
+            let alias_id = alias.alias_id_non_null(output_id);
+
+            essence.outputs().iter().any(|output| match output {
+                Output::Alias(next) => *next.alias_id() == alias_id && next.state_index() == alias.state_index() + 1,
+                _ => false,
+            })
+        }
+        _ => false,
+    }
+}
@@ -21,6 +21,8 @@ pub mod block;
 pub mod helper;
 /// A module that provides types and syntactic validations of inputs.
 pub mod input;
+/// A module that provides a local ledger state to dry-run transactions.
+pub mod ledger;
 /// A module that provides types and syntactic validations of outputs.
 pub mod output;
 /// A module that provides types and syntactic validations of parents.
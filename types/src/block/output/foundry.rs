@@ -197,6 +197,20 @@ impl FoundryOutputBuilder {
         Ok(self)
     }
 
+    /// Sets the immutable metadata feature to the IRC30 metadata of the native token.
+    #[cfg(feature = "irc")]
+    pub fn with_irc_30_metadata(
+        mut self,
+        metadata: &super::irc::Irc30Metadata,
+    ) -> Result<Self, super::irc::IrcMetadataError> {
+        let feature = Feature::Metadata(metadata.try_into()?);
+
+        self.immutable_features.retain(|f| f.kind() != feature.kind());
+        self.immutable_features.push(feature);
+
+        Ok(self)
+    }
+
     ///
     pub fn finish_unverified(self) -> Result<FoundryOutput, Error> {
         let unlock_conditions = UnlockConditions::new(self.unlock_conditions)?;
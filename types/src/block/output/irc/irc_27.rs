@@ -0,0 +1,251 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+
+use super::{from_json, non_empty, required, standard, to_json, IrcMetadataError};
+use crate::block::{address::Address, output::feature::MetadataFeature};
+
+/// An attribute of an NFT following the IRC27 standard.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Irc27Attribute {
+    /// The name of the trait.
+    pub trait_type: String,
+    /// The value of the trait.
+    pub value: serde_json::Value,
+}
+
+impl Irc27Attribute {
+    /// Creates a new [`Irc27Attribute`].
+    pub fn new(trait_type: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
+        Self {
+            trait_type: trait_type.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// The metadata of an NFT following the IRC27 standard, stored in its immutable metadata feature.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Irc27Metadata {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(rename = "type")]
+    media_type: String,
+    uri: String,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collection_name: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    royalties: BTreeMap<String, f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issuer_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    attributes: Vec<Irc27Attribute>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Irc27MetadataJson {
+    standard: Option<String>,
+    version: Option<String>,
+    #[serde(rename = "type")]
+    media_type: Option<String>,
+    uri: Option<String>,
+    name: Option<String>,
+    collection_name: Option<String>,
+    #[serde(default)]
+    royalties: BTreeMap<String, f64>,
+    issuer_name: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    attributes: Vec<Irc27Attribute>,
+}
+
+impl Irc27Metadata {
+    /// The value of the `standard` field.
+    pub const STANDARD: &'static str = "IRC27";
+    /// The supported value of the `version` field.
+    pub const VERSION: &'static str = "v1.0";
+
+    /// Creates a new [`Irc27Metadata`] with the required MIME type, URI and name.
+    pub fn new(
+        media_type: impl Into<String>,
+        uri: impl Into<String>,
+        name: impl Into<String>,
+    ) -> Result<Self, IrcMetadataError> {
+        let media_type = media_type.into();
+        let uri = uri.into();
+        let name = name.into();
+
+        non_empty(&media_type, "type")?;
+        non_empty(&uri, "uri")?;
+        non_empty(&name, "name")?;
+
+        Ok(Self {
+            standard: Self::STANDARD,
+            version: Self::VERSION,
+            media_type,
+            uri,
+            name,
+            collection_name: None,
+            royalties: BTreeMap::new(),
+            issuer_name: None,
+            description: None,
+            attributes: Vec::new(),
+        })
+    }
+
+    /// Sets the name of the collection of the NFT.
+    pub fn with_collection_name(mut self, collection_name: impl Into<String>) -> Self {
+        self.collection_name.replace(collection_name.into());
+        self
+    }
+
+    /// Sets the royalties, as shares between 0 and 1 of the sale price per bech32 address, summing up to at most 1.
+    pub fn with_royalties(mut self, royalties: BTreeMap<String, f64>) -> Result<Self, IrcMetadataError> {
+        verify_royalties(&royalties)?;
+        self.royalties = royalties;
+        Ok(self)
+    }
+
+    /// Sets the name of the issuer of the NFT.
+    pub fn with_issuer_name(mut self, issuer_name: impl Into<String>) -> Self {
+        self.issuer_name.replace(issuer_name.into());
+        self
+    }
+
+    /// Sets the description of the NFT.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description.replace(description.into());
+        self
+    }
+
+    /// Adds attributes to the NFT.
+    pub fn with_attributes(mut self, attributes: impl IntoIterator<Item = Irc27Attribute>) -> Self {
+        self.attributes.extend(attributes);
+        self
+    }
+
+    /// Returns the MIME type of the NFT file.
+    pub fn media_type(&self) -> &str {
+        &self.media_type
+    }
+
+    /// Returns the URI of the NFT file.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Returns the name of the NFT.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the name of the collection of the NFT.
+    pub fn collection_name(&self) -> Option<&str> {
+        self.collection_name.as_deref()
+    }
+
+    /// Returns the royalties of the NFT.
+    pub fn royalties(&self) -> &BTreeMap<String, f64> {
+        &self.royalties
+    }
+
+    /// Returns the name of the issuer of the NFT.
+    pub fn issuer_name(&self) -> Option<&str> {
+        self.issuer_name.as_deref()
+    }
+
+    /// Returns the description of the NFT.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the attributes of the NFT.
+    pub fn attributes(&self) -> &[Irc27Attribute] {
+        &self.attributes
+    }
+
+    /// Parses and validates IRC27 JSON metadata.
+    pub fn from_json(json: &[u8]) -> Result<Self, IrcMetadataError> {
+        let json = from_json::<Irc27MetadataJson>(json)?;
+
+        standard(required(json.standard, "standard")?, Self::STANDARD)?;
+
+        let version = required(json.version, "version")?;
+
+        if version != Self::VERSION {
+            return Err(IrcMetadataError::InvalidVersion {
+                expected: Self::VERSION,
+                actual: version,
+            });
+        }
+
+        let mut metadata = Self::new(
+            required(json.media_type, "type")?,
+            required(json.uri, "uri")?,
+            required(json.name, "name")?,
+        )?
+        .with_royalties(json.royalties)?
+        .with_attributes(json.attributes);
+
+        metadata.collection_name = json.collection_name;
+        metadata.issuer_name = json.issuer_name;
+        metadata.description = json.description;
+
+        Ok(metadata)
+    }
+
+    /// Serializes the metadata into JSON, checking that it fits in a [`MetadataFeature`].
+    pub fn to_json(&self) -> Result<String, IrcMetadataError> {
+        // PANIC: serde_json only produces valid UTF-8.
+        Ok(String::from_utf8(to_json(self)?).unwrap())
+    }
+}
+
+fn verify_royalties(royalties: &BTreeMap<String, f64>) -> Result<(), IrcMetadataError> {
+    let mut sum = 0.0;
+
+    for (address, share) in royalties {
+        Address::try_from_bech32(address).map_err(|_| IrcMetadataError::InvalidRoyaltyAddress(address.clone()))?;
+
+        if !(0.0..=1.0).contains(share) {
+            return Err(IrcMetadataError::InvalidRoyaltyShare {
+                address: address.clone(),
+                share: *share,
+            });
+        }
+
+        sum += share;
+    }
+
+    // Allows for the rounding errors of the sum, like the shares 0.34, 0.56 and 0.1 adding up to slightly more than 1.
+    if sum > 1.0 + f64::EPSILON * royalties.len() as f64 {
+        return Err(IrcMetadataError::RoyaltySharesOverflow(sum));
+    }
+
+    Ok(())
+}
+
+impl TryFrom<&MetadataFeature> for Irc27Metadata {
+    type Error = IrcMetadataError;
+
+    fn try_from(feature: &MetadataFeature) -> Result<Self, Self::Error> {
+        Self::from_json(feature.data())
+    }
+}
+
+impl TryFrom<&Irc27Metadata> for MetadataFeature {
+    type Error = IrcMetadataError;
+
+    fn try_from(metadata: &Irc27Metadata) -> Result<Self, Self::Error> {
+        // PANIC: the JSON is not empty and its length was checked.
+        Ok(MetadataFeature::new(to_json(metadata)?).unwrap())
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use alloc::string::String;
+
+use serde::{Deserialize, Serialize};
+
+use super::{from_json, non_empty, required, standard, to_json, IrcMetadataError};
+use crate::block::output::feature::MetadataFeature;
+
+/// The metadata of a native token following the IRC30 standard, stored in the metadata feature of its foundry.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Irc30Metadata {
+    standard: &'static str,
+    name: String,
+    symbol: String,
+    decimals: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logo_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logo: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Irc30MetadataJson {
+    standard: Option<String>,
+    name: Option<String>,
+    symbol: Option<String>,
+    decimals: Option<u32>,
+    description: Option<String>,
+    url: Option<String>,
+    logo_url: Option<String>,
+    logo: Option<String>,
+}
+
+impl Irc30Metadata {
+    /// The value of the `standard` field.
+    pub const STANDARD: &'static str = "IRC30";
+
+    /// Creates a new [`Irc30Metadata`] with the required name, symbol and decimals.
+    pub fn new(name: impl Into<String>, symbol: impl Into<String>, decimals: u32) -> Result<Self, IrcMetadataError> {
+        let name = name.into();
+        let symbol = symbol.into();
+
+        non_empty(&name, "name")?;
+        non_empty(&symbol, "symbol")?;
+
+        Ok(Self {
+            standard: Self::STANDARD,
+            name,
+            symbol,
+            decimals,
+            description: None,
+            url: None,
+            logo_url: None,
+            logo: None,
+        })
+    }
+
+    /// Sets the description of the token.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description.replace(description.into());
+        self
+    }
+
+    /// Sets the URL of the website of the token.
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url.replace(url.into());
+        self
+    }
+
+    /// Sets the URL of the logo of the token.
+    pub fn with_logo_url(mut self, logo_url: impl Into<String>) -> Self {
+        self.logo_url.replace(logo_url.into());
+        self
+    }
+
+    /// Sets the logo of the token, as an SVG document.
+    pub fn with_logo(mut self, logo: impl Into<String>) -> Self {
+        self.logo.replace(logo.into());
+        self
+    }
+
+    /// Returns the name of the token.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the symbol of the token.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Returns the number of decimals of the token amounts.
+    pub fn decimals(&self) -> u32 {
+        self.decimals
+    }
+
+    /// Returns the description of the token.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the URL of the website of the token.
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    /// Returns the URL of the logo of the token.
+    pub fn logo_url(&self) -> Option<&str> {
+        self.logo_url.as_deref()
+    }
+
+    /// Returns the logo of the token.
+    pub fn logo(&self) -> Option<&str> {
+        self.logo.as_deref()
+    }
+
+    /// Parses and validates IRC30 JSON metadata.
+    pub fn from_json(json: &[u8]) -> Result<Self, IrcMetadataError> {
+        let json = from_json::<Irc30MetadataJson>(json)?;
+
+        standard(required(json.standard, "standard")?, Self::STANDARD)?;
+
+        let mut metadata = Self::new(
+            required(json.name, "name")?,
+            required(json.symbol, "symbol")?,
+            required(json.decimals, "decimals")?,
+        )?;
+
+        metadata.description = json.description;
+        metadata.url = json.url;
+        metadata.logo_url = json.logo_url;
+        metadata.logo = json.logo;
+
+        Ok(metadata)
+    }
+
+    /// Serializes the metadata into JSON, checking that it fits in a [`MetadataFeature`].
+    pub fn to_json(&self) -> Result<String, IrcMetadataError> {
+        // PANIC: serde_json only produces valid UTF-8.
+        Ok(String::from_utf8(to_json(self)?).unwrap())
+    }
+}
+
+impl TryFrom<&MetadataFeature> for Irc30Metadata {
+    type Error = IrcMetadataError;
+
+    fn try_from(feature: &MetadataFeature) -> Result<Self, Self::Error> {
+        Self::from_json(feature.data())
+    }
+}
+
+impl TryFrom<&Irc30Metadata> for MetadataFeature {
+    type Error = IrcMetadataError;
+
+    fn try_from(metadata: &Irc30Metadata) -> Result<Self, Self::Error> {
+        // PANIC: the JSON is not empty and its length was checked.
+        Ok(MetadataFeature::new(to_json(metadata)?).unwrap())
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed metadata of the IRC27 (NFT) and IRC30 (native token) standards, stored as JSON in metadata features.
+
+mod irc_27;
+mod irc_30;
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+pub use self::{
+    irc_27::{Irc27Attribute, Irc27Metadata},
+    irc_30::Irc30Metadata,
+};
+use crate::block::output::feature::MetadataFeature;
+
+/// Error occurring when building, parsing or validating IRC metadata, naming the field that is wrong.
+#[derive(Debug, PartialEq)]
+#[allow(missing_docs)]
+pub enum IrcMetadataError {
+    InvalidJson(String),
+    InvalidStandard { expected: &'static str, actual: String },
+    InvalidVersion { expected: &'static str, actual: String },
+    MissingField(&'static str),
+    EmptyField(&'static str),
+    InvalidRoyaltyAddress(String),
+    InvalidRoyaltyShare { address: String, share: f64 },
+    RoyaltySharesOverflow(f64),
+    MetadataTooLarge { length: usize, max: usize },
+}
+
+impl IrcMetadataError {
+    /// Returns the name of the wrong field, if the error concerns a single one.
+    pub fn field(&self) -> Option<&'static str> {
+        match self {
+            Self::InvalidJson(_) | Self::MetadataTooLarge { .. } => None,
+            Self::InvalidStandard { .. } => Some("standard"),
+            Self::InvalidVersion { .. } => Some("version"),
+            Self::MissingField(field) | Self::EmptyField(field) => Some(field),
+            Self::InvalidRoyaltyAddress(_) | Self::InvalidRoyaltyShare { .. } | Self::RoyaltySharesOverflow(_) => {
+                Some("royalties")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IrcMetadataError {}
+
+impl fmt::Display for IrcMetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJson(error) => write!(f, "invalid JSON: {error}"),
+            Self::InvalidStandard { expected, actual } => {
+                write!(f, "invalid field `standard`: expected {expected}, found {actual}")
+            }
+            Self::InvalidVersion { expected, actual } => {
+                write!(f, "invalid field `version`: expected {expected}, found {actual}")
+            }
+            Self::MissingField(field) => write!(f, "missing field `{field}`"),
+            Self::EmptyField(field) => write!(f, "empty field `{field}`"),
+            Self::InvalidRoyaltyAddress(address) => {
+                write!(f, "invalid field `royalties`: {address} is not a bech32 address")
+            }
+            Self::InvalidRoyaltyShare { address, share } => {
+                write!(
+                    f,
+                    "invalid field `royalties`: share {share} of {address} is not within 0 and 1"
+                )
+            }
+            Self::RoyaltySharesOverflow(sum) => {
+                write!(f, "invalid field `royalties`: shares sum up to {sum}, more than 1")
+            }
+            Self::MetadataTooLarge { length, max } => {
+                write!(f, "metadata of {length} bytes exceeds the maximum of {max} bytes")
+            }
+        }
+    }
+}
+
+impl From<serde_json::Error> for IrcMetadataError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::InvalidJson(error.to_string())
+    }
+}
+
+// Serializes validated metadata into JSON fitting in a metadata feature.
+fn to_json(metadata: &impl Serialize) -> Result<Vec<u8>, IrcMetadataError> {
+    let json = serde_json::to_vec(metadata)?;
+    let max = *MetadataFeature::LENGTH_RANGE.end() as usize;
+
+    if json.len() > max {
+        return Err(IrcMetadataError::MetadataTooLarge {
+            length: json.len(),
+            max,
+        });
+    }
+
+    Ok(json)
+}
+
+// Deserializes the JSON of a metadata feature.
+fn from_json<T: DeserializeOwned>(json: &[u8]) -> Result<T, IrcMetadataError> {
+    let max = *MetadataFeature::LENGTH_RANGE.end() as usize;
+
+    if json.len() > max {
+        return Err(IrcMetadataError::MetadataTooLarge {
+            length: json.len(),
+            max,
+        });
+    }
+
+    Ok(serde_json::from_slice(json)?)
+}
+
+fn required<T>(value: Option<T>, field: &'static str) -> Result<T, IrcMetadataError> {
+    value.ok_or(IrcMetadataError::MissingField(field))
+}
+
+fn non_empty(value: &str, field: &'static str) -> Result<(), IrcMetadataError> {
+    if value.trim().is_empty() {
+        return Err(IrcMetadataError::EmptyField(field));
+    }
+
+    Ok(())
+}
+
+fn standard(actual: String, expected: &'static str) -> Result<(), IrcMetadataError> {
+    if actual != expected {
+        return Err(IrcMetadataError::InvalidStandard { expected, actual });
+    }
+
+    Ok(())
+}
@@ -24,6 +24,8 @@ pub mod basic;
 pub mod feature;
 ///
 pub mod foundry;
+#[cfg(feature = "irc")]
+pub mod irc;
 ///
 pub mod nft;
 ///
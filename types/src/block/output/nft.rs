@@ -174,6 +174,20 @@ impl NftOutputBuilder {
         Ok(self)
     }
 
+    /// Sets the immutable metadata feature to the IRC27 metadata of the NFT.
+    #[cfg(feature = "irc")]
+    pub fn with_irc_27_metadata(
+        mut self,
+        metadata: &super::irc::Irc27Metadata,
+    ) -> Result<Self, super::irc::IrcMetadataError> {
+        let feature = Feature::Metadata(metadata.try_into()?);
+
+        self.immutable_features.retain(|f| f.kind() != feature.kind());
+        self.immutable_features.push(feature);
+
+        Ok(self)
+    }
+
     ///
     pub fn finish_unverified(self) -> Result<NftOutput, Error> {
         let unlock_conditions = UnlockConditions::new(self.unlock_conditions)?;
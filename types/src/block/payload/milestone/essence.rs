@@ -15,9 +15,9 @@ use packable::{
 
 use crate::block::{
     parent::Parents,
-    payload::milestone::{MerkleRoot, MilestoneId, MilestoneIndex, MilestoneOptions},
+    payload::milestone::{MerkleProof, MerkleRoot, MilestoneId, MilestoneIndex, MilestoneOptions},
     protocol::ProtocolParameters,
-    Error,
+    BlockId, Error,
 };
 
 pub(crate) type MilestoneMetadataLength = BoundedU16<{ u16::MIN }, { u16::MAX }>;
@@ -115,6 +115,17 @@ impl MilestoneEssence {
         &self.options
     }
 
+    /// Verifies that a block was referenced by the milestone with a proof against the inclusion merkle root.
+    pub fn verify_inclusion_proof(&self, block_id: &BlockId, proof: &MerkleProof) -> bool {
+        proof.verify(block_id, &self.inclusion_merkle_root)
+    }
+
+    /// Verifies that the transaction of a block was applied by the milestone with a proof against the applied merkle
+    /// root.
+    pub fn verify_applied_proof(&self, block_id: &BlockId, proof: &MerkleProof) -> bool {
+        proof.verify(block_id, &self.applied_merkle_root)
+    }
+
     /// Hashes the [`MilestoneEssence`] to be signed.
     pub fn hash(&self) -> [u8; 32] {
         Blake2b256::digest(self.pack_to_vec()).into()
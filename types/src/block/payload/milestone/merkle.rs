@@ -1,7 +1,14 @@
 // Copyright 2022 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::block::Error;
+use alloc::vec::Vec;
+
+use crypto::hashes::{blake2b::Blake2b256, Digest};
+
+use crate::block::{BlockId, Error};
+
+const LEAF_HASH_PREFIX: u8 = 0;
+const NODE_HASH_PREFIX: u8 = 1;
 
 /// A Merkle root of a list of hashes.
 #[derive(Clone, Copy, Eq, PartialEq, packable::Packable, derive_more::From, derive_more::AsRef)]
@@ -52,3 +59,126 @@ impl core::str::FromStr for MerkleRoot {
         ))
     }
 }
+
+/// Computes the Merkle roots of milestones, the inclusion and applied Merkle roots, following TIP-4.
+///
+/// The tree is built from a list of block IDs in white-flag order. Leaves are hashed as `Blake2b-256(0x00 || block_id)`
+/// and nodes as `Blake2b-256(0x01 || left || right)`, the left subtree holding the largest power of two of block IDs
+/// that is smaller than their count. The root of an empty list is `Blake2b-256("")`.
+pub struct MerkleHasher;
+
+impl MerkleHasher {
+    /// Computes the Merkle root of a list of block IDs.
+    pub fn digest(block_ids: &[BlockId]) -> MerkleRoot {
+        MerkleRoot::from(tree_hash(block_ids))
+    }
+
+    /// Creates a proof that the block ID at `index` is part of the Merkle tree of a list of block IDs, `None` if the
+    /// index is out of bounds.
+    pub fn proof(block_ids: &[BlockId], index: usize) -> Option<MerkleProof> {
+        if index >= block_ids.len() {
+            return None;
+        }
+
+        let mut path = Vec::new();
+        audit_path(block_ids, index, &mut path);
+
+        Some(MerkleProof::new(path))
+    }
+}
+
+/// A sibling hash on the audit path of a [`MerkleProof`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MerkleAuditNode {
+    /// The hash of the left sibling subtree.
+    Left([u8; MerkleRoot::LENGTH]),
+    /// The hash of the right sibling subtree.
+    Right([u8; MerkleRoot::LENGTH]),
+}
+
+/// A proof that a block ID is part of a Merkle tree, made of the sibling hashes on the path from its leaf to the root.
+///
+/// Checked against the inclusion or applied Merkle root of a milestone, it proves that a block was referenced by the
+/// milestone, or that its transaction was applied to the ledger, without having to trust a node.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MerkleProof {
+    path: Vec<MerkleAuditNode>,
+}
+
+impl MerkleProof {
+    /// Creates a new [`MerkleProof`] from an audit path, ordered from the leaf to the root.
+    pub fn new(path: Vec<MerkleAuditNode>) -> Self {
+        Self { path }
+    }
+
+    /// Returns the audit path of a [`MerkleProof`], ordered from the leaf to the root.
+    pub fn path(&self) -> &[MerkleAuditNode] {
+        &self.path
+    }
+
+    /// Computes the Merkle root of the tree holding the block ID at the position described by the audit path.
+    pub fn root(&self, block_id: &BlockId) -> MerkleRoot {
+        let hash = self.path.iter().fold(leaf_hash(block_id), |hash, node| match node {
+            MerkleAuditNode::Left(left) => node_hash(left, &hash),
+            MerkleAuditNode::Right(right) => node_hash(&hash, right),
+        });
+
+        MerkleRoot::from(hash)
+    }
+
+    /// Verifies that the block ID is part of the Merkle tree with the given root.
+    pub fn verify(&self, block_id: &BlockId, root: &MerkleRoot) -> bool {
+        self.root(block_id) == *root
+    }
+}
+
+fn leaf_hash(block_id: &BlockId) -> [u8; MerkleRoot::LENGTH] {
+    Blake2b256::new()
+        .chain_update([LEAF_HASH_PREFIX])
+        .chain_update(block_id)
+        .finalize()
+        .into()
+}
+
+fn node_hash(left: &[u8; MerkleRoot::LENGTH], right: &[u8; MerkleRoot::LENGTH]) -> [u8; MerkleRoot::LENGTH] {
+    Blake2b256::new()
+        .chain_update([NODE_HASH_PREFIX])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+fn tree_hash(block_ids: &[BlockId]) -> [u8; MerkleRoot::LENGTH] {
+    match block_ids {
+        [] => Blake2b256::digest([]).into(),
+        [block_id] => leaf_hash(block_id),
+        _ => {
+            let (left, right) = block_ids.split_at(split_index(block_ids.len()));
+            node_hash(&tree_hash(left), &tree_hash(right))
+        }
+    }
+}
+
+fn audit_path(block_ids: &[BlockId], index: usize, path: &mut Vec<MerkleAuditNode>) {
+    if block_ids.len() < 2 {
+        return;
+    }
+
+    let (left, right) = block_ids.split_at(split_index(block_ids.len()));
+
+    if index < left.len() {
+        audit_path(left, index, path);
+        path.push(MerkleAuditNode::Right(tree_hash(right)));
+    } else {
+        audit_path(right, index - left.len(), path);
+        path.push(MerkleAuditNode::Left(tree_hash(left)));
+    }
+}
+
+/// Returns the largest power of two smaller than `count`, which must be at least 2.
+fn split_index(count: usize) -> usize {
+    1 << (usize::BITS - (count - 1).leading_zeros() - 1)
+}
@@ -7,6 +7,7 @@ mod essence;
 mod index;
 mod merkle;
 mod milestone_id;
+mod verifier;
 
 ///
 pub mod option;
@@ -22,9 +23,10 @@ use packable::{bounded::BoundedU8, prefix::VecPrefix, Packable};
 pub use self::{
     essence::MilestoneEssence,
     index::MilestoneIndex,
-    merkle::MerkleRoot,
+    merkle::{MerkleAuditNode, MerkleHasher, MerkleProof, MerkleRoot},
     milestone_id::MilestoneId,
     option::{MilestoneOption, MilestoneOptions, ParametersMilestoneOption, ReceiptMilestoneOption},
+    verifier::{MilestoneChainError, MilestoneChainVerifier, MilestoneKeyRange},
 };
 pub(crate) use self::{essence::MilestoneMetadataLength, option::BinaryParametersLength};
 use crate::block::{protocol::ProtocolParameters, signature::Signature, Error};
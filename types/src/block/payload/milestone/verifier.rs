@@ -0,0 +1,225 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Module describing the verification of a chain of milestones.
+
+use alloc::{string::String, vec::Vec};
+
+use packable::{
+    error::{UnexpectedEOF, UnpackError},
+    PackableExt,
+};
+
+use crate::block::{
+    payload::milestone::{MilestoneId, MilestoneIndex, MilestonePayload, MilestoneValidationError},
+    protocol::ProtocolParameters,
+    Error,
+};
+
+/// A coordinator public key and the range of milestone indexes, inclusive, it is applicable to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneKeyRange {
+    public_key: [u8; 32],
+    start: MilestoneIndex,
+    end: MilestoneIndex,
+}
+
+impl MilestoneKeyRange {
+    /// Creates a new [`MilestoneKeyRange`], an `end` of 0 meaning that the key stays applicable.
+    pub fn new(public_key: [u8; 32], start: MilestoneIndex, end: MilestoneIndex) -> Self {
+        Self { public_key, start, end }
+    }
+
+    /// Returns the public key of a [`MilestoneKeyRange`].
+    pub fn public_key(&self) -> &[u8; 32] {
+        &self.public_key
+    }
+
+    /// Returns the first milestone index of a [`MilestoneKeyRange`].
+    pub fn start(&self) -> MilestoneIndex {
+        self.start
+    }
+
+    /// Returns the last milestone index of a [`MilestoneKeyRange`], 0 if there is none.
+    pub fn end(&self) -> MilestoneIndex {
+        self.end
+    }
+
+    /// Checks whether the key is applicable to the milestone with the given index.
+    pub fn is_applicable(&self, index: MilestoneIndex) -> bool {
+        self.start <= index && (*self.end == 0 || index <= self.end)
+    }
+}
+
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub enum MilestoneChainError {
+    IndexNotContinuous {
+        expected: MilestoneIndex,
+        found: MilestoneIndex,
+    },
+    PreviousMilestoneIdMismatch {
+        expected: MilestoneId,
+        found: MilestoneId,
+    },
+    ProtocolVersionMismatch {
+        expected: u8,
+        found: u8,
+    },
+    InvalidParametersTargetIndex {
+        index: MilestoneIndex,
+        target: MilestoneIndex,
+    },
+    InvalidProtocolParameters(UnpackError<Error, UnexpectedEOF>),
+    InvalidSignatures(MilestoneValidationError),
+}
+
+impl From<MilestoneValidationError> for MilestoneChainError {
+    fn from(error: MilestoneValidationError) -> Self {
+        MilestoneChainError::InvalidSignatures(error)
+    }
+}
+
+/// Verifies that milestones form a chain, one milestone after the other.
+///
+/// Every milestone has to directly follow the latest verified one, by index and by previous milestone ID, to be signed
+/// by enough of the coordinator keys applicable to its index and to have the protocol version in effect. Protocol
+/// parameters announced by a [`ParametersMilestoneOption`](crate::block::payload::milestone::ParametersMilestoneOption)
+/// take effect at their target milestone index.
+#[derive(Clone, Debug)]
+pub struct MilestoneChainVerifier {
+    key_ranges: Vec<MilestoneKeyRange>,
+    min_threshold: usize,
+    protocol_parameters: ProtocolParameters,
+    // Announced protocol parameters, sorted by target milestone index.
+    pending_protocol_parameters: Vec<(MilestoneIndex, ProtocolParameters)>,
+    latest_milestone: (MilestoneIndex, MilestoneId),
+}
+
+impl MilestoneChainVerifier {
+    /// Creates a new [`MilestoneChainVerifier`] expecting the chain to start at the first milestone, which has a null
+    /// previous milestone ID.
+    pub fn new(
+        key_ranges: Vec<MilestoneKeyRange>,
+        min_threshold: usize,
+        protocol_parameters: ProtocolParameters,
+    ) -> Self {
+        Self {
+            key_ranges,
+            min_threshold,
+            protocol_parameters,
+            pending_protocol_parameters: Vec::new(),
+            latest_milestone: (MilestoneIndex::new(0), MilestoneId::null()),
+        }
+    }
+
+    /// Continues the chain from a trusted milestone instead of the first one, e.g. a milestone from a snapshot.
+    pub fn with_trusted_milestone(mut self, index: MilestoneIndex, milestone_id: MilestoneId) -> Self {
+        self.latest_milestone = (index, milestone_id);
+        self
+    }
+
+    /// Returns the index and ID of the latest verified or trusted milestone.
+    pub fn latest_milestone(&self) -> (MilestoneIndex, MilestoneId) {
+        self.latest_milestone
+    }
+
+    /// Returns the protocol parameters in effect at the latest verified milestone.
+    pub fn protocol_parameters(&self) -> &ProtocolParameters {
+        &self.protocol_parameters
+    }
+
+    /// Returns the public keys applicable to the milestone with the given index.
+    pub fn applicable_public_keys(&self, index: MilestoneIndex) -> Vec<[u8; 32]> {
+        self.key_ranges
+            .iter()
+            .filter(|key_range| key_range.is_applicable(index))
+            .map(|key_range| key_range.public_key)
+            .collect()
+    }
+
+    /// Verifies that a milestone directly follows the latest verified one and, if so, makes it the latest one.
+    ///
+    /// The state of the verifier is left untouched if the milestone is rejected.
+    pub fn verify(&mut self, milestone: &MilestonePayload) -> Result<(), MilestoneChainError> {
+        let essence = milestone.essence();
+        let (latest_index, latest_milestone_id) = self.latest_milestone;
+        let index = essence.index();
+
+        if index != latest_index + 1 {
+            return Err(MilestoneChainError::IndexNotContinuous {
+                expected: latest_index + 1,
+                found: index,
+            });
+        }
+
+        if *essence.previous_milestone_id() != latest_milestone_id {
+            return Err(MilestoneChainError::PreviousMilestoneIdMismatch {
+                expected: latest_milestone_id,
+                found: *essence.previous_milestone_id(),
+            });
+        }
+
+        let activated = self
+            .pending_protocol_parameters
+            .iter()
+            .take_while(|(target, _)| *target <= index)
+            .count();
+        let protocol_parameters = self.pending_protocol_parameters[..activated]
+            .last()
+            .map_or(&self.protocol_parameters, |(_, protocol_parameters)| {
+                protocol_parameters
+            });
+
+        if essence.protocol_version() != protocol_parameters.protocol_version() {
+            return Err(MilestoneChainError::ProtocolVersionMismatch {
+                expected: protocol_parameters.protocol_version(),
+                found: essence.protocol_version(),
+            });
+        }
+
+        let applicable_public_keys = self
+            .applicable_public_keys(index)
+            .iter()
+            .map(hex::encode)
+            .collect::<Vec<String>>();
+        milestone.validate(&applicable_public_keys, self.min_threshold)?;
+
+        let announced = match essence.options().parameters() {
+            Some(parameters) => {
+                if parameters.target_milestone_index() <= index {
+                    return Err(MilestoneChainError::InvalidParametersTargetIndex {
+                        index,
+                        target: parameters.target_milestone_index(),
+                    });
+                }
+
+                let protocol_parameters = ProtocolParameters::unpack_verified(parameters.binary_parameters(), &())
+                    .map_err(MilestoneChainError::InvalidProtocolParameters)?;
+
+                if protocol_parameters.protocol_version() != parameters.protocol_version() {
+                    return Err(MilestoneChainError::ProtocolVersionMismatch {
+                        expected: parameters.protocol_version(),
+                        found: protocol_parameters.protocol_version(),
+                    });
+                }
+
+                Some((parameters.target_milestone_index(), protocol_parameters))
+            }
+            None => None,
+        };
+
+        if let Some((_, protocol_parameters)) = self.pending_protocol_parameters.drain(..activated).next_back() {
+            self.protocol_parameters = protocol_parameters;
+        }
+        if let Some((target, protocol_parameters)) = announced {
+            // A new announcement replaces the ones targeting the same or a later index.
+            self.pending_protocol_parameters
+                .retain(|(pending_target, _)| *pending_target < target);
+            self.pending_protocol_parameters.push((target, protocol_parameters));
+        }
+        self.latest_milestone = (index, milestone.id());
+
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright 2022 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use alloc::vec::Vec;
 use core::{convert::Infallible, fmt};
 
 use hashbrown::{HashMap, HashSet};
@@ -9,7 +10,7 @@ use primitive_types::U256;
 use crate::block::{
     address::Address,
     error::Error,
-    output::{ChainId, FoundryId, InputsCommitment, NativeTokens, Output, OutputId, TokenId},
+    output::{ChainId, FoundryId, InputsCommitment, NativeTokens, Output, OutputId, StateTransitionError, TokenId},
     payload::transaction::{RegularTransactionEssence, TransactionEssence, TransactionId},
     unlock::Unlocks,
 };
@@ -135,6 +136,8 @@ pub struct ValidationContext<'a> {
     pub storage_deposit_returns: HashMap<Address, u64>,
     ///
     pub simple_deposits: HashMap<Address, u64>,
+    // The addresses assumed to sign an unsigned transaction, whose signature unlocks aren't verified.
+    pub(crate) assumed_signers: Option<HashSet<Address>>,
 }
 
 impl<'a> ValidationContext<'a> {
@@ -179,23 +182,188 @@ impl<'a> ValidationContext<'a> {
             unlocked_addresses: HashSet::new(),
             storage_deposit_returns: HashMap::new(),
             simple_deposits: HashMap::new(),
+            assumed_signers: None,
         }
     }
 }
 
+/// A failed semantic validation check, with the values involved.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SemanticValidationFailure {
+    /// The inputs commitment of the essence doesn't match the consumed outputs.
+    InputsCommitmentMismatch {
+        /// The inputs commitment computed from the consumed outputs.
+        expected: InputsCommitment,
+        /// The inputs commitment of the essence.
+        actual: InputsCommitment,
+    },
+    /// An input couldn't be unlocked by its unlock.
+    InvalidUnlock {
+        /// The index of the input and of its unlock.
+        index: usize,
+        /// The reason of the failure.
+        reason: ConflictReason,
+    },
+    /// An input is still time locked.
+    TimelockNotExpired {
+        /// The index of the input.
+        input_index: usize,
+        /// The timestamp until which the input is time locked.
+        timelock: u32,
+        /// The milestone timestamp the transaction is validated against.
+        milestone_timestamp: u32,
+    },
+    /// The sender of an output isn't unlocked by the transaction.
+    UnverifiedSender {
+        /// The index of the output.
+        output_index: usize,
+        /// The sender address.
+        sender: Address,
+    },
+    /// The storage deposit returns to an address are not fulfilled.
+    StorageDepositReturnUnfulfilled {
+        /// The return address.
+        return_address: Address,
+        /// The amount to return to the address.
+        expected: u64,
+        /// The amount deposited to the address in outputs without further unlock conditions.
+        actual: u64,
+    },
+    /// The consumed and created amounts differ.
+    CreatedConsumedAmountMismatch {
+        /// The consumed amount.
+        consumed: u64,
+        /// The created amount.
+        created: u64,
+    },
+    /// More native tokens are created than consumed without their foundry being transitioned.
+    UnbalancedNativeToken {
+        /// The ID of the native token.
+        token_id: TokenId,
+        /// The consumed amount of the native token.
+        consumed: U256,
+        /// The created amount of the native token.
+        created: U256,
+    },
+    /// The transaction holds too many distinct native tokens.
+    TooManyNativeTokens {
+        /// The number of distinct native tokens.
+        count: usize,
+        /// The maximum number of distinct native tokens.
+        max: usize,
+    },
+    /// A chain state transition is invalid.
+    InvalidChainStateTransition {
+        /// The ID of the chain.
+        chain_id: ChainId,
+        /// The index of the input holding the current state, if any.
+        input_index: Option<usize>,
+        /// The index of the output holding the next state, if any.
+        output_index: Option<usize>,
+        /// The reason of the failure.
+        error: StateTransitionError,
+    },
+}
+
+impl SemanticValidationFailure {
+    /// Returns the [`ConflictReason`] matching the failure.
+    pub fn conflict_reason(&self) -> ConflictReason {
+        match self {
+            Self::InputsCommitmentMismatch { .. } => ConflictReason::InputsCommitmentsMismatch,
+            Self::InvalidUnlock { reason, .. } => *reason,
+            Self::TimelockNotExpired { .. } => ConflictReason::TimelockNotExpired,
+            Self::UnverifiedSender { .. } => ConflictReason::UnverifiedSender,
+            Self::StorageDepositReturnUnfulfilled { .. } => ConflictReason::StorageDepositReturnUnfulfilled,
+            Self::CreatedConsumedAmountMismatch { .. } => ConflictReason::CreatedConsumedAmountMismatch,
+            Self::UnbalancedNativeToken { .. } | Self::TooManyNativeTokens { .. } => {
+                ConflictReason::InvalidNativeTokens
+            }
+            Self::InvalidChainStateTransition { .. } => ConflictReason::InvalidChainStateTransition,
+        }
+    }
+}
+
+/// The failures found by [`semantic_validation_report`], in the order in which they were found.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SemanticValidationReport {
+    failures: Vec<SemanticValidationFailure>,
+    // Whether the validation stops at the first failure.
+    first_only: bool,
+}
+
+impl SemanticValidationReport {
+    /// Returns the failures of the [`SemanticValidationReport`].
+    pub fn failures(&self) -> &[SemanticValidationFailure] {
+        &self.failures
+    }
+
+    /// Checks whether the transaction passed all the checks.
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Returns the conflict reason of the first failure, which is the one returned by [`semantic_validation`].
+    pub fn conflict_reason(&self) -> ConflictReason {
+        self.failures
+            .first()
+            .map_or(ConflictReason::None, SemanticValidationFailure::conflict_reason)
+    }
+
+    /// Adds a failure and returns whether the validation has to stop.
+    fn fail(&mut self, failure: SemanticValidationFailure) -> bool {
+        self.failures.push(failure);
+        self.first_only
+    }
+}
+
 ///
 pub fn semantic_validation(
-    mut context: ValidationContext,
+    context: ValidationContext,
     inputs: &[(OutputId, &Output)],
     unlocks: &Unlocks,
 ) -> Result<ConflictReason, Error> {
+    let mut report = SemanticValidationReport {
+        failures: Vec::new(),
+        first_only: true,
+    };
+
+    validate(context, inputs, unlocks, &mut report)?;
+
+    Ok(report.conflict_reason())
+}
+
+/// Runs all the semantic validation checks instead of stopping at the first failure, to diagnose why a transaction is
+/// rejected.
+pub fn semantic_validation_report(
+    context: ValidationContext,
+    inputs: &[(OutputId, &Output)],
+    unlocks: &Unlocks,
+) -> Result<SemanticValidationReport, Error> {
+    let mut report = SemanticValidationReport::default();
+
+    validate(context, inputs, unlocks, &mut report)?;
+
+    Ok(report)
+}
+
+fn validate(
+    mut context: ValidationContext,
+    inputs: &[(OutputId, &Output)],
+    unlocks: &Unlocks,
+    report: &mut SemanticValidationReport,
+) -> Result<(), Error> {
     // Validation of the inputs commitment.
-    if context.essence.inputs_commitment() != &context.inputs_commitment {
-        return Ok(ConflictReason::InputsCommitmentsMismatch);
+    if context.essence.inputs_commitment() != &context.inputs_commitment
+        && report.fail(SemanticValidationFailure::InputsCommitmentMismatch {
+            expected: context.inputs_commitment,
+            actual: *context.essence.inputs_commitment(),
+        })
+    {
+        return Ok(());
     }
 
     // Validation of inputs.
-    for ((output_id, consumed_output), unlock) in inputs.iter().zip(unlocks.iter()) {
+    for (index, ((output_id, consumed_output), unlock)) in inputs.iter().zip(unlocks.iter()).enumerate() {
         let (conflict, amount, consumed_native_tokens, unlock_conditions) = match consumed_output {
             Output::Basic(output) => (
                 output.unlock(output_id, unlock, inputs, &mut context),
@@ -224,12 +392,22 @@ pub fn semantic_validation(
             _ => return Err(Error::UnsupportedOutputKind(consumed_output.kind())),
         };
 
-        if let Err(conflict) = conflict {
-            return Ok(conflict);
+        if let Err(reason) = conflict {
+            if report.fail(SemanticValidationFailure::InvalidUnlock { index, reason }) {
+                return Ok(());
+            }
         }
 
         if unlock_conditions.is_time_locked(context.milestone_timestamp) {
-            return Ok(ConflictReason::TimelockNotExpired);
+            let timelock = unlock_conditions.timelock().map_or(0, |timelock| timelock.timestamp());
+
+            if report.fail(SemanticValidationFailure::TimelockNotExpired {
+                input_index: index,
+                timelock,
+                milestone_timestamp: context.milestone_timestamp,
+            }) {
+                return Ok(());
+            }
         }
 
         if !unlock_conditions.is_expired(context.milestone_timestamp) {
@@ -260,7 +438,7 @@ pub fn semantic_validation(
     }
 
     // Validation of outputs.
-    for created_output in context.essence.outputs() {
+    for (index, created_output) in context.essence.outputs().iter().enumerate() {
         let (amount, created_native_tokens, features) = match created_output {
             Output::Basic(output) => {
                 if let Some(address) = output.simple_deposit_address() {
@@ -280,8 +458,13 @@ pub fn semantic_validation(
         };
 
         if let Some(sender) = features.sender() {
-            if !context.unlocked_addresses.contains(sender.address()) {
-                return Ok(ConflictReason::UnverifiedSender);
+            if !context.unlocked_addresses.contains(sender.address())
+                && report.fail(SemanticValidationFailure::UnverifiedSender {
+                    output_index: index,
+                    sender: *sender.address(),
+                })
+            {
+                return Ok(());
             }
         }
 
@@ -304,18 +487,27 @@ pub fn semantic_validation(
 
     // Validation of storage deposit returns.
     for (return_address, return_amount) in context.storage_deposit_returns.iter() {
-        if let Some(deposit_amount) = context.simple_deposits.get(return_address) {
-            if deposit_amount < return_amount {
-                return Ok(ConflictReason::StorageDepositReturnUnfulfilled);
-            }
-        } else {
-            return Ok(ConflictReason::StorageDepositReturnUnfulfilled);
+        let deposit_amount = context.simple_deposits.get(return_address).copied();
+
+        if !matches!(deposit_amount, Some(deposit_amount) if deposit_amount >= *return_amount)
+            && report.fail(SemanticValidationFailure::StorageDepositReturnUnfulfilled {
+                return_address: *return_address,
+                expected: *return_amount,
+                actual: deposit_amount.unwrap_or_default(),
+            })
+        {
+            return Ok(());
         }
     }
 
     // Validation of amounts.
-    if context.input_amount != context.output_amount {
-        return Ok(ConflictReason::CreatedConsumedAmountMismatch);
+    if context.input_amount != context.output_amount
+        && report.fail(SemanticValidationFailure::CreatedConsumedAmountMismatch {
+            consumed: context.input_amount,
+            created: context.output_amount,
+        })
+    {
+        return Ok(());
     }
 
     let mut native_token_ids = HashSet::new();
@@ -333,40 +525,83 @@ pub fn semantic_validation(
             && !context
                 .output_chains
                 .contains_key(&ChainId::from(FoundryId::from(*token_id)))
+            && report.fail(SemanticValidationFailure::UnbalancedNativeToken {
+                token_id: *token_id,
+                consumed: input_amount,
+                created: *output_amount,
+            })
         {
-            return Ok(ConflictReason::InvalidNativeTokens);
+            return Ok(());
         }
 
         native_token_ids.insert(token_id);
     }
 
-    if native_token_ids.len() > NativeTokens::COUNT_MAX as usize {
-        return Ok(ConflictReason::InvalidNativeTokens);
+    if native_token_ids.len() > NativeTokens::COUNT_MAX as usize
+        && report.fail(SemanticValidationFailure::TooManyNativeTokens {
+            count: native_token_ids.len(),
+            max: NativeTokens::COUNT_MAX as usize,
+        })
+    {
+        return Ok(());
     }
 
     // Validation of state transitions and destructions.
     for (chain_id, current_state) in context.input_chains.iter() {
-        if Output::verify_state_transition(
-            Some(current_state),
-            context.output_chains.get(chain_id).map(core::ops::Deref::deref),
-            &context,
-        )
-        .is_err()
-        {
-            return Ok(ConflictReason::InvalidChainStateTransition);
+        let next_state = context.output_chains.get(chain_id).map(core::ops::Deref::deref);
+
+        if let Err(error) = Output::verify_state_transition(Some(current_state), next_state, &context) {
+            let failure = SemanticValidationFailure::InvalidChainStateTransition {
+                chain_id: *chain_id,
+                input_index: input_index(inputs, chain_id),
+                output_index: next_state.and_then(|next_state| output_index(&context, next_state)),
+                error,
+            };
+
+            if report.fail(failure) {
+                return Ok(());
+            }
         }
     }
 
     // Validation of state creations.
     for (chain_id, next_state) in context.output_chains.iter() {
-        if context.input_chains.get(chain_id).is_none()
-            && Output::verify_state_transition(None, Some(next_state), &context).is_err()
-        {
-            return Ok(ConflictReason::InvalidChainStateTransition);
+        if context.input_chains.get(chain_id).is_none() {
+            if let Err(error) = Output::verify_state_transition(None, Some(next_state), &context) {
+                let failure = SemanticValidationFailure::InvalidChainStateTransition {
+                    chain_id: *chain_id,
+                    input_index: None,
+                    output_index: output_index(&context, next_state),
+                    error,
+                };
+
+                if report.fail(failure) {
+                    return Ok(());
+                }
+            }
         }
     }
 
-    Ok(ConflictReason::None)
+    Ok(())
+}
+
+/// Returns the index of the input holding the state of a chain.
+fn input_index(inputs: &[(OutputId, &Output)], chain_id: &ChainId) -> Option<usize> {
+    inputs.iter().position(|(output_id, output)| {
+        output
+            .chain_id()
+            .map(|input_chain_id| input_chain_id.or_from_output_id(output_id))
+            == Some(*chain_id)
+    })
+}
+
+/// Returns the index of a created output.
+fn output_index(context: &ValidationContext, output: &Output) -> Option<usize> {
+    context
+        .essence
+        .outputs()
+        .iter()
+        .position(|created_output| core::ptr::eq(created_output, output))
 }
 
 #[cfg(feature = "inx")]
@@ -0,0 +1,171 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use iota_types::block::{
+    address::{Address, AliasAddress},
+    output::{
+        feature::{Feature, MetadataFeature},
+        irc::{Irc27Attribute, Irc27Metadata, Irc30Metadata, IrcMetadataError},
+        unlock_condition::{AddressUnlockCondition, ImmutableAliasAddressUnlockCondition, UnlockCondition},
+        AliasId, FoundryOutputBuilder, NftId, NftOutputBuilder, SimpleTokenScheme, TokenScheme,
+    },
+};
+use primitive_types::U256;
+
+const ROYALTY_ADDRESS: &str = "rms1qpllaj0pyveqfkwxmnngz2c488hfdtmfrj3wfkgxtk4gtyrax0jaxzt70zy";
+
+#[test]
+fn irc_27_round_trip() {
+    let metadata = Irc27Metadata::new("image/png", "https://example.com/nft.png", "My NFT")
+        .unwrap()
+        .with_collection_name("My Collection")
+        .with_royalties(BTreeMap::from([(ROYALTY_ADDRESS.to_string(), 0.025)]))
+        .unwrap()
+        .with_issuer_name("Me")
+        .with_description("An NFT")
+        .with_attributes([Irc27Attribute::new("Background", "Purple")]);
+
+    let json = metadata.to_json().unwrap();
+    assert!(json.starts_with(r#"{"standard":"IRC27","version":"v1.0","type":"image/png""#));
+    assert_eq!(Irc27Metadata::from_json(json.as_bytes()).unwrap(), metadata);
+
+    let feature = MetadataFeature::try_from(&metadata).unwrap();
+    assert_eq!(Irc27Metadata::try_from(&feature).unwrap(), metadata);
+}
+
+#[test]
+fn irc_27_invalid_fields() {
+    let error = Irc27Metadata::from_json(br#"{"standard":"IRC27","version":"v1.0","type":"image/png","name":"NFT"}"#)
+        .unwrap_err();
+    assert_eq!(error, IrcMetadataError::MissingField("uri"));
+    assert_eq!(error.field(), Some("uri"));
+
+    assert_eq!(
+        Irc27Metadata::from_json(br#"{"standard":"IRC27","version":"v2.0","type":"image/png","uri":"u","name":"n"}"#)
+            .unwrap_err()
+            .field(),
+        Some("version")
+    );
+    assert_eq!(
+        Irc27Metadata::from_json(br#"{"standard":"IRC30","version":"v1.0","type":"image/png","uri":"u","name":"n"}"#)
+            .unwrap_err()
+            .field(),
+        Some("standard")
+    );
+    assert_eq!(
+        Irc27Metadata::new("image/png", "https://example.com/nft.png", " ").unwrap_err(),
+        IrcMetadataError::EmptyField("name")
+    );
+
+    let metadata = Irc27Metadata::new("image/png", "https://example.com/nft.png", "My NFT").unwrap();
+    assert_eq!(
+        metadata
+            .clone()
+            .with_royalties(BTreeMap::from([("not an address".to_string(), 0.1)]))
+            .unwrap_err(),
+        IrcMetadataError::InvalidRoyaltyAddress("not an address".to_string())
+    );
+    assert!(matches!(
+        metadata
+            .clone()
+            .with_royalties(BTreeMap::from([(ROYALTY_ADDRESS.to_string(), 1.5)])),
+        Err(IrcMetadataError::InvalidRoyaltyShare { .. })
+    ));
+    assert!(matches!(
+        metadata.with_description("a".repeat(8192)).to_json(),
+        Err(IrcMetadataError::MetadataTooLarge { max: 8192, .. })
+    ));
+}
+
+#[test]
+fn irc_27_royalty_shares_sum() {
+    let metadata = Irc27Metadata::new("image/png", "https://example.com/nft.png", "My NFT").unwrap();
+    let royalty_addresses = [
+        ROYALTY_ADDRESS,
+        "rms1qqhvvur9xfj6yhgsxfa4f8xst7vz9zxeu3vcxds8mh4a6jlpteq9xrajhtf",
+        "rms1qr47gz3xxjqpjrwd0yu5glhqrth6w0t08npney8000ust2lcw2r92j5a8rt",
+    ];
+    let royalties = |shares: [f64; 3]| {
+        royalty_addresses
+            .iter()
+            .map(|address| address.to_string())
+            .zip(shares)
+            .collect::<BTreeMap<_, _>>()
+    };
+
+    // The floating point sum of these shares is slightly more than 1.
+    assert!(metadata.clone().with_royalties(royalties([0.34, 0.56, 0.1])).is_ok());
+    assert!(matches!(
+        metadata.with_royalties(royalties([0.34, 0.56, 0.2])),
+        Err(IrcMetadataError::RoyaltySharesOverflow(_))
+    ));
+}
+
+#[test]
+fn irc_30_round_trip() {
+    let metadata = Irc30Metadata::new("Shimmer Token", "STK", 6)
+        .unwrap()
+        .with_description("A token")
+        .with_url("https://example.com")
+        .with_logo_url("https://example.com/logo.png");
+
+    let json = metadata.to_json().unwrap();
+    assert_eq!(
+        json,
+        r#"{"standard":"IRC30","name":"Shimmer Token","symbol":"STK","decimals":6,"description":"A token","url":"https://example.com","logoUrl":"https://example.com/logo.png"}"#
+    );
+    assert_eq!(Irc30Metadata::from_json(json.as_bytes()).unwrap(), metadata);
+
+    assert_eq!(
+        Irc30Metadata::from_json(br#"{"standard":"IRC30","name":"Token","symbol":"TK"}"#).unwrap_err(),
+        IrcMetadataError::MissingField("decimals")
+    );
+    assert!(matches!(
+        Irc30Metadata::from_json(b"not json"),
+        Err(IrcMetadataError::InvalidJson(_))
+    ));
+}
+
+#[test]
+fn builder_helpers() {
+    let nft_metadata = Irc27Metadata::new("image/png", "https://example.com/nft.png", "My NFT").unwrap();
+    let nft = NftOutputBuilder::new_with_amount(1_000_000, NftId::null())
+        .unwrap()
+        .add_immutable_feature(Feature::Metadata(MetadataFeature::new(vec![1]).unwrap()))
+        .with_irc_27_metadata(&nft_metadata)
+        .unwrap()
+        .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(
+            Address::try_from_bech32(ROYALTY_ADDRESS).unwrap().1,
+        )))
+        .finish_unverified()
+        .unwrap();
+
+    // The previous metadata feature is replaced.
+    assert_eq!(nft.immutable_features().len(), 1);
+    assert_eq!(
+        Irc27Metadata::try_from(nft.immutable_features().metadata().unwrap()).unwrap(),
+        nft_metadata
+    );
+
+    let token_metadata = Irc30Metadata::new("Shimmer Token", "STK", 6).unwrap();
+    let foundry = FoundryOutputBuilder::new_with_amount(
+        1_000_000,
+        1,
+        TokenScheme::Simple(SimpleTokenScheme::new(U256::from(100), U256::from(0), U256::from(100)).unwrap()),
+    )
+    .unwrap()
+    .with_irc_30_metadata(&token_metadata)
+    .unwrap()
+    .add_unlock_condition(UnlockCondition::ImmutableAliasAddress(
+        ImmutableAliasAddressUnlockCondition::new(AliasAddress::new(AliasId::null())),
+    ))
+    .finish_unverified()
+    .unwrap();
+
+    assert_eq!(
+        Irc30Metadata::try_from(foundry.immutable_features().metadata().unwrap()).unwrap(),
+        token_metadata
+    );
+}
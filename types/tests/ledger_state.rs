@@ -0,0 +1,158 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crypto::{
+    hashes::{blake2b::Blake2b256, Digest},
+    signatures::ed25519::SecretKey,
+};
+use iota_types::block::{
+    address::{Address, Ed25519Address},
+    input::{Input, UtxoInput},
+    ledger::{LedgerState, LedgerStateError},
+    output::{
+        unlock_condition::{AddressUnlockCondition, TimelockUnlockCondition, UnlockCondition},
+        BasicOutput, InputsCommitment, Output, OutputId,
+    },
+    payload::transaction::{RegularTransactionEssence, TransactionEssence, TransactionId, TransactionPayload},
+    protocol::protocol_parameters,
+    semantic::ConflictReason,
+    signature::{Ed25519Signature, Signature},
+    unlock::{SignatureUnlock, Unlock, Unlocks},
+};
+
+const GENESIS_TRANSACTION_ID: &str = "0x52fdfc072182654f163f5f0f9a621d729566c74d10037c4d7bbb0407d1e2c649";
+
+fn basic_output(amount: u64, key: &SecretKey, timelock: Option<u32>) -> Output {
+    let address = Address::Ed25519(Ed25519Address::new(
+        Blake2b256::digest(key.public_key().to_bytes()).into(),
+    ));
+    let mut builder = BasicOutput::build_with_amount(amount)
+        .unwrap()
+        .add_unlock_condition(UnlockCondition::Address(AddressUnlockCondition::new(address)));
+
+    if let Some(timelock) = timelock {
+        builder = builder.add_unlock_condition(UnlockCondition::Timelock(
+            TimelockUnlockCondition::new(timelock).unwrap(),
+        ));
+    }
+
+    builder.finish_output(protocol_parameters().token_supply()).unwrap()
+}
+
+fn transaction(state: &LedgerState, input: OutputId, outputs: Vec<Output>, key: &SecretKey) -> TransactionPayload {
+    let protocol_parameters = protocol_parameters();
+    let essence = RegularTransactionEssence::builder(
+        protocol_parameters.network_id(),
+        InputsCommitment::new(state.output(&input).into_iter()),
+    )
+    .with_inputs(vec![Input::Utxo(UtxoInput::from(input))])
+    .with_outputs(outputs)
+    .finish(&protocol_parameters)
+    .unwrap();
+    let essence = TransactionEssence::Regular(essence);
+    let signature = key.sign(&essence.hash());
+    let unlocks = Unlocks::new(vec![Unlock::Signature(SignatureUnlock::from(Signature::Ed25519(
+        Ed25519Signature::new(key.public_key().to_bytes(), signature.to_bytes()),
+    )))])
+    .unwrap();
+
+    TransactionPayload::new(essence, unlocks).unwrap()
+}
+
+fn genesis(key: &SecretKey, timelock: Option<u32>) -> (LedgerState, OutputId) {
+    let output_id = OutputId::new(
+        TransactionId::new(prefix_hex::decode(GENESIS_TRANSACTION_ID).unwrap()),
+        0,
+    )
+    .unwrap();
+
+    (
+        LedgerState::new([(output_id, basic_output(3_000_000, key, timelock))], 100),
+        output_id,
+    )
+}
+
+#[test]
+fn chained_transactions() {
+    let key = SecretKey::from_bytes([1; 32]);
+    let other_key = SecretKey::from_bytes([2; 32]);
+    let (state, genesis_output_id) = genesis(&key, None);
+
+    let first = transaction(
+        &state,
+        genesis_output_id,
+        vec![
+            basic_output(1_000_000, &other_key, None),
+            basic_output(2_000_000, &key, None),
+        ],
+        &key,
+    );
+    let (next_state, created) = state.apply_transaction(&first).unwrap();
+
+    assert_eq!(
+        created,
+        vec![
+            OutputId::new(first.id(), 0).unwrap(),
+            OutputId::new(first.id(), 1).unwrap()
+        ]
+    );
+    assert!(!next_state.is_unspent(&genesis_output_id));
+    assert_eq!(next_state.len(), 2);
+    // The original state is left untouched.
+    assert!(state.is_unspent(&genesis_output_id));
+
+    let second = transaction(
+        &next_state,
+        created[1],
+        vec![basic_output(2_000_000, &other_key, None)],
+        &key,
+    );
+    let (final_state, created) = state.apply_transactions([&first, &second]).unwrap();
+
+    assert_eq!(created.len(), 2);
+    assert_eq!(final_state.len(), 2);
+    assert!(final_state.is_unspent(&OutputId::new(first.id(), 0).unwrap()));
+    assert!(final_state.is_unspent(&OutputId::new(second.id(), 0).unwrap()));
+
+    // The output consumed by the first transaction can't be spent again.
+    assert!(matches!(
+        final_state.apply_transaction(&first),
+        Err(LedgerStateError::InputNotUnspent(output_id)) if output_id == genesis_output_id
+    ));
+    // The second transaction depends on the first one.
+    assert!(matches!(
+        state.apply_transactions([&second, &first]),
+        Err(LedgerStateError::InputNotUnspent(_))
+    ));
+}
+
+#[test]
+fn timelocked_input() {
+    let key = SecretKey::from_bytes([1; 32]);
+    let (state, genesis_output_id) = genesis(&key, Some(200));
+    let transaction = transaction(
+        &state,
+        genesis_output_id,
+        vec![basic_output(3_000_000, &key, None)],
+        &key,
+    );
+
+    assert!(matches!(
+        state.apply_transaction(&transaction),
+        Err(LedgerStateError::Conflict(ConflictReason::TimelockNotExpired))
+    ));
+
+    let (state, created) = state
+        .with_milestone_timestamp(200)
+        .apply_transaction(&transaction)
+        .unwrap();
+
+    assert_eq!(state.milestone_timestamp(), 200);
+    assert_eq!(state.output(&created[0]), transaction_output(&transaction));
+}
+
+fn transaction_output(transaction: &TransactionPayload) -> Option<&Output> {
+    let TransactionEssence::Regular(essence) = transaction.essence();
+
+    essence.outputs().first()
+}
@@ -0,0 +1,74 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use core::str::FromStr;
+
+use iota_types::block::{
+    payload::milestone::{MerkleAuditNode, MerkleHasher, MerkleProof, MerkleRoot},
+    rand::block::rand_block_id,
+    BlockId,
+};
+
+// Example from TIP-4.
+const BLOCK_IDS: [&str; 7] = [
+    "0x52fdfc072182654f163f5f0f9a621d729566c74d10037c4d7bbb0407d1e2c649",
+    "0x81855ad8681d0d86d1e91e00167939cb6694d2c422acd208a0072939487f6999",
+    "0xeb9d18a44784045d87f3c67cf22746e995af5a25367951baa2ff6cd471c483f1",
+    "0x5fb90badb37c5821b6d95526a41a9504680b4e7c8b763a1b1d49d4955c848621",
+    "0x6325253fec738dd7a9e28bf921119c160f0702448615bbda08313f6a8eb668d2",
+    "0x0bf5059875921e668a5bdf2c7fc4844592d2572bcd0668d2d6c52f5054e2d083",
+    "0x6bf84c7174cb7476364cc3dbd968b0f7172ed85794bb358b0c3b525da1786f9f",
+];
+const ROOT: &str = "0xbf67ce7ba23e8c0951b5abaec4f5524360d2c26d971ff226d3359fa70cdb0beb";
+
+fn block_ids() -> Vec<BlockId> {
+    BLOCK_IDS.iter().map(|id| BlockId::from_str(id).unwrap()).collect()
+}
+
+#[test]
+fn digest() {
+    assert_eq!(MerkleHasher::digest(&block_ids()), MerkleRoot::from_str(ROOT).unwrap());
+}
+
+#[test]
+fn digest_empty() {
+    assert_eq!(
+        MerkleHasher::digest(&[]),
+        MerkleRoot::from_str("0x0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8").unwrap()
+    );
+}
+
+#[test]
+fn proof_valid() {
+    let block_ids = block_ids();
+    let root = MerkleHasher::digest(&block_ids);
+
+    for (index, block_id) in block_ids.iter().enumerate() {
+        let proof = MerkleHasher::proof(&block_ids, index).unwrap();
+
+        assert!(proof.verify(block_id, &root));
+    }
+
+    // A tree with a single leaf has an empty audit path.
+    let proof = MerkleHasher::proof(&block_ids[..1], 0).unwrap();
+    assert!(proof.path().is_empty());
+    assert!(proof.verify(&block_ids[0], &MerkleHasher::digest(&block_ids[..1])));
+}
+
+#[test]
+fn proof_invalid() {
+    let block_ids = block_ids();
+    let root = MerkleHasher::digest(&block_ids);
+    let proof = MerkleHasher::proof(&block_ids, 2).unwrap();
+
+    assert!(MerkleHasher::proof(&block_ids, block_ids.len()).is_none());
+    assert!(!proof.verify(&block_ids[3], &root));
+    assert!(!proof.verify(&rand_block_id(), &root));
+
+    let mut path = proof.path().to_vec();
+    path[0] = match path[0] {
+        MerkleAuditNode::Left(hash) => MerkleAuditNode::Right(hash),
+        MerkleAuditNode::Right(hash) => MerkleAuditNode::Left(hash),
+    };
+    assert!(!MerkleProof::new(path).verify(&block_ids[2], &root));
+}
@@ -0,0 +1,192 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crypto::signatures::ed25519::SecretKey;
+use iota_types::block::{
+    output::RentStructure,
+    payload::milestone::{
+        MerkleRoot, MilestoneChainError, MilestoneChainVerifier, MilestoneEssence, MilestoneId, MilestoneIndex,
+        MilestoneKeyRange, MilestoneOption, MilestoneOptions, MilestonePayload, MilestoneValidationError,
+        ParametersMilestoneOption,
+    },
+    protocol::{protocol_parameters, ProtocolParameters},
+    rand::parents::rand_parents,
+    signature::{Ed25519Signature, Signature},
+};
+use packable::PackableExt;
+
+fn milestone(
+    index: u32,
+    previous_milestone_id: MilestoneId,
+    protocol_version: u8,
+    options: Vec<MilestoneOption>,
+    keys: &[&SecretKey],
+) -> MilestonePayload {
+    let essence = MilestoneEssence::new(
+        MilestoneIndex::new(index),
+        index,
+        protocol_version,
+        previous_milestone_id,
+        rand_parents(),
+        MerkleRoot::null(),
+        MerkleRoot::null(),
+        Vec::new(),
+        MilestoneOptions::new(options).unwrap(),
+    )
+    .unwrap();
+    let mut signatures = keys
+        .iter()
+        .map(|key| {
+            Signature::from(Ed25519Signature::new(
+                key.public_key().to_bytes(),
+                key.sign(&essence.hash()).to_bytes(),
+            ))
+        })
+        .collect::<Vec<_>>();
+    signatures.sort_by_key(|signature| {
+        let Signature::Ed25519(signature) = signature;
+        *signature.public_key()
+    });
+
+    MilestonePayload::new(essence, signatures).unwrap()
+}
+
+#[test]
+fn verify_chain() {
+    let key = SecretKey::from_bytes([1; 32]);
+    let version = protocol_parameters().protocol_version();
+    let mut verifier = MilestoneChainVerifier::new(
+        vec![MilestoneKeyRange::new(
+            key.public_key().to_bytes(),
+            MilestoneIndex::new(1),
+            MilestoneIndex::new(0),
+        )],
+        1,
+        protocol_parameters(),
+    );
+
+    let first = milestone(1, MilestoneId::null(), version, Vec::new(), &[&key]);
+    verifier.verify(&first).unwrap();
+    let second = milestone(2, first.id(), version, Vec::new(), &[&key]);
+    verifier.verify(&second).unwrap();
+    assert_eq!(verifier.latest_milestone(), (MilestoneIndex::new(2), second.id()));
+
+    // A milestone can't be verified twice, skipped or point to another previous milestone.
+    assert!(matches!(
+        verifier.verify(&second),
+        Err(MilestoneChainError::IndexNotContinuous { .. })
+    ));
+    assert!(matches!(
+        verifier.verify(&milestone(4, second.id(), version, Vec::new(), &[&key])),
+        Err(MilestoneChainError::IndexNotContinuous { .. })
+    ));
+    assert!(matches!(
+        verifier.verify(&milestone(3, first.id(), version, Vec::new(), &[&key])),
+        Err(MilestoneChainError::PreviousMilestoneIdMismatch { .. })
+    ));
+    assert!(matches!(
+        verifier.verify(&milestone(
+            3,
+            second.id(),
+            version,
+            Vec::new(),
+            &[&SecretKey::from_bytes([2; 32])]
+        )),
+        Err(MilestoneChainError::InvalidSignatures(
+            MilestoneValidationError::UnapplicablePublicKey(_)
+        ))
+    ));
+    assert_eq!(verifier.latest_milestone(), (MilestoneIndex::new(2), second.id()));
+
+    verifier
+        .verify(&milestone(3, second.id(), version, Vec::new(), &[&key]))
+        .unwrap();
+}
+
+#[test]
+fn verify_key_ranges() {
+    let old_key = SecretKey::from_bytes([1; 32]);
+    let new_key = SecretKey::from_bytes([2; 32]);
+    let version = protocol_parameters().protocol_version();
+    let first = milestone(10, MilestoneId::null(), version, Vec::new(), &[&old_key]);
+    let mut verifier = MilestoneChainVerifier::new(
+        vec![
+            MilestoneKeyRange::new(
+                old_key.public_key().to_bytes(),
+                MilestoneIndex::new(1),
+                MilestoneIndex::new(11),
+            ),
+            MilestoneKeyRange::new(
+                new_key.public_key().to_bytes(),
+                MilestoneIndex::new(11),
+                MilestoneIndex::new(0),
+            ),
+        ],
+        1,
+        protocol_parameters(),
+    )
+    .with_trusted_milestone(MilestoneIndex::new(10), first.id());
+
+    // Both keys are applicable to milestone 11, only the new one afterwards.
+    assert_eq!(verifier.applicable_public_keys(MilestoneIndex::new(11)).len(), 2);
+    let second = milestone(11, first.id(), version, Vec::new(), &[&old_key]);
+    verifier.verify(&second).unwrap();
+    assert!(matches!(
+        verifier.verify(&milestone(12, second.id(), version, Vec::new(), &[&old_key])),
+        Err(MilestoneChainError::InvalidSignatures(_))
+    ));
+    verifier
+        .verify(&milestone(12, second.id(), version, Vec::new(), &[&new_key]))
+        .unwrap();
+}
+
+#[test]
+fn verify_protocol_parameters_upgrade() {
+    let key = SecretKey::from_bytes([1; 32]);
+    let version = protocol_parameters().protocol_version();
+    let upgraded = ProtocolParameters::new(
+        version + 1,
+        String::from("testnet"),
+        String::from("rms"),
+        0,
+        15,
+        RentStructure::default(),
+        1_813_620_509_061_365,
+    )
+    .unwrap();
+    let mut verifier = MilestoneChainVerifier::new(
+        vec![MilestoneKeyRange::new(
+            key.public_key().to_bytes(),
+            MilestoneIndex::new(1),
+            MilestoneIndex::new(0),
+        )],
+        1,
+        protocol_parameters(),
+    );
+
+    let upgrade = |target: u32| {
+        vec![MilestoneOption::Parameters(
+            ParametersMilestoneOption::new(MilestoneIndex::new(target), version + 1, upgraded.pack_to_vec()).unwrap(),
+        )]
+    };
+
+    assert!(matches!(
+        verifier.verify(&milestone(1, MilestoneId::null(), version, upgrade(1), &[&key])),
+        Err(MilestoneChainError::InvalidParametersTargetIndex { .. })
+    ));
+
+    let first = milestone(1, MilestoneId::null(), version, upgrade(3), &[&key]);
+    verifier.verify(&first).unwrap();
+    let second = milestone(2, first.id(), version, Vec::new(), &[&key]);
+    verifier.verify(&second).unwrap();
+    assert_eq!(verifier.protocol_parameters(), &protocol_parameters());
+
+    assert!(matches!(
+        verifier.verify(&milestone(3, second.id(), version, Vec::new(), &[&key])),
+        Err(MilestoneChainError::ProtocolVersionMismatch { .. })
+    ));
+    verifier
+        .verify(&milestone(3, second.id(), version + 1, Vec::new(), &[&key]))
+        .unwrap();
+    assert_eq!(verifier.protocol_parameters(), &upgraded);
+}
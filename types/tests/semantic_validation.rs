@@ -0,0 +1,174 @@
+// Copyright 2023 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crypto::{
+    hashes::{blake2b::Blake2b256, Digest},
+    signatures::ed25519::SecretKey,
+};
+use iota_types::block::{
+    address::{Address, Ed25519Address},
+    input::{Input, UtxoInput},
+    output::{
+        feature::{Feature, SenderFeature},
+        unlock_condition::{AddressUnlockCondition, TimelockUnlockCondition, UnlockCondition},
+        BasicOutput, InputsCommitment, NativeToken, Output, OutputId, TokenId,
+    },
+    payload::transaction::{RegularTransactionEssence, TransactionEssence, TransactionId},
+    protocol::protocol_parameters,
+    semantic::{
+        semantic_validation, semantic_validation_report, ConflictReason, SemanticValidationFailure, ValidationContext,
+    },
+    signature::{Ed25519Signature, Signature},
+    unlock::{SignatureUnlock, Unlock, Unlocks},
+};
+use primitive_types::U256;
+
+const TRANSACTION_ID: &str = "0x52fdfc072182654f163f5f0f9a621d729566c74d10037c4d7bbb0407d1e2c649";
+const MILESTONE_TIMESTAMP: u32 = 200;
+
+struct Transaction {
+    transaction_id: TransactionId,
+    essence: RegularTransactionEssence,
+    inputs: Vec<(OutputId, Output)>,
+    unlocks: Unlocks,
+}
+
+impl Transaction {
+    fn new(input: Output, outputs: Vec<Output>, key: &SecretKey, sign: bool) -> Self {
+        let protocol_parameters = protocol_parameters();
+        let output_id = OutputId::new(TransactionId::new(prefix_hex::decode(TRANSACTION_ID).unwrap()), 0).unwrap();
+        let essence = RegularTransactionEssence::builder(
+            protocol_parameters.network_id(),
+            InputsCommitment::new([&input].into_iter()),
+        )
+        .with_inputs(vec![Input::Utxo(UtxoInput::from(output_id))])
+        .with_outputs(outputs)
+        .finish(&protocol_parameters)
+        .unwrap();
+        let essence_hash = TransactionEssence::Regular(essence.clone()).hash();
+        let signature = key.sign(if sign { &essence_hash } else { b"not the essence" });
+        let unlocks = Unlocks::new(vec![Unlock::Signature(SignatureUnlock::from(Signature::Ed25519(
+            Ed25519Signature::new(key.public_key().to_bytes(), signature.to_bytes()),
+        )))])
+        .unwrap();
+
+        Self {
+            transaction_id: TransactionId::new([1; 32]),
+            essence,
+            inputs: vec![(output_id, input)],
+            unlocks,
+        }
+    }
+
+    fn validate(&self) -> (ConflictReason, Vec<SemanticValidationFailure>) {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|(output_id, output)| (*output_id, output))
+            .collect::<Vec<_>>();
+        let context = || {
+            ValidationContext::new(
+                &self.transaction_id,
+                &self.essence,
+                self.inputs.iter().map(|(output_id, output)| (output_id, output)),
+                &self.unlocks,
+                MILESTONE_TIMESTAMP,
+            )
+        };
+
+        let conflict = semantic_validation(context(), &inputs, &self.unlocks).unwrap();
+        let report = semantic_validation_report(context(), &inputs, &self.unlocks).unwrap();
+        assert_eq!(report.conflict_reason(), conflict);
+        assert_eq!(report.is_valid(), conflict == ConflictReason::None);
+
+        (conflict, report.failures().to_vec())
+    }
+}
+
+fn address(key: &SecretKey) -> Address {
+    Address::Ed25519(Ed25519Address::new(
+        Blake2b256::digest(key.public_key().to_bytes()).into(),
+    ))
+}
+
+fn basic_output(amount: u64, unlock_conditions: Vec<UnlockCondition>, features: Vec<Feature>) -> Output {
+    BasicOutput::build_with_amount(amount)
+        .unwrap()
+        .with_unlock_conditions(unlock_conditions)
+        .with_features(features)
+        .finish_output(protocol_parameters().token_supply())
+        .unwrap()
+}
+
+#[test]
+fn valid_transaction() {
+    let key = SecretKey::from_bytes([1; 32]);
+    let address = UnlockCondition::Address(AddressUnlockCondition::new(address(&key)));
+    let transaction = Transaction::new(
+        basic_output(1_000_000, vec![address.clone()], Vec::new()),
+        vec![basic_output(1_000_000, vec![address], Vec::new())],
+        &key,
+        true,
+    );
+
+    assert_eq!(transaction.validate(), (ConflictReason::None, Vec::new()));
+}
+
+#[test]
+fn report_all_failures() {
+    let key = SecretKey::from_bytes([1; 32]);
+    let sender = address(&SecretKey::from_bytes([2; 32]));
+    let token_id = TokenId::new([3; 38]);
+    let address = UnlockCondition::Address(AddressUnlockCondition::new(address(&key)));
+    let output = BasicOutput::build_with_amount(2_000_000)
+        .unwrap()
+        .add_unlock_condition(address.clone())
+        .add_feature(Feature::Sender(SenderFeature::new(sender)))
+        .add_native_token(NativeToken::new(token_id, U256::from(10)).unwrap())
+        .finish_output(protocol_parameters().token_supply())
+        .unwrap();
+    let transaction = Transaction::new(
+        basic_output(
+            1_000_000,
+            vec![
+                address,
+                UnlockCondition::Timelock(TimelockUnlockCondition::new(300).unwrap()),
+            ],
+            Vec::new(),
+        ),
+        vec![output],
+        &key,
+        false,
+    );
+
+    assert_eq!(
+        transaction.validate(),
+        (
+            ConflictReason::InvalidSignature,
+            vec![
+                SemanticValidationFailure::InvalidUnlock {
+                    index: 0,
+                    reason: ConflictReason::InvalidSignature
+                },
+                SemanticValidationFailure::TimelockNotExpired {
+                    input_index: 0,
+                    timelock: 300,
+                    milestone_timestamp: MILESTONE_TIMESTAMP
+                },
+                SemanticValidationFailure::UnverifiedSender {
+                    output_index: 0,
+                    sender
+                },
+                SemanticValidationFailure::CreatedConsumedAmountMismatch {
+                    consumed: 1_000_000,
+                    created: 2_000_000
+                },
+                SemanticValidationFailure::UnbalancedNativeToken {
+                    token_id,
+                    consumed: U256::zero(),
+                    created: U256::from(10)
+                },
+            ]
+        )
+    );
+}